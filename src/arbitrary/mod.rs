@@ -0,0 +1,180 @@
+//! [Proptest](https://docs.rs/proptest) strategies generating random valid polygons and multi-polygons, to property-test buffering.
+//!
+//! This module is available with the `proptest-support` feature. The orphan rules forbid implementing
+//! [`Arbitrary`](proptest::arbitrary::Arbitrary) for the types of `geo` in this crate, so the strategies are provided as functions.
+//!
+//! Every generated polygon is valid: its rings are simple, its exterior is counter-clockwise, its interiors are clockwise, lie inside
+//! the exterior and do not touch each other, and the members of a generated multi-polygon are disjoint. Each ring is star-shaped
+//! (every vertex is visible from a common center), which still covers convex and concave rings of up to 16 vertices.
+//!
+//! # Example
+//!
+//! ```
+//! use geo_buf::arbitrary::polygon_with_holes;
+//! use geo_buf::buffer_polygon;
+//! use geo::Area;
+//! use proptest::test_runner::{Config, TestRunner};
+//!
+//! let mut runner = TestRunner::new(Config {
+//!     failure_persistence: None,
+//!     ..Config::with_cases(32)
+//! });
+//! runner
+//!     .run(&polygon_with_holes(), |p| {
+//!         // Inflating never loses area, and deflating never gains any.
+//!         let area = p.unsigned_area();
+//!         assert!(buffer_polygon(&p, 0.1).unsigned_area() >= area - 1e-6);
+//!         assert!(buffer_polygon(&p, -0.1).unsigned_area() <= area + 1e-6);
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::f64::consts::TAU;
+
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// The most holes of a polygon generated by [`polygon_with_holes`].
+const MAX_HOLES: usize = 3;
+/// The most members of a multi-polygon generated by [`multi_polygon`].
+const MAX_MEMBERS: usize = 4;
+
+/// Returns a strategy for counter-clockwise star-shaped rings around the origin, with at least `min_vertices` vertices
+/// whose distances from the origin lie between `min_radius` and 1.
+///
+/// Each vertex lies in its own sector of the full angle, so the angles increase strictly and the ring is simple.
+fn star(min_vertices: usize, min_radius: f64) -> impl Strategy<Value = LineString> {
+    (min_vertices..=16)
+        .prop_flat_map(move |n| (vec(0.1..0.9, n), vec(min_radius..1., n)))
+        .prop_map(|(offsets, radii)| {
+            let n = offsets.len() as f64;
+            let mut ls: LineString = offsets
+                .iter()
+                .zip(&radii)
+                .enumerate()
+                .map(|(i, (offset, radius))| {
+                    let angle: f64 = TAU * (i as f64 + offset) / n;
+                    Coord {
+                        x: radius * angle.cos(),
+                        y: radius * angle.sin(),
+                    }
+                })
+                .collect();
+            ls.close();
+            ls
+        })
+}
+
+/// Returns a strategy for a center and a scale, to move the unit shapes to random places.
+fn placement() -> impl Strategy<Value = (Coord, f64)> {
+    (-100f64..100., -100f64..100., 1f64..100.).prop_map(|(x, y, scale)| (Coord { x, y }, scale))
+}
+
+fn place_ring(ls: &LineString, center: Coord, scale: f64) -> LineString {
+    ls.coords().map(|&c| center + c * scale).collect()
+}
+
+fn place_polygon(p: &Polygon, center: Coord, scale: f64) -> Polygon {
+    Polygon::new(
+        place_ring(p.exterior(), center, scale),
+        p.interiors()
+            .iter()
+            .map(|ls| place_ring(ls, center, scale))
+            .collect(),
+    )
+}
+
+/// Returns a strategy for polygons with holes within the unit disk around the origin.
+///
+/// Consecutive vertices of an exterior of at least 8 vertices are less than 0.9 * π / 2 apart in angle, so its edges stay outside
+/// of the disk of radius 0.7 * cos(0.9 * π / 4) > 0.5. The holes are kept in disjoint disks of radius 0.15
+/// centered 0.35 away from the origin.
+fn unit_polygon_with_holes() -> impl Strategy<Value = Polygon> {
+    (star(8, 0.7), vec(star(3, 0.2), 1..=MAX_HOLES)).prop_map(|(exterior, holes)| {
+        let count = holes.len() as f64;
+        let interiors = holes
+            .iter()
+            .enumerate()
+            .map(|(k, hole)| {
+                let angle = TAU * k as f64 / count;
+                let center = Coord {
+                    x: 0.35 * angle.cos(),
+                    y: 0.35 * angle.sin(),
+                };
+                let mut ls = place_ring(hole, center, 0.15);
+                ls.0.reverse();
+                ls
+            })
+            .collect();
+        Polygon::new(exterior, interiors)
+    })
+}
+
+/// This function returns a proptest strategy generating random valid simple polygons without holes.
+///
+/// The polygons have from 3 to 16 vertices, a counter-clockwise exterior, and lie within a few hundred units of the origin.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::arbitrary::simple_polygon;
+/// use geo::Winding;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let p = simple_polygon().new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!(p.interiors().is_empty());
+/// assert!(p.exterior().is_ccw());
+/// ```
+pub fn simple_polygon() -> impl Strategy<Value = Polygon> {
+    (star(3, 0.2), placement()).prop_map(|(exterior, (center, scale))| {
+        Polygon::new(place_ring(&exterior, center, scale), vec![])
+    })
+}
+
+/// This function returns a proptest strategy generating random valid polygons with one to three holes.
+///
+/// The exteriors are counter-clockwise and the interiors are clockwise, as in the results of [`buffer_polygon`](crate::buffer_polygon).
+pub fn polygon_with_holes() -> impl Strategy<Value = Polygon> {
+    (unit_polygon_with_holes(), placement())
+        .prop_map(|(p, (center, scale))| place_polygon(&p, center, scale))
+}
+
+/// This function returns a proptest strategy generating random valid multi-polygons of one to four disjoint members,
+/// each of which may or may not have holes.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::arbitrary::multi_polygon;
+/// use geo::Intersects;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let mp = multi_polygon().new_tree(&mut TestRunner::default()).unwrap().current();
+/// for (i, p) in mp.0.iter().enumerate() {
+///     assert!(mp.0[i + 1..].iter().all(|q| !p.intersects(q)));
+/// }
+/// ```
+pub fn multi_polygon() -> impl Strategy<Value = MultiPolygon> {
+    let member = prop_oneof![
+        star(3, 0.2).prop_map(|exterior| Polygon::new(exterior, vec![])),
+        unit_polygon_with_holes(),
+    ];
+    (vec(member, 1..=MAX_MEMBERS), placement()).prop_map(|(members, (center, scale))| {
+        // The members lie within unit disks spaced 2.5 apart along the x-axis.
+        members
+            .iter()
+            .enumerate()
+            .map(|(k, p)| {
+                let offset = Coord {
+                    x: 2.5 * k as f64,
+                    y: 0.,
+                };
+                place_polygon(p, center + offset * scale, scale)
+            })
+            .collect()
+    })
+}
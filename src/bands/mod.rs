@@ -0,0 +1,147 @@
+use geo::winding_order::WindingOrder;
+use geo::{Area, Contains};
+use geo_types::{LineString, MultiPolygon, Polygon};
+use i_overlay::core::overlay_rule::OverlayRule;
+
+use crate::skeleton::{Side, Skeleton};
+use crate::{postprocess, repair, BufferOptions};
+
+/// The buffers of a polygon by several distances, sharing the outward skeleton between all inflations and the inward skeleton
+/// between all deflations.
+struct Offsets<'a> {
+    input: &'a Polygon,
+    oriented: MultiPolygon,
+    outward: Option<Skeleton>,
+    inward: Option<Skeleton>,
+}
+
+impl<'a> Offsets<'a> {
+    fn new(input: &'a Polygon) -> Self {
+        let mut oriented = MultiPolygon::new(vec![input.clone()]);
+        postprocess::orient(&mut oriented, WindingOrder::CounterClockwise);
+        Self {
+            input,
+            oriented,
+            outward: None,
+            inward: None,
+        }
+    }
+
+    /// Returns the buffer of the polygon by `distance`, building the skeleton on its side if it is the first one.
+    fn get(&mut self, distance: f64) -> MultiPolygon {
+        if distance == 0. {
+            return self.oriented.clone();
+        }
+        let input = self.input;
+        let skel = if distance > 0. {
+            self.outward
+                .get_or_insert_with(|| Skeleton::skeleton_of_polygon(input, Side::Exterior))
+        } else {
+            self.inward
+                .get_or_insert_with(|| Skeleton::skeleton_of_polygon(input, Side::Interior))
+        };
+        skel.buffer(distance.abs(), &BufferOptions::new())
+    }
+}
+
+/// Returns the band between the buffer of `input` by each of `distances` and the buffer by the next of `distances` towards zero
+/// (or `input` itself), labeled with the distance.
+///
+/// All inflations share the outward skeleton and all deflations share the inward skeleton of `input`.
+pub(crate) fn buffer_rings(input: &Polygon, distances: &[f64]) -> Vec<(f64, MultiPolygon)> {
+    let mut offsets = Offsets::new(input);
+    distances
+        .iter()
+        .map(|&distance| {
+            // The closest of the other distances between zero and this one, if any.
+            let previous = distances
+                .iter()
+                .copied()
+                .filter(|&d| d.signum() == distance.signum() && d.abs() < distance.abs())
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+                .unwrap_or(0.);
+            let (outer, inner) = if distance > 0. {
+                (offsets.get(distance), offsets.get(previous))
+            } else {
+                (offsets.get(previous), offsets.get(distance))
+            };
+            (
+                distance,
+                repair::overlay(&outer, &inner, OverlayRule::Difference),
+            )
+        })
+        .collect()
+}
+
+/// A ring of the buffer of a polygon by one of several distances, in the tree of the rings of all these buffers nested by
+/// containment, see [`buffer_ring_tree`](crate::buffer_ring_tree).
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffsetRing {
+    /// The distance of the buffer this ring belongs to.
+    pub distance: f64,
+    /// The ring, counter-clockwise for an exterior and clockwise for an interior of the buffer.
+    pub ring: LineString,
+    /// Whether the ring is an interior of the buffer.
+    pub hole: bool,
+    /// The index of the smallest ring containing this ring, or `None` if no ring contains it.
+    pub parent: Option<usize>,
+    /// The indices of the rings whose smallest containing ring is this ring.
+    pub children: Vec<usize>,
+    /// The number of rings containing this ring, so that filling the rings of odd depth with the even-odd rule draws the bands.
+    pub depth: usize,
+}
+
+/// Returns the rings of the buffers of `input` by each of `distances`, nested by containment, each ring before the rings it contains.
+///
+/// The rings of the buffers by different distances never cross, so each ring lies in the smallest ring containing any of its points,
+/// which is the last containing ring before it once the rings are sorted by decreasing area.
+pub(crate) fn buffer_ring_tree(input: &Polygon, distances: &[f64]) -> Vec<OffsetRing> {
+    let mut offsets = Offsets::new(input);
+    let mut rings: Vec<(f64, Polygon, bool)> = Vec::new();
+    for &distance in distances {
+        for p in offsets.get(distance) {
+            rings.push((distance, Polygon::new(p.exterior().clone(), vec![]), false));
+            rings.extend(
+                p.interiors()
+                    .iter()
+                    .map(|ls| (distance, Polygon::new(ls.clone(), vec![]), true)),
+            );
+        }
+    }
+    rings.sort_by(|a, b| b.1.unsigned_area().total_cmp(&a.1.unsigned_area()));
+    let mut tree: Vec<OffsetRing> = Vec::with_capacity(rings.len());
+    for (i, (distance, polygon, hole)) in rings.iter().enumerate() {
+        let parent = (0..i)
+            .rev()
+            .find(|&j| rings[j].1.contains(polygon.exterior()));
+        let depth = parent.map_or(0, |j| tree[j].depth + 1);
+        if let Some(j) = parent {
+            tree[j].children.push(i);
+        }
+        tree.push(OffsetRing {
+            distance: *distance,
+            ring: polygon.exterior().clone(),
+            hole: *hole,
+            parent,
+            children: Vec::new(),
+            depth,
+        });
+    }
+    tree
+}
+
+/// Returns the band of width `width` centered on the boundary of `input`, i.e. the buffer of `input` by `width / 2`
+/// without the buffer of `input` by `-width / 2`, both built with `options`.
+pub(crate) fn stroke_boundary(
+    options: &BufferOptions,
+    input: &Polygon,
+    width: f64,
+) -> MultiPolygon {
+    let half = width.abs() / 2.;
+    let input = std::slice::from_ref(input);
+    repair::overlay(
+        &options.buffer(input, half),
+        &options.buffer(input, -half),
+        OverlayRule::Difference,
+    )
+}
@@ -0,0 +1,281 @@
+//! The `geo-buf` command: buffers the polygons of a GeoJSON or WKT input, or builds their straight skeletons, and writes GeoJSON.
+//! Requires the `cli` feature.
+//!
+//! ```text
+//! geo-buf buffer --distance -2.5 --join round parcels.geojson > setbacks.geojson
+//! echo 'POLYGON((0 0,10 0,10 10,0 10,0 0))' | geo-buf skeleton
+//! ```
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use geo_buf::{
+    skeleton_of_multi_polygon, BufferOptions, BufferParams, EndCapStyle, JoinStyle, Side,
+};
+use geo_types::{Geometry, MultiPolygon};
+use geojson::{GeoJson, Value};
+use wkt::TryFromWkt;
+
+const USAGE: &str = "\
+Usage: geo-buf <COMMAND> [OPTIONS] [FILE]
+
+Reads polygons and multi-polygons as GeoJSON or WKT from FILE, or from the standard input if FILE is missing or `-`,
+and writes the result as GeoJSON to the standard output. The properties of GeoJSON features are kept.
+
+Commands:
+  buffer    Buffers each geometry by the distance
+  skeleton  Writes the straight skeleton of each geometry as a MultiLineString
+
+Options:
+  -d, --distance <DISTANCE>   The distance to buffer by: positive to inflate, negative to deflate (required by `buffer`)
+  -j, --join <JOIN>           The corners around the convex vertices: miter (the default), round or bevel
+      --mitre-limit <LIMIT>   Cuts off the miters reaching farther than LIMIT times the distance from their vertex
+  -u, --units <UNIT>          The unit of the distance: m (the default), km, cm, mm, ft, yd, mi or nmi
+      --coord-units <UNIT>    The unit of the coordinates, which the distance is converted to (m by default)
+  -f, --format <FORMAT>       The format of the input: geojson or wkt (detected from the input by default)
+      --outward               Builds the skeleton outside of the polygons instead of inside of them
+  -h, --help                  Prints this help
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Buffer,
+    Skeleton,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Join {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    GeoJson,
+    Wkt,
+}
+
+struct Args {
+    command: Command,
+    distance: Option<f64>,
+    join: Join,
+    mitre_limit: Option<f64>,
+    units: f64,
+    coord_units: f64,
+    format: Option<Format>,
+    side: Side,
+    file: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("geo-buf: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("geo-buf: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses the arguments after the name of the program, or returns `None` if they ask for the help.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let command = match args.next().as_deref() {
+        Some("buffer") => Command::Buffer,
+        Some("skeleton") => Command::Skeleton,
+        Some("-h" | "--help") => return Ok(None),
+        Some(other) => return Err(format!("unknown command `{other}`")),
+        None => return Err("missing command".to_string()),
+    };
+    let mut res = Args {
+        command,
+        distance: None,
+        join: Join::Miter,
+        mitre_limit: None,
+        units: 1.,
+        coord_units: 1.,
+        format: None,
+        side: Side::Interior,
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-d" | "--distance" => res.distance = Some(parse_number(&value()?)?),
+            "-j" | "--join" => {
+                res.join = match value()?.as_str() {
+                    "miter" | "mitre" => Join::Miter,
+                    "round" => Join::Round,
+                    "bevel" => Join::Bevel,
+                    other => return Err(format!("unknown join `{other}`")),
+                }
+            }
+            "--mitre-limit" | "--miter-limit" => res.mitre_limit = Some(parse_number(&value()?)?),
+            "-u" | "--units" => res.units = parse_unit(&value()?)?,
+            "--coord-units" => res.coord_units = parse_unit(&value()?)?,
+            "-f" | "--format" => {
+                res.format = Some(match value()?.as_str() {
+                    "geojson" | "json" => Format::GeoJson,
+                    "wkt" => Format::Wkt,
+                    other => return Err(format!("unknown format `{other}`")),
+                })
+            }
+            "--outward" => res.side = Side::Exterior,
+            "-" => res.file = None,
+            flag if flag.starts_with('-') && parse_number(flag).is_err() => {
+                return Err(format!("unknown option `{flag}`"))
+            }
+            file if res.file.is_none() => res.file = Some(file.to_string()),
+            extra => return Err(format!("unexpected argument `{extra}`")),
+        }
+    }
+    if res.command == Command::Buffer && res.distance.is_none() {
+        return Err("`buffer` needs a `--distance`".to_string());
+    }
+    Ok(Some(res))
+}
+
+fn parse_number(s: &str) -> Result<f64, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|x| x.is_finite())
+        .ok_or_else(|| format!("invalid number `{s}`"))
+}
+
+/// Returns the length of `unit` in meters.
+fn parse_unit(unit: &str) -> Result<f64, String> {
+    Ok(match unit {
+        "m" => 1.,
+        "km" => 1000.,
+        "cm" => 0.01,
+        "mm" => 0.001,
+        "ft" => 0.3048,
+        "yd" => 0.9144,
+        "mi" => 1609.344,
+        "nmi" => 1852.,
+        other => return Err(format!("unknown unit `{other}`")),
+    })
+}
+
+/// Reads the input, applies the command to each of its geometries, and returns the result as GeoJSON.
+fn run(args: &Args) -> Result<String, String> {
+    let input = match args.file.as_deref() {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?,
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("standard input: {e}"))?;
+            input
+        }
+    };
+    let format = args
+        .format
+        .unwrap_or(if input.trim_start().starts_with('{') {
+            Format::GeoJson
+        } else {
+            Format::Wkt
+        });
+    match format {
+        Format::GeoJson => {
+            let mut geojson: GeoJson =
+                input.parse().map_err(|e| format!("invalid GeoJSON: {e}"))?;
+            match &mut geojson {
+                GeoJson::Geometry(geometry) => apply_to_geometry(args, geometry)?,
+                GeoJson::Feature(feature) => apply_to_feature(args, feature)?,
+                GeoJson::FeatureCollection(fc) => {
+                    fc.bbox = None;
+                    for feature in &mut fc.features {
+                        apply_to_feature(args, feature)?;
+                    }
+                }
+            }
+            Ok(geojson.to_string())
+        }
+        Format::Wkt => {
+            let geometry = Geometry::<f64>::try_from_wkt_str(&input)
+                .map_err(|e| format!("invalid WKT: {e}"))?;
+            let input_multi_polygon = match geometry {
+                Geometry::Polygon(p) => MultiPolygon::new(vec![p]),
+                Geometry::MultiPolygon(mp) => mp,
+                Geometry::Rect(r) => MultiPolygon::new(vec![r.to_polygon()]),
+                Geometry::Triangle(t) => MultiPolygon::new(vec![t.to_polygon()]),
+                _ => return Err("expected a polygon or a multi-polygon".to_string()),
+            };
+            Ok(geojson::Geometry::new(apply(args, &input_multi_polygon)).to_string())
+        }
+    }
+}
+
+fn apply_to_feature(args: &Args, feature: &mut geojson::Feature) -> Result<(), String> {
+    feature.bbox = None;
+    match &mut feature.geometry {
+        Some(geometry) => apply_to_geometry(args, geometry),
+        None => Ok(()),
+    }
+}
+
+fn apply_to_geometry(args: &Args, geometry: &mut geojson::Geometry) -> Result<(), String> {
+    let input_multi_polygon = match &geometry.value {
+        value @ Value::Polygon(_) => {
+            MultiPolygon::new(vec![value.try_into().map_err(|e| format!("{e}"))?])
+        }
+        value @ Value::MultiPolygon(_) => value.try_into().map_err(|e| format!("{e}"))?,
+        value => {
+            return Err(format!(
+                "expected a Polygon or a MultiPolygon, found a {}",
+                value.type_name()
+            ))
+        }
+    };
+    geometry.bbox = None;
+    geometry.value = apply(args, &input_multi_polygon);
+    Ok(())
+}
+
+/// Applies the command to `input`.
+fn apply(args: &Args, input: &MultiPolygon) -> Value {
+    match args.command {
+        Command::Buffer => {
+            let distance = args.distance.unwrap_or(0.) * args.units / args.coord_units;
+            let options = match (args.join, args.mitre_limit) {
+                (Join::Miter, None) => BufferOptions::new(),
+                (Join::Miter, Some(limit)) => {
+                    BufferParams::geos_like(8, EndCapStyle::Round, JoinStyle::Mitre, limit)
+                        .to_options()
+                }
+                (Join::Round, _) => BufferOptions::new().rounded(true),
+                (Join::Bevel, _) => {
+                    BufferParams::geos_like(8, EndCapStyle::Round, JoinStyle::Bevel, 0.)
+                        .to_options()
+                }
+            };
+            Value::from(&geo_buf::buffer_multi_polygon_with(
+                input, distance, &options,
+            ))
+        }
+        Command::Skeleton => {
+            skeleton_of_multi_polygon(input, args.side)
+                .to_geojson()
+                .value
+        }
+    }
+}
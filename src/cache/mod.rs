@@ -0,0 +1,189 @@
+//! A cache of straight skeletons keyed by their input, behind the `cache` feature.
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use geo_types::{Coord, MultiPolygon, Polygon};
+use lru::LruCache;
+
+use crate::{buffer_multi_polygon, BufferOptions, Error, Side, Skeleton, SkeletonBuilder};
+
+/// The input of a skeleton as a cache key: the bits of the coordinates of its rings, and the side of the skeleton.
+///
+/// Two inputs are the same key if their rings have the same coordinates in the same order, `0.` and `-0.` being the same.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct InputKey {
+    side: Side,
+    /// The number of rings of each polygon and the number of coordinates of each ring, so that differently split
+    /// coordinates make different keys.
+    shape: Vec<usize>,
+    coords: Vec<(u64, u64)>,
+}
+
+impl InputKey {
+    fn new(input: &MultiPolygon, side: Side) -> Self {
+        let mut shape = Vec::new();
+        let mut coords = Vec::new();
+        for p in input {
+            shape.push(1 + p.interiors().len());
+            for ring in std::iter::once(p.exterior()).chain(p.interiors()) {
+                shape.push(ring.0.len());
+                coords.extend(
+                    ring.0
+                        .iter()
+                        .map(|c| ((c.x + 0.).to_bits(), (c.y + 0.).to_bits())),
+                );
+            }
+        }
+        Self {
+            side,
+            shape,
+            coords,
+        }
+    }
+}
+
+/// A least-recently-used cache of straight skeletons, keyed by the coordinates of their input and their side.
+///
+/// Building the straight skeleton is the expensive part of buffering, so a service buffering the same geometries (e.g. admin
+/// boundaries) by different distances saves most of the work by keeping their skeletons. Requires the `cache` feature.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, BufferCache};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let mut cache = BufferCache::new(16);
+/// for distance in [-0.2, -0.45, 0.5, 1.] {
+///     assert_eq!(cache.buffer_polygon(&p1, distance), buffer_polygon(&p1, distance));
+/// }
+///
+/// // One inward and one outward skeleton were built.
+/// assert_eq!(cache.len(), 2);
+/// assert_eq!((cache.hits(), cache.misses()), (2, 2));
+///
+/// // Far from the origin, as in Web-Mercator meters.
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(1e7, 5e6), (1e7 + 4., 5e6), (1e7 + 4., 5e6 + 4.), (1e7 + 2., 5e6 + 1.), (1e7, 5e6 + 4.)]), vec![],
+/// );
+/// assert_eq!(cache.buffer_polygon(&p2, -0.45), buffer_polygon(&p2, -0.45));
+/// ```
+#[derive(Debug)]
+pub struct BufferCache {
+    /// The skeletons of the inputs moved near the origin as [`buffer_multi_polygon`] moves them, with the translation back.
+    skeletons: LruCache<InputKey, (Arc<Skeleton>, Coord)>,
+    /// The builder of the skeletons missing from the cache.
+    builder: SkeletonBuilder,
+    hits: u64,
+    misses: u64,
+}
+
+impl BufferCache {
+    /// Creates an empty cache keeping at most `capacity` skeletons.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        let capacity =
+            NonZeroUsize::new(capacity).expect("the capacity of the cache must be positive");
+        Self {
+            skeletons: LruCache::new(capacity),
+            builder: SkeletonBuilder::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the straight skeleton of `input` on `side`, building it if it is not cached, and evicting the least recently
+    /// used skeleton if the cache is full.
+    ///
+    /// An input far from the origin is skeletonized near the origin as [`buffer_multi_polygon`] does, and the skeleton returned
+    /// is translated back to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NumericFailure`] if the construction of the straight skeleton fails, see
+    /// [`try_skeleton_of_multi_polygon`](crate::try_skeleton_of_multi_polygon).
+    /// Failures are not cached.
+    pub fn skeleton(&mut self, input: &MultiPolygon, side: Side) -> Result<Arc<Skeleton>, Error> {
+        let (skel, shift) = self.entry(input, side)?;
+        if shift == Coord::zero() {
+            return Ok(skel);
+        }
+        Ok(Arc::new(skel.translated(shift)))
+    }
+
+    /// Returns the cached skeleton of `input` on `side` and its translation back to `input`, building them if they are not cached.
+    fn entry(&mut self, input: &MultiPolygon, side: Side) -> Result<(Arc<Skeleton>, Coord), Error> {
+        let key = InputKey::new(input, side);
+        if let Some((skel, shift)) = self.skeletons.get(&key) {
+            self.hits += 1;
+            return Ok((Arc::clone(skel), *shift));
+        }
+        self.misses += 1;
+        // Only the sign of the distance matters to the default options.
+        let distance = match side {
+            Side::Interior => -1.,
+            Side::Exterior => 1.,
+        };
+        let (skel, shift) =
+            match BufferOptions::new().try_skeleton(&mut self.builder, &input.0, distance, None)? {
+                Some((skel, _, shift)) => (skel, shift),
+                None => (
+                    self.builder.try_skeleton_of_multi_polygon(input, side)?,
+                    Coord::zero(),
+                ),
+            };
+        let skel = Arc::new(skel);
+        self.skeletons.put(key, (Arc::clone(&skel), shift));
+        Ok((skel, shift))
+    }
+
+    /// Returns the buffer of `input` by `distance` as [`buffer_multi_polygon`] does, from the cached skeleton of `input`.
+    ///
+    /// If the skeleton cannot be built, the buffer is computed as [`buffer_multi_polygon`] does, without caching anything.
+    pub fn buffer_multi_polygon(&mut self, input: &MultiPolygon, distance: f64) -> MultiPolygon {
+        match self.entry(input, Side::of_distance(distance)) {
+            Ok((skel, shift)) => {
+                BufferOptions::new().buffer_shifted_skeleton(&skel, distance.abs(), shift)
+            }
+            Err(_) => buffer_multi_polygon(input, distance),
+        }
+    }
+
+    /// Returns the buffer of `input` by `distance` as [`buffer_polygon`](crate::buffer_polygon) does, from the cached skeleton
+    /// of `input`. See [`BufferCache::buffer_multi_polygon`].
+    pub fn buffer_polygon(&mut self, input: &Polygon, distance: f64) -> MultiPolygon {
+        self.buffer_multi_polygon(&MultiPolygon::new(vec![input.clone()]), distance)
+    }
+
+    /// Returns the number of cached skeletons.
+    pub fn len(&self) -> usize {
+        self.skeletons.len()
+    }
+
+    /// Returns whether no skeleton is cached.
+    pub fn is_empty(&self) -> bool {
+        self.skeletons.is_empty()
+    }
+
+    /// Returns the number of skeletons found in the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of skeletons which had to be built.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Removes every skeleton from the cache, keeping the counts of hits and misses.
+    pub fn clear(&mut self) {
+        self.skeletons.clear();
+    }
+}
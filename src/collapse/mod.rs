@@ -0,0 +1,175 @@
+use geo::{Contains, Distance, Euclidean, InteriorPoint};
+use geo_types::{Geometry, MultiLineString, MultiPolygon, Point, Polygon};
+
+use crate::postprocess;
+use crate::skeleton::{Side, Skeleton};
+use crate::BufferOptions;
+use geo_types::Coord;
+
+/// A polygon of the input which vanished entirely when deflating, see
+/// [`buffer_multi_polygon_with_collapses`](crate::buffer_multi_polygon_with_collapses).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Collapse {
+    /// The index of the polygon in the input multi-polygon.
+    pub index: usize,
+    /// The deflation distance at which the last part of the polygon vanished. Every deflation by at least this distance removes the polygon.
+    pub distance: f64,
+    /// The point where the last part of the polygon vanished.
+    pub location: Coord,
+}
+
+/// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, and lists the polygons which vanished entirely.
+///
+/// When deflating, the polygons do not interact, so each of them gets its own skeleton, which tells when and where it vanishes.
+pub(crate) fn buffer_with_collapses(
+    options: &BufferOptions,
+    input: &[Polygon],
+    distance: f64,
+) -> (MultiPolygon, Vec<Collapse>) {
+    if distance >= 0. {
+        return (options.buffer(input, distance), Vec::new());
+    }
+    let offset_distance = -distance;
+    let mut builder = options.skeleton_builder();
+    let mut res = MultiPolygon::new(vec![]);
+    let mut collapses = Vec::new();
+    for (index, p) in input.iter().enumerate() {
+        let skel = builder.skeleton_of_polygon(p, Side::Interior);
+        match skel.last_event() {
+            Some((time, location)) if time <= offset_distance => collapses.push(Collapse {
+                index,
+                distance: time,
+                location,
+            }),
+            _ => res
+                .0
+                .extend(options.buffer_skeleton(&skel, offset_distance)),
+        }
+    }
+    postprocess::canonicalize(&mut res);
+    (res, collapses)
+}
+
+/// Buffers each polygon of `input` by `distance` on its own as [`BufferOptions::buffer`] does, and replaces each polygon which vanishes
+/// entirely with its spine, see [`Skeleton::spine`], or with the point where it vanished if its spine is empty.
+pub(crate) fn buffer_keeping_collapsed(
+    options: &BufferOptions,
+    input: &[Polygon],
+    distance: f64,
+) -> Vec<Geometry> {
+    let mut builder = options.skeleton_builder();
+    input
+        .iter()
+        .map(|p| {
+            if distance >= 0. {
+                return Geometry::MultiPolygon(options.buffer_with_builder(
+                    &mut builder,
+                    std::slice::from_ref(p),
+                    distance,
+                ));
+            }
+            let skel = builder.skeleton_of_polygon(p, Side::Interior);
+            match skel.last_event() {
+                Some((time, location)) if time <= -distance => {
+                    let spine = skel.spine();
+                    if spine.is_empty() {
+                        Geometry::Point(Point(location))
+                    } else {
+                        Geometry::MultiLineString(MultiLineString::new(spine))
+                    }
+                }
+                _ => Geometry::MultiPolygon(options.buffer_skeleton(&skel, -distance)),
+            }
+        })
+        .collect()
+}
+
+/// A connected part of a polygon while it is deflated, see [`shrink_until_collapse`](crate::shrink_until_collapse).
+///
+/// Deflating a polygon further and further splits it into parts, which split again, until each of them vanishes.
+/// The parts form a tree, whose root is the polygon itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollapseComponent {
+    /// The index of the part this part split off from, or `None` for the polygon itself.
+    pub parent: Option<usize>,
+    /// The indices of the parts this part split into, or nothing if it vanished.
+    pub children: Vec<usize>,
+    /// The deflation distance at which this part split off from its parent, or `0` for the polygon itself.
+    pub birth: f64,
+    /// The deflation distance at which this part split or vanished.
+    pub death: f64,
+    /// The point where this part split or vanished.
+    pub location: Coord,
+}
+
+/// Returns the parts of `input` while it is deflated until it vanishes, in the order they split off, starting with `input` itself.
+///
+/// The parts are followed from one change of the wavefront of the inward skeleton to the next: each part of the wavefront lies in a single part
+/// of the wavefront before, which split if it holds several of them and vanished if it holds none.
+pub(crate) fn shrink_until_collapse(input: &Polygon) -> Vec<CollapseComponent> {
+    let skel = Skeleton::skeleton_of_polygon(input, Side::Interior);
+    let changes = skel.topology_changes();
+    if changes.is_empty() {
+        return Vec::new();
+    }
+    let mut components = vec![CollapseComponent {
+        parent: None,
+        children: Vec::new(),
+        birth: 0.,
+        death: f64::INFINITY,
+        location: Coord::zero(),
+    }];
+    // The parts of the current wavefront and their components.
+    let mut alive = vec![(0, input.clone())];
+    for (time, locations, wavefront) in changes {
+        let mut successors = vec![Vec::new(); alive.len()];
+        for part in wavefront {
+            let point = part.interior_point().unwrap_or(Point(part.exterior().0[0]));
+            let parent = alive
+                .iter()
+                .position(|(_, p)| p.contains(&point))
+                .unwrap_or_else(|| closest(alive.iter().map(|(_, p)| p), point));
+            successors[parent].push(part);
+        }
+        let mut next = Vec::new();
+        for ((index, polygon), mut parts) in alive.into_iter().zip(successors) {
+            if parts.len() == 1 {
+                next.push((index, parts.pop().unwrap()));
+                continue;
+            }
+            let location = locations
+                .iter()
+                .copied()
+                .min_by(|a, b| {
+                    Euclidean::distance(&Point(*a), &polygon)
+                        .total_cmp(&Euclidean::distance(&Point(*b), &polygon))
+                })
+                .unwrap_or_default();
+            components[index].death = time;
+            components[index].location = location;
+            for part in parts {
+                let child = components.len();
+                components[index].children.push(child);
+                components.push(CollapseComponent {
+                    parent: Some(index),
+                    children: Vec::new(),
+                    birth: time,
+                    death: f64::INFINITY,
+                    location: Coord::zero(),
+                });
+                next.push((child, part));
+            }
+        }
+        alive = next;
+    }
+    components
+}
+
+/// Returns the index of the polygon of `polygons` closest to `point`.
+fn closest<'a>(polygons: impl Iterator<Item = &'a Polygon>, point: Point) -> usize {
+    polygons
+        .map(|p| Euclidean::distance(&point, p))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i)
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use geo::ConvexHull;
+use geo_types::{Coord, LineString, MultiLineString, MultiPoint, MultiPolygon, Point};
+use i_overlay::core::overlay_rule::OverlayRule;
+
+use crate::postprocess::{self, coord_key};
+use crate::repair;
+
+/// The buffer of a street network split into carriageways and junctions, see
+/// [`buffer_street_network`](crate::buffer_street_network).
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreetNetwork {
+    /// The area of each line string of the input outside of the junctions, in the order of the input.
+    pub carriageways: Vec<MultiPolygon>,
+    /// The areas where three or more segments of the input meet, ordered by their locations.
+    pub junctions: Vec<Junction>,
+}
+
+/// A junction of a [`StreetNetwork`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Junction {
+    /// The vertex of the input where the segments meet.
+    pub location: Coord,
+    /// The part of the buffer around the vertex.
+    pub area: MultiPolygon,
+}
+
+/// Returns the corridor along `input` whose half-width is `half_widths[i]` at the `i`-th vertex of `input` and changes linearly
+/// along each segment, with round ends and joins approximated by `resolution`-gons.
+///
+/// The corridor of a segment is the convex hull of the `resolution`-gons around its ends, so the corridor of `input` is
+/// the union of the corridors of its segments.
+pub(crate) fn tapered(input: &LineString, half_widths: &[f64], resolution: usize) -> MultiPolygon {
+    let discs: Vec<Vec<Point>> = input
+        .coords()
+        .zip(half_widths)
+        .map(|(&c, &r)| ngon(c, r.max(0.), resolution))
+        .collect();
+    let hulls: Vec<_> = match &discs[..] {
+        [] => Vec::new(),
+        [single] => vec![MultiPoint::new(single.clone()).convex_hull()],
+        _ => discs
+            .windows(2)
+            .map(|pair| MultiPoint::new([&pair[0][..], &pair[1][..]].concat()).convex_hull())
+            .collect(),
+    };
+    let mut res = MultiPolygon::new(hulls);
+    repair::repair(&mut res);
+    res
+}
+
+/// Buffers the line strings of `input` by `half_width`, and splits the buffer into junctions and carriageways.
+///
+/// A junction is a vertex with three or more incident segments. Its area is the part of the buffer within `2 * half_width`
+/// of the vertex, which holds the overlap of the incident carriageways unless they meet at an acute angle.
+pub(crate) fn street_network(
+    input: &MultiLineString,
+    half_width: f64,
+    resolution: usize,
+) -> StreetNetwork {
+    let mut degrees: HashMap<_, (Coord, usize)> = HashMap::new();
+    for l in input
+        .iter()
+        .flat_map(|ls| ls.lines())
+        .filter(|l| l.start != l.end)
+    {
+        for c in [l.start, l.end] {
+            degrees.entry(coord_key(c)).or_insert((c, 0)).1 += 1;
+        }
+    }
+    let mut centers: Vec<Coord> = degrees
+        .into_values()
+        .filter(|&(_, degree)| degree >= 3)
+        .map(|(c, _)| c)
+        .collect();
+    centers.sort_by(postprocess::cmp_coords);
+
+    let corridors: Vec<MultiPolygon> = input
+        .iter()
+        .map(|ls| tapered(ls, &vec![half_width; ls.0.len()], resolution))
+        .collect();
+    let discs: Vec<MultiPolygon> = centers
+        .iter()
+        .map(|&c| {
+            let disc = MultiPoint::new(ngon(c, 2. * half_width.max(0.), resolution));
+            MultiPolygon::new(vec![disc.convex_hull()])
+        })
+        .collect();
+    let all_discs = MultiPolygon::new(discs.iter().flatten().cloned().collect());
+    let mut union = MultiPolygon::new(corridors.iter().flatten().cloned().collect());
+    repair::repair(&mut union);
+
+    StreetNetwork {
+        carriageways: corridors
+            .iter()
+            .map(|corridor| repair::overlay(corridor, &all_discs, OverlayRule::Difference))
+            .collect(),
+        junctions: centers
+            .iter()
+            .zip(&discs)
+            .map(|(&c, disc)| Junction {
+                location: c,
+                area: repair::overlay(&union, disc, OverlayRule::Intersect),
+            })
+            .collect(),
+    }
+}
+
+/// Returns the vertices of the regular `resolution`-gon (at least a triangle) around `c` with circumradius `radius`.
+fn ngon(c: Coord, radius: f64, resolution: usize) -> Vec<Point> {
+    let resolution = resolution.max(3);
+    (0..resolution)
+        .map(|i| {
+            let (sin, cos) = (i as f64 * TAU / resolution as f64).sin_cos();
+            Point::new(c.x + radius * cos, c.y + radius * sin)
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+//! Buffering of polygon coverages, i.e. sets of polygons which share their boundaries without overlapping
+//! (as census tracts or parcels do).
+//!
+//! Buffering each polygon of a coverage on its own opens gaps between deflated neighbours and makes inflated neighbours overlap.
+//! Instead, [`buffer_coverage`] buffers the union of the coverage through one straight skeleton and distributes the result among
+//! the polygons, so that the shared edges stay shared.
+
+use std::collections::{HashMap, HashSet};
+
+use geo::winding_order::WindingOrder;
+use geo::{BoundingRect, InteriorPoint};
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+use i_overlay::core::fill_rule::FillRule;
+use i_overlay::float::slice::FloatSlice;
+use rstar::RTree;
+
+use crate::postprocess::{self, coord_key, CoordKey};
+use crate::repair::{self, contains, polygons_of_rings};
+use crate::skeleton::{Side, Skeleton};
+use crate::BufferOptions;
+
+/// The relative distance by which the arcs of the skeleton overshoot the offset, so that they cut it through.
+const OVERSHOOT: f64 = 1e-6;
+
+/// The distance, relative to the size of the result, within which the vertices of the result are snapped, see [`snap`].
+const SNAP_TOLERANCE: f64 = 1e-9;
+
+/// This function returns the buffered (multi-)polygon of each polygon of the given coverage, such that the results form a coverage again.
+///
+/// The outer boundary of the coverage (including the boundaries of the gaps in it) moves by the given distance, as the boundary of
+/// [`buffer_multi_polygon`](crate::buffer_multi_polygon) of the union of the coverage does, while the edges shared by two polygons
+/// stay where they are. When inflating, the area gained next to an edge goes to the polygon owning the edge, and the boundaries
+/// between the gains of neighbouring polygons follow the straight skeleton of the union.
+///
+/// The `i`-th `MultiPolygon` of the result is the buffered `i`-th polygon of `coverage`, which is empty if the polygon vanished.
+///
+/// # Arguments
+///
+/// + `coverage`: polygons which do not overlap each other, and where neighbours have the same vertices along their shared edges.
+/// + `distance`: determines how distant from each edge of the outer boundary of the coverage to each edge of the result. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given coverage, and,
+///     - `-` to deflate (to add margins, make smaller) the given coverage.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::coverage::buffer_coverage;
+/// use geo::{Area, BoundingRect, Polygon, LineString, Rect};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(1., 0.), (2., 0.), (2., 1.), (1., 1.)]), vec![],
+/// );
+/// let coverage = [p1, p2];
+///
+/// let res = buffer_coverage(&coverage, 0.5);
+/// assert_eq!(res[0].bounding_rect().unwrap(), Rect::new((-0.5, -0.5), (1., 1.5)));
+/// assert_eq!(res[1].bounding_rect().unwrap(), Rect::new((1., -0.5), (2.5, 1.5)));
+/// assert!((res[0].unsigned_area() - 3.).abs() < 1e-6);
+///
+/// let res = buffer_coverage(&coverage, -0.25);
+/// assert_eq!(res[0].bounding_rect().unwrap(), Rect::new((0.25, 0.25), (1., 0.75)));
+/// assert_eq!(res[1].bounding_rect().unwrap(), Rect::new((1., 0.25), (1.75, 0.75)));
+/// ```
+#[must_use = "Use the newly buffered MultiPolygons"]
+pub fn buffer_coverage(coverage: &[Polygon], distance: f64) -> Vec<MultiPolygon> {
+    let mut oriented = MultiPolygon::new(coverage.to_vec());
+    postprocess::orient(&mut oriented, WindingOrder::CounterClockwise);
+    let mut res = vec![MultiPolygon::new(vec![]); coverage.len()];
+    if distance == 0. {
+        for (r, p) in res.iter_mut().zip(oriented) {
+            r.0.push(p);
+        }
+        return res;
+    }
+
+    // With counter-clockwise exteriors, a shared edge is traversed once in each direction.
+    let edges: Vec<(Coord, Coord)> = oriented
+        .0
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .flat_map(|ls| ls.lines())
+        .map(|l| (l.start, l.end))
+        .filter(|(a, b)| a != b)
+        .collect();
+    let directed: HashSet<(CoordKey, CoordKey)> = edges
+        .iter()
+        .map(|&(a, b)| (coord_key(a), coord_key(b)))
+        .collect();
+    let (shared, boundary): (Vec<_>, Vec<_>) = edges
+        .into_iter()
+        .partition(|&(a, b)| directed.contains(&(coord_key(b), coord_key(a))));
+    let union = polygons_of_rings(chain_rings(&boundary));
+    if union.is_empty() {
+        return res;
+    }
+    let skel = Skeleton::skeleton_of_polygon_vector(&union, Side::of_distance(distance));
+    let offset = BufferOptions::new().buffer_skeleton(&skel, distance.abs());
+
+    // Cutting the offset along the shared edges, and along the arcs of the skeleton when inflating, leaves each polygon
+    // together with the area gained next to its edges in one piece.
+    let mut cuts: Vec<Vec<[f64; 2]>> = shared
+        .iter()
+        .filter(|(a, b)| postprocess::cmp_coords(a, b).is_lt())
+        .map(|(a, b)| vec![[a.x, a.y], [b.x, b.y]])
+        .collect();
+    if distance > 0. {
+        skel.for_each_arc_until(distance * (1. + OVERSHOOT), |a, b| {
+            cuts.push(vec![[a.x, a.y], [b.x, b.y]]);
+        });
+    }
+    let paths = repair::to_paths(&offset);
+    let mut pieces = repair::from_shapes(paths.slice_by(&cuts, FillRule::Negative));
+    snap(
+        &mut pieces,
+        paths.into_iter().chain(cuts).flatten().collect(),
+    );
+
+    if distance > 0. {
+        // Each piece contains the polygon it belongs to.
+        for (r, p) in res.iter_mut().zip(&oriented) {
+            let Some(point) = p.interior_point() else {
+                continue;
+            };
+            r.0.extend(pieces.iter().find(|piece| contains(piece, point)).cloned());
+        }
+    } else {
+        // Each piece is inside the polygon it belongs to.
+        for piece in pieces {
+            let Some(point) = piece.interior_point() else {
+                continue;
+            };
+            if let Some(i) = oriented.iter().position(|p| contains(p, point)) {
+                res[i].0.push(piece);
+            }
+        }
+    }
+    for r in &mut res {
+        postprocess::canonicalize(r);
+        postprocess::orient(r, WindingOrder::CounterClockwise);
+    }
+    res
+}
+
+/// Moves each vertex of `mp` onto the closest of `vertices`, if it is that close that they differ by rounding only.
+///
+/// `i_overlay` rounds the coordinates to a grid, so the pieces of the offset come out slightly off the vertices they were cut from.
+fn snap(mp: &mut MultiPolygon, vertices: Vec<[f64; 2]>) {
+    let Some(bounds) = mp.bounding_rect() else {
+        return;
+    };
+    let tolerance = SNAP_TOLERANCE * f64::max(1., f64::max(bounds.width(), bounds.height()));
+    let tree = RTree::bulk_load(vertices);
+    let snap_ring = |ls: &mut LineString| {
+        for c in &mut ls.0 {
+            if let Some(&[x, y]) = tree.nearest_neighbor(&[c.x, c.y]) {
+                if f64::hypot(x - c.x, y - c.y) < tolerance {
+                    *c = Coord { x, y };
+                }
+            }
+        }
+    };
+    for p in &mut mp.0 {
+        p.exterior_mut(snap_ring);
+        p.interiors_mut(|interiors| interiors.iter_mut().for_each(snap_ring));
+    }
+}
+
+/// Joins the directed edges `edges` into closed rings, following an unused edge at each vertex.
+///
+/// All vertices are kept, in particular the vertices where the owner of the edges changes.
+fn chain_rings(edges: &[(Coord, Coord)]) -> Vec<LineString> {
+    let mut outgoing: HashMap<CoordKey, Vec<usize>> = HashMap::new();
+    for (i, &(a, _)) in edges.iter().enumerate() {
+        outgoing.entry(coord_key(a)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = vec![edges[start].0];
+        let mut cur = start;
+        loop {
+            used[cur] = true;
+            let end = edges[cur].1;
+            ring.push(end);
+            if coord_key(end) == coord_key(ring[0]) {
+                break;
+            }
+            let next = outgoing
+                .get(&coord_key(end))
+                .and_then(|next| next.iter().copied().find(|&i| !used[i]));
+            match next {
+                Some(next) => cur = next,
+                None => break,
+            }
+        }
+        // Open chains only come from invalid coverages, and are dropped.
+        if ring.len() >= 4 && coord_key(ring[0]) == coord_key(ring[ring.len() - 1]) {
+            rings.push(LineString::new(ring));
+        }
+    }
+    rings
+}
@@ -0,0 +1,298 @@
+//! Rounded buffers with their corners kept as circular arcs, see [`buffer_polygon_curved`](crate::buffer_polygon_curved).
+
+use std::f64::consts::FRAC_PI_2;
+
+use geo::{Contains, Winding};
+use geo_types::{Coord, LineString, Polygon};
+
+use crate::skeleton::{Side, Skeleton};
+
+/// A circular arc from `start_angle` to `end_angle` (in radian, measured counter-clockwise from the positive x-axis) around `center`.
+///
+/// The arc runs counter-clockwise if `end_angle` is greater than `start_angle`, and clockwise otherwise.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CircularArc {
+    /// The center of the circle.
+    pub center: Coord,
+    /// The radius of the circle.
+    pub radius: f64,
+    /// The angle of the first point of the arc.
+    pub start_angle: f64,
+    /// The angle of the last point of the arc.
+    pub end_angle: f64,
+}
+
+impl CircularArc {
+    /// Returns the point of the circle at `angle`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CircularArc, CoordExt};
+    ///
+    /// let arc = CircularArc { center: (1., 1.).into(), radius: 2., start_angle: 0., end_angle: std::f64::consts::PI };
+    ///
+    /// assert!(arc.point_at(std::f64::consts::FRAC_PI_2).approx_eq(&(1., 3.).into()));
+    /// ```
+    pub fn point_at(&self, angle: f64) -> Coord {
+        self.center
+            + Coord {
+                x: angle.cos(),
+                y: angle.sin(),
+            } * self.radius
+    }
+
+    /// Returns the first point of the arc.
+    pub fn start(&self) -> Coord {
+        self.point_at(self.start_angle)
+    }
+
+    /// Returns the last point of the arc.
+    pub fn end(&self) -> Coord {
+        self.point_at(self.end_angle)
+    }
+
+    /// Returns the points of a polyline along the arc from its first to its last point, whose segments stay within `tolerance`
+    /// of the arc. Each segment spans at most a quarter circle.
+    pub fn approximate(&self, tolerance: f64) -> Vec<Coord> {
+        let sweep = self.end_angle - self.start_angle;
+        // A chord spanning `step` deviates by `radius * (1 - cos(step / 2))` from the arc.
+        let step = if tolerance < self.radius {
+            2. * f64::acos(1. - tolerance / self.radius)
+        } else {
+            FRAC_PI_2
+        };
+        let n = (sweep.abs() / step.min(FRAC_PI_2)).ceil().max(1.) as usize;
+        let mut coords: Vec<Coord> = (0..n)
+            .map(|k| self.point_at(self.start_angle + sweep * k as f64 / n as f64))
+            .collect();
+        coords.push(self.end());
+        coords
+    }
+
+    /// Returns the arc as cubic Bézier curves, each spanning at most a quarter circle, as the control points after the first point
+    /// of each curve.
+    #[cfg(any(feature = "kurbo", feature = "lyon"))]
+    pub(crate) fn cubic_beziers(&self) -> impl Iterator<Item = [Coord; 3]> + '_ {
+        let sweep = self.end_angle - self.start_angle;
+        let n = (sweep.abs() / FRAC_PI_2).ceil().max(1.) as usize;
+        let step = sweep / n as f64;
+        // The control points lie on the tangents at both ends, `4/3 * tan(step / 4)` radii away, which keeps the curve within
+        // 0.03% of the radius from the circle.
+        let reach = 4. / 3. * (step / 4.).tan() * self.radius;
+        let tangent = |angle: f64| Coord {
+            x: -angle.sin(),
+            y: angle.cos(),
+        };
+        (0..n).map(move |k| {
+            let a0 = self.start_angle + step * k as f64;
+            let a1 = if k + 1 == n {
+                self.end_angle
+            } else {
+                a0 + step
+            };
+            let (p0, p3) = (self.point_at(a0), self.point_at(a1));
+            [p0 + tangent(a0) * reach, p3 - tangent(a1) * reach, p3]
+        })
+    }
+}
+
+/// A piece of the boundary of a [`CurvedRing`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CurveSegment {
+    /// A straight segment.
+    Line { start: Coord, end: Coord },
+    /// A circular arc.
+    Arc(CircularArc),
+}
+
+impl CurveSegment {
+    /// Returns the first point of the segment.
+    pub fn start(&self) -> Coord {
+        match self {
+            CurveSegment::Line { start, .. } => *start,
+            CurveSegment::Arc(arc) => arc.start(),
+        }
+    }
+
+    /// Returns the last point of the segment.
+    pub fn end(&self) -> Coord {
+        match self {
+            CurveSegment::Line { end, .. } => *end,
+            CurveSegment::Arc(arc) => arc.end(),
+        }
+    }
+}
+
+/// A closed ring made of straight segments and circular arcs, each starting where the one before ends.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurvedRing {
+    /// The pieces of the ring in order.
+    pub segments: Vec<CurveSegment>,
+}
+
+impl CurvedRing {
+    /// Returns the closed ring through the ends of the straight segments, with the arcs replaced by polylines within `tolerance`
+    /// of them, see [`CircularArc::approximate`].
+    pub fn approximate(&self, tolerance: f64) -> LineString {
+        let mut coords = Vec::new();
+        for segment in &self.segments {
+            match segment {
+                CurveSegment::Line { start, .. } => coords.push(*start),
+                CurveSegment::Arc(arc) => {
+                    let mut points = arc.approximate(tolerance);
+                    points.pop();
+                    coords.extend(points);
+                }
+            }
+        }
+        let mut ls = LineString::from(coords);
+        ls.close();
+        ls
+    }
+
+    /// Returns the ring as a closed path of lines and cubic Bézier curves, each arc drawn as one curve per quarter circle
+    /// (at most), see [`CurvedPolygon::to_bez_path`].
+    #[cfg(feature = "kurbo")]
+    pub fn to_bez_path(&self) -> kurbo::BezPath {
+        let mut path = kurbo::BezPath::new();
+        self.append_to(&mut path);
+        path
+    }
+
+    #[cfg(feature = "kurbo")]
+    fn append_to(&self, path: &mut kurbo::BezPath) {
+        let point = |c: Coord| kurbo::Point::new(c.x, c.y);
+        let Some(first) = self.segments.first() else {
+            return;
+        };
+        path.move_to(point(first.start()));
+        for segment in &self.segments {
+            match segment {
+                CurveSegment::Line { end, .. } => path.line_to(point(*end)),
+                CurveSegment::Arc(arc) => {
+                    for [p1, p2, p3] in arc.cubic_beziers() {
+                        path.curve_to(point(p1), point(p2), point(p3));
+                    }
+                }
+            }
+        }
+        path.close_path();
+    }
+}
+
+/// A polygon whose rings are [`CurvedRing`]s, see [`buffer_polygon_curved`](crate::buffer_polygon_curved).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurvedPolygon {
+    /// The outer boundary, counter-clockwise.
+    pub exterior: CurvedRing,
+    /// The boundaries of the holes, clockwise.
+    pub interiors: Vec<CurvedRing>,
+}
+
+impl CurvedPolygon {
+    /// Returns the polygon whose rings are the rings of this polygon approximated within `tolerance`, see [`CurvedRing::approximate`].
+    pub fn approximate(&self, tolerance: f64) -> Polygon {
+        Polygon::new(
+            self.exterior.approximate(tolerance),
+            self.interiors
+                .iter()
+                .map(|ring| ring.approximate(tolerance))
+                .collect(),
+        )
+    }
+
+    /// Returns the polygon as a [`kurbo::BezPath`] with one closed subpath per ring, the arcs drawn as cubic Bézier curves,
+    /// so vector graphics tools can import the rounded corners as curves rather than polylines.
+    ///
+    /// The rings keep their orientation, so the path fills the polygon under both the non-zero and the even-odd rule.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo::polygon;
+    /// use geo_buf::buffer_polygon_curved;
+    /// use kurbo::{PathEl, Shape};
+    ///
+    /// let p1 = polygon![(x: 0., y: 0.), (x: 1., y: 0.), (x: 1., y: 1.), (x: 0., y: 1.)];
+    /// let path = buffer_polygon_curved(&p1, 1.)[0].to_bez_path();
+    ///
+    /// let curves = path.elements().iter().filter(|el| matches!(el, PathEl::CurveTo(..))).count();
+    /// assert_eq!(curves, 4);
+    /// // A unit square rounded by 1 has area 1 + 4 + π.
+    /// assert!((path.area() - (5. + std::f64::consts::PI)).abs() < 1e-3);
+    /// ```
+    #[cfg(feature = "kurbo")]
+    pub fn to_bez_path(&self) -> kurbo::BezPath {
+        let mut path = kurbo::BezPath::new();
+        for ring in std::iter::once(&self.exterior).chain(&self.interiors) {
+            ring.append_to(&mut path);
+        }
+        path
+    }
+}
+
+/// Returns the buffer of the polygons `input` by `distance` with rounded corners, keeping the corners as arcs.
+pub(crate) fn buffer(input: &[Polygon], distance: f64) -> Vec<CurvedPolygon> {
+    let skel = Skeleton::skeleton_of_polygon_vector(input, Side::of_distance(distance));
+    let offset_distance = distance.abs();
+    let vertex_queue = skel.get_vertex_queue(offset_distance);
+    assemble(skel.curved_wavefront(&vertex_queue, offset_distance))
+}
+
+/// A vertex of the wavefront with its corner: either a single point or an arc.
+pub(crate) enum Corner {
+    Point(Coord),
+    Arc(CircularArc),
+}
+
+/// Returns the ring through `corners`, joining each of them to the next by a straight segment.
+pub(crate) fn ring(corners: &[Corner]) -> CurvedRing {
+    let ends = |corner: &Corner| match corner {
+        Corner::Point(c) => (*c, *c),
+        Corner::Arc(arc) => (arc.start(), arc.end()),
+    };
+    let mut segments = Vec::new();
+    for (i, corner) in corners.iter().enumerate() {
+        if let Corner::Arc(arc) = corner {
+            segments.push(CurveSegment::Arc(*arc));
+        }
+        let (_, start) = ends(corner);
+        let (end, _) = ends(&corners[(i + 1) % corners.len()]);
+        if start != end {
+            segments.push(CurveSegment::Line { start, end });
+        }
+    }
+    CurvedRing { segments }
+}
+
+/// Groups `rings` into polygons: the counter-clockwise rings become exteriors, and each clockwise ring becomes an interior of the
+/// polygon whose exterior contains it.
+pub(crate) fn assemble(rings: Vec<CurvedRing>) -> Vec<CurvedPolygon> {
+    let mut res = Vec::new();
+    let mut holes = Vec::new();
+    for ring in rings {
+        let ls = ring.approximate(f64::INFINITY);
+        if ls.is_ccw() {
+            res.push((
+                CurvedPolygon {
+                    exterior: ring,
+                    interiors: Vec::new(),
+                },
+                Polygon::new(ls, vec![]),
+            ));
+        } else if ls.is_cw() {
+            holes.push((ring, ls));
+        }
+    }
+    for (ring, ls) in holes {
+        if let Some((p, _)) = res.iter_mut().find(|(_, e)| e.contains(&ls)) {
+            p.interiors.push(ring);
+        }
+    }
+    res.into_iter().map(|(p, _)| p).collect()
+}
@@ -0,0 +1,160 @@
+//! Rendering of inputs, straight skeletons and buffers into SVG for debugging.
+
+use std::fmt::Write;
+
+use geo::BoundingRect;
+use geo_types::{Coord, LineString, MultiPolygon, Rect};
+
+use crate::skeleton::{EventFrame, EventKind, Side, Skeleton};
+use crate::BufferOptions;
+
+const INPUT_STYLE: &str = r##"fill="#d0d0d0" fill-opacity="0.6" stroke="#202020""##;
+const SKELETON_STYLE: &str = r##"fill="none" stroke="#d03030""##;
+const OFFSET_STYLE: &str = r##"fill="none" stroke="#3050d0""##;
+
+/// Renders `input`, its straight skeleton(s) and its buffers by each of `distances` into an SVG document.
+///
+/// The inward skeleton is drawn if some distance is negative, and the outward one if some distance is non-negative.
+pub(crate) fn render(input: &MultiPolygon, distances: &[f64]) -> String {
+    let mut skeleton_segments = Vec::new();
+    for side in [Side::Interior, Side::Exterior] {
+        if distances.iter().any(|&d| Side::of_distance(d) == side) {
+            let skel = Skeleton::skeleton_of_polygon_vector(&input.0, side);
+            skeleton_segments.extend(skel.to_linestring());
+        }
+    }
+    let offsets: Vec<MultiPolygon> = distances
+        .iter()
+        .map(|&d| BufferOptions::new().buffer(&input.0, d))
+        .collect();
+
+    let bounds = union_rects(
+        std::iter::once(input.bounding_rect()).chain(offsets.iter().map(|mp| mp.bounding_rect())),
+    );
+    let margin = margin_of(bounds);
+    let mut svg = header(bounds, margin);
+    write_multi_polygon(&mut svg, input, INPUT_STYLE);
+    // Segments to infinity are clipped to the picture.
+    let _ = writeln!(
+        svg,
+        r#"<clipPath id="bounds"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+        bounds.min().x - margin,
+        bounds.min().y - margin,
+        bounds.width() + 2. * margin,
+        bounds.height() + 2. * margin,
+    );
+    let _ = write!(
+        svg,
+        r#"<path clip-path="url(#bounds)" {SKELETON_STYLE} d=""#
+    );
+    for ls in &skeleton_segments {
+        write_path(&mut svg, ls, false);
+    }
+    let _ = writeln!(svg, r#"" vector-effect="non-scaling-stroke"/>"#);
+    for mp in &offsets {
+        write_multi_polygon(&mut svg, mp, OFFSET_STYLE);
+    }
+    footer(svg)
+}
+
+/// Renders the wavefront of `frame` and the location of its event into an SVG document.
+pub(crate) fn render_frame(frame: &EventFrame) -> String {
+    let location = Coord {
+        x: frame.location.x,
+        y: frame.location.y,
+    };
+    let bounds = union_rects(
+        frame
+            .wavefront
+            .iter()
+            .map(|ls| ls.bounding_rect())
+            .chain(std::iter::once(Some(Rect::new(location, location)))),
+    );
+    let margin = margin_of(bounds);
+    let mut svg = header(bounds, margin);
+    let _ = write!(svg, r#"<path {OFFSET_STYLE} d=""#);
+    for ls in &frame.wavefront {
+        write_path(&mut svg, ls, true);
+    }
+    let _ = writeln!(svg, r#"" vector-effect="non-scaling-stroke"/>"#);
+    let kind = match frame.kind {
+        EventKind::Shrink => "Shrink",
+        EventKind::Split => "Split",
+    };
+    let _ = writeln!(
+        svg,
+        r##"<circle cx="{}" cy="{}" r="{}" fill="#d03030"><title>{kind} event {} at time {}</title></circle>"##,
+        location.x,
+        location.y,
+        margin / 4.,
+        frame.index,
+        frame.time,
+    );
+    footer(svg)
+}
+
+fn union_rects(rects: impl Iterator<Item = Option<Rect>>) -> Rect {
+    rects
+        .flatten()
+        .reduce(|a, b| {
+            Rect::new(
+                Coord {
+                    x: a.min().x.min(b.min().x),
+                    y: a.min().y.min(b.min().y),
+                },
+                Coord {
+                    x: a.max().x.max(b.max().x),
+                    y: a.max().y.max(b.max().y),
+                },
+            )
+        })
+        .unwrap_or(Rect::new((0., 0.), (1., 1.)))
+}
+
+fn margin_of(bounds: Rect) -> f64 {
+    0.05 * f64::max(bounds.width(), bounds.height()).max(f64::EPSILON)
+}
+
+/// Opens an SVG document showing `bounds` with `margin` around, and a group in the coordinates of the geometries.
+fn header(bounds: Rect, margin: f64) -> String {
+    let mut svg = String::new();
+    // The y axis is flipped so that the picture is not upside down.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        bounds.min().x - margin,
+        -bounds.max().y - margin,
+        bounds.width() + 2. * margin,
+        bounds.height() + 2. * margin,
+    );
+    let _ = writeln!(
+        svg,
+        r#"<g transform="scale(1,-1)" stroke-width="1" stroke-linejoin="round">"#
+    );
+    svg
+}
+
+/// Closes the document opened by [`header`].
+fn footer(mut svg: String) -> String {
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn write_multi_polygon(svg: &mut String, mp: &MultiPolygon, style: &str) {
+    let _ = write!(svg, r#"<path {style} fill-rule="evenodd" d=""#);
+    for p in mp {
+        for ls in std::iter::once(p.exterior()).chain(p.interiors()) {
+            write_path(svg, ls, true);
+        }
+    }
+    let _ = writeln!(svg, r#"" vector-effect="non-scaling-stroke"/>"#);
+}
+
+fn write_path(svg: &mut String, ls: &LineString, closed: bool) {
+    for (i, c) in ls.0.iter().enumerate() {
+        let _ = write!(svg, "{}{} {} ", if i == 0 { 'M' } else { 'L' }, c.x, c.y);
+    }
+    if closed {
+        svg.push_str("Z ");
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+use geo_types::Coord;
+
+use crate::RepairReport;
+
+/// A remark about the quality of a buffer, see [`Diagnostics`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// A polygon of the result has been removed because its exterior encloses less than the [minimal output area](crate::BufferOptions::min_output_area).
+    /// Holds the index of the polygon before the removal and the area of its exterior.
+    PolygonBelowMinArea { polygon: usize, area: f64 },
+    /// A hole of the result has been removed because it encloses less than the [minimal hole area](crate::BufferOptions::min_hole_area).
+    /// Holds the index of its polygon and its index among the interiors of the polygon before the removal, and its area.
+    HoleBelowMinArea {
+        polygon: usize,
+        hole: usize,
+        area: f64,
+    },
+    /// A ring of the result has been removed because fewer than three vertices are left once the vertices closer than the
+    /// [minimal segment length](crate::BufferOptions::min_segment_length), or snapped together by the [output grid](crate::BufferOptions::output_grid),
+    /// are merged. Holds the index of its polygon before the
+    /// removal and the index of the ring, `0` being the exterior and `i + 1` the `i`-th interior. The whole polygon is removed with its exterior.
+    RingCollapsed { polygon: usize, ring: usize },
+    /// A mitre has been cut off because it reaches beyond the [mitre limit](crate::BufferParams::mitre_limit).
+    /// Holds the vertex the mitre grows out of, i.e. a vertex of the input or a node of the straight skeleton.
+    MiterLimited { vertex: Coord },
+    /// The straight skeleton broke down on the input, so the result has been computed by [`Algorithm::OffsetUnion`](crate::Algorithm::OffsetUnion).
+    FellBack,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::PolygonBelowMinArea { polygon, area } => {
+                write!(f, "polygon {polygon} dropped: area {area} below min area")
+            }
+            Warning::HoleBelowMinArea {
+                polygon,
+                hole,
+                area,
+            } => write!(
+                f,
+                "hole {hole} of polygon {polygon} dropped: area {area} below min hole area"
+            ),
+            Warning::RingCollapsed { polygon, ring } => write!(
+                f,
+                "ring {ring} of polygon {polygon} dropped: collapsed to fewer than three vertices"
+            ),
+            Warning::MiterLimited { vertex } => {
+                write!(f, "miter limited at vertex ({}, {})", vertex.x, vertex.y)
+            }
+            Warning::FellBack => write!(f, "straight skeleton failed, buffered by offset union"),
+        }
+    }
+}
+
+/// Quality information about a buffer, see [`buffer_polygon_with_diagnostics`](crate::buffer_polygon_with_diagnostics).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostics {
+    /// The remarks about the result, in the order of the stages which gave rise to them.
+    pub warnings: Vec<Warning>,
+    /// The validation of the result.
+    pub repair: RepairReport,
+}
@@ -0,0 +1,135 @@
+//! Export of inputs, straight skeletons and buffers to DXF for CAD tools.
+//!
+//! The drawing is written in the ASCII format of AutoCAD R12 (`AC1009`), the version every CAD tool still reads: a table of
+//! layers and the entities, without the optional sections.
+
+use std::fmt::Write;
+
+use geo_types::{Coord, LineString, MultiPolygon};
+
+use crate::skeleton::{Side, Skeleton};
+use crate::BufferOptions;
+
+const INPUT_LAYER: &str = "INPUT";
+const SKELETON_LAYER: &str = "SKELETON";
+
+/// The ACI colors of the layers: white for the input, red for the skeleton and blue for the offsets.
+const INPUT_COLOR: u8 = 7;
+const SKELETON_COLOR: u8 = 1;
+const OFFSET_COLOR: u8 = 5;
+
+/// Writes `input`, its straight skeleton(s) and its buffers by each of `distances` into a DXF document.
+///
+/// The input goes to the `INPUT` layer, the skeletons to the `SKELETON` layer and the buffer by `distances[i]` to the `OFFSET_i` layer.
+/// The inward skeleton is written if some distance is negative, and the outward one if some distance is non-negative.
+pub(crate) fn render(input: &MultiPolygon, distances: &[f64]) -> String {
+    let mut skeleton_segments = Vec::new();
+    for side in [Side::Interior, Side::Exterior] {
+        if distances.iter().any(|&d| Side::of_distance(d) == side) {
+            let skel = Skeleton::skeleton_of_polygon_vector(&input.0, side);
+            skeleton_segments.extend(skel.to_linestring());
+        }
+    }
+    let offsets: Vec<MultiPolygon> = distances
+        .iter()
+        .map(|&d| BufferOptions::new().buffer(&input.0, d))
+        .collect();
+
+    let mut layers = vec![
+        (INPUT_LAYER.to_string(), INPUT_COLOR),
+        (SKELETON_LAYER.to_string(), SKELETON_COLOR),
+    ];
+    layers.extend((0..offsets.len()).map(|i| (format!("OFFSET_{i}"), OFFSET_COLOR)));
+
+    let mut dxf = header(&layers);
+    write_multi_polygon(&mut dxf, input, INPUT_LAYER);
+    for ls in &skeleton_segments {
+        for l in ls.lines() {
+            write_line(&mut dxf, l.start, l.end, SKELETON_LAYER);
+        }
+    }
+    for ((layer, _), mp) in layers[2..].iter().zip(&offsets) {
+        write_multi_polygon(&mut dxf, mp, layer);
+    }
+    footer(dxf)
+}
+
+/// Writes a group code and its value.
+fn group(dxf: &mut String, code: u16, value: impl std::fmt::Display) {
+    let _ = writeln!(dxf, "{code:>3}\n{value}");
+}
+
+/// Starts the document with the header, the table of `layers` given by their names and colors, and opens the entities.
+fn header(layers: &[(String, u8)]) -> String {
+    let mut dxf = String::new();
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "HEADER");
+    group(&mut dxf, 9, "$ACADVER");
+    group(&mut dxf, 1, "AC1009");
+    group(&mut dxf, 0, "ENDSEC");
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "TABLES");
+    group(&mut dxf, 0, "TABLE");
+    group(&mut dxf, 2, "LAYER");
+    group(&mut dxf, 70, layers.len());
+    for (name, color) in layers {
+        group(&mut dxf, 0, "LAYER");
+        group(&mut dxf, 2, name);
+        group(&mut dxf, 70, 0);
+        group(&mut dxf, 62, color);
+        group(&mut dxf, 6, "CONTINUOUS");
+    }
+    group(&mut dxf, 0, "ENDTAB");
+    group(&mut dxf, 0, "ENDSEC");
+    group(&mut dxf, 0, "SECTION");
+    group(&mut dxf, 2, "ENTITIES");
+    dxf
+}
+
+/// Closes the entities and the document.
+fn footer(mut dxf: String) -> String {
+    group(&mut dxf, 0, "ENDSEC");
+    group(&mut dxf, 0, "EOF");
+    dxf
+}
+
+fn write_point(dxf: &mut String, c: Coord, first_code: u16) {
+    group(dxf, first_code, c.x);
+    group(dxf, first_code + 10, c.y);
+    group(dxf, first_code + 20, 0.);
+}
+
+fn write_line(dxf: &mut String, a: Coord, b: Coord, layer: &str) {
+    group(dxf, 0, "LINE");
+    group(dxf, 8, layer);
+    write_point(dxf, a, 10);
+    write_point(dxf, b, 11);
+}
+
+/// Writes each ring of `mp` as a closed polyline.
+fn write_multi_polygon(dxf: &mut String, mp: &MultiPolygon, layer: &str) {
+    for p in mp {
+        for ls in std::iter::once(p.exterior()).chain(p.interiors()) {
+            write_ring(dxf, ls, layer);
+        }
+    }
+}
+
+fn write_ring(dxf: &mut String, ls: &LineString, layer: &str) {
+    let coords = &ls.0[..ls.0.len().saturating_sub(1)];
+    if coords.is_empty() {
+        return;
+    }
+    group(dxf, 0, "POLYLINE");
+    group(dxf, 8, layer);
+    group(dxf, 66, 1);
+    write_point(dxf, Coord::zero(), 10);
+    group(dxf, 70, 1);
+    for &c in coords {
+        group(dxf, 0, "VERTEX");
+        group(dxf, 8, layer);
+        write_point(dxf, c, 10);
+    }
+    group(dxf, 0, "SEQEND");
+    group(dxf, 8, layer);
+}
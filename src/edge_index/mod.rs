@@ -0,0 +1,337 @@
+use rstar::{Envelope, RTree, RTreeObject, AABB};
+
+use crate::skeleton::VertexType;
+use crate::vertex_queue::{IndexType, VertexQueue};
+use geo_types::Coord;
+
+/// The factor by which horizons are extended when a query exceeds them.
+pub(crate) const HORIZON_GROWTH: f64 = 1.5;
+
+/// Enlarges the time bound `time` slightly, so that events at `time` pass the culling despite rounding errors.
+fn inflate(time: f64) -> f64 {
+    time * (1. + 1e-9) + 1e-9
+}
+
+/// A wavefront edge, stamped with the state of the vertex queue at the time it was indexed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct WavefrontEdge {
+    node: usize,
+    real: usize,
+    right_node: usize,
+    right_real: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct IndexedEdge {
+    envelope: AABB<[f64; 2]>,
+    edge: WavefrontEdge,
+}
+
+impl RTreeObject for IndexedEdge {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// Spatial index over the edges of the wavefront, used to find split event candidates by range query.
+///
+/// Each wavefront edge is indexed by the bounding box of the region it sweeps from time 0 to `horizon`.
+/// A split event of a reflex vertex at time *t* ≤ `horizon` lies on the wavefront edge it splits,
+/// so only the edges whose boxes meet the path of the reflex vertex have to be checked.
+///
+/// Entries are never removed. Whenever an event changes a wavefront edge a new entry is inserted,
+/// and the outdated entries are filtered out by their stamps when they are read.
+/// The horizon is extended by a constant factor (and the index rebuilt) whenever a query exceeds it,
+/// which only happens logarithmically often since the events are processed in order of time.
+pub(crate) struct EdgeIndex {
+    tree: RTree<IndexedEdge>,
+    /// Edges whose boxes cannot be computed (e.g. because of non-finite rays). They are always candidates.
+    unbounded: Vec<WavefrontEdge>,
+    horizon: f64,
+}
+
+impl EdgeIndex {
+    /// Creates an empty index. It is built on the first query.
+    pub(crate) fn new() -> Self {
+        Self {
+            tree: RTree::new(),
+            unbounded: Vec::new(),
+            horizon: -1.,
+        }
+    }
+
+    /// Empties the index so that it can be reused for another wavefront.
+    pub(crate) fn clear(&mut self) {
+        self.tree = RTree::new();
+        self.unbounded.clear();
+        self.horizon = -1.;
+    }
+
+    fn rebuild(&mut self, vertex_queue: &VertexQueue, vertex_vector: &[VertexType], horizon: f64) {
+        self.horizon = horizon;
+        self.unbounded.clear();
+        let mut bounded = Vec::new();
+        for node in vertex_queue.live_nodes() {
+            let (edge, envelope) = self.make_entry(node.get_index(), vertex_queue, vertex_vector);
+            match envelope {
+                Some(envelope) => bounded.push(IndexedEdge { envelope, edge }),
+                None => self.unbounded.push(edge),
+            }
+        }
+        self.tree = RTree::bulk_load(bounded);
+    }
+
+    fn make_entry(
+        &self,
+        node: usize,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+    ) -> (WavefrontEdge, Option<AABB<[f64; 2]>>) {
+        let right_node = vertex_queue.content[node].right.get_index();
+        let edge = WavefrontEdge {
+            node,
+            real: vertex_queue.content[node].index.get_real_index(),
+            right_node,
+            right_real: vertex_queue.content[right_node].index.get_real_index(),
+        };
+        let (lv, rv) = (&vertex_vector[edge.real], &vertex_vector[edge.right_real]);
+        (edge, Self::swept_envelope(lv, rv, self.horizon))
+    }
+
+    /// Returns the bounding box of the region swept by the wavefront edge from `lv` to `rv` from time 0 to `time`.
+    fn swept_envelope(lv: &VertexType, rv: &VertexType, time: f64) -> Option<AABB<[f64; 2]>> {
+        match (lv, rv) {
+            (VertexType::Tree { .. }, VertexType::Tree { .. }) => Self::envelope_of(&[
+                lv.location_at(0.),
+                lv.location_at(time),
+                rv.location_at(0.),
+                rv.location_at(time),
+            ]),
+            _ => None,
+        }
+    }
+
+    fn envelope_of(points: &[Coord]) -> Option<AABB<[f64; 2]>> {
+        let mut lo = [f64::INFINITY; 2];
+        let mut hi = [f64::NEG_INFINITY; 2];
+        for p in points {
+            if !p.x.is_finite() || !p.y.is_finite() {
+                return None;
+            }
+            lo = [f64::min(lo[0], p.x), f64::min(lo[1], p.y)];
+            hi = [f64::max(hi[0], p.x), f64::max(hi[1], p.y)];
+        }
+        let margin = 1e-6
+            * (1.
+                + [lo[0], lo[1], hi[0], hi[1]]
+                    .iter()
+                    .fold(0., |m, x| f64::max(m, x.abs())));
+        Some(AABB::from_corners(
+            [lo[0] - margin, lo[1] - margin],
+            [hi[0] + margin, hi[1] + margin],
+        ))
+    }
+
+    /// Indexes the wavefront edges incident to the node `node`, after it has been changed by an event.
+    pub(crate) fn update(
+        &mut self,
+        node: IndexType,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+    ) {
+        let left = vertex_queue.lv(node).get_index();
+        for node in [left, node.get_index()] {
+            let (edge, envelope) = self.make_entry(node, vertex_queue, vertex_vector);
+            match envelope {
+                Some(envelope) => self.tree.insert(IndexedEdge { envelope, edge }),
+                None => self.unbounded.push(edge),
+            }
+        }
+    }
+
+    fn is_current(edge: &WavefrontEdge, vertex_queue: &VertexQueue) -> bool {
+        let content = &vertex_queue.content[edge.node];
+        !content.done
+            && content.left != content.right
+            && content.index == IndexType::RealIndex(edge.real)
+            && content.right == IndexType::PointerIndex(edge.right_node)
+            && vertex_queue.content[edge.right_node].index == IndexType::RealIndex(edge.right_real)
+    }
+
+    /// Extends the horizon to `time` if it is finite and beyond it, so that [`EdgeIndex::locate`] can query until then.
+    pub(crate) fn reserve(
+        &mut self,
+        time: f64,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+    ) {
+        if time.is_finite() && inflate(time) > self.horizon {
+            self.rebuild(
+                vertex_queue,
+                vertex_vector,
+                f64::max(inflate(time), self.horizon * HORIZON_GROWTH),
+            );
+        }
+    }
+
+    /// Collects into `ret` the wavefront vertices whose right edges may be split by the reflex vertex `anchor`
+    /// until time `time`, or every wavefront vertex if no useful bound exists.
+    ///
+    /// The horizon has to reach `time` already, see [`EdgeIndex::reserve`].
+    pub(crate) fn locate(
+        &self,
+        anchor: &VertexType,
+        time: f64,
+        vertex_queue: &VertexQueue,
+        ret: &mut Vec<IndexType>,
+    ) {
+        ret.clear();
+        let query = if time.is_finite() {
+            debug_assert!(inflate(time) <= self.horizon, "query beyond the horizon");
+            Self::envelope_of(&[anchor.location_at(0.), anchor.location_at(inflate(time))])
+        } else {
+            None
+        };
+        let Some(query) = query else {
+            ret.extend(vertex_queue.live_nodes());
+            return;
+        };
+        ret.extend(
+            self.tree
+                .locate_in_envelope_intersecting(&query)
+                .map(|e| &e.edge)
+                .chain(self.unbounded.iter())
+                .filter(|e| Self::is_current(e, vertex_queue))
+                .map(|e| IndexType::PointerIndex(e.node)),
+        );
+        ret.sort_unstable_by_key(IndexType::get_index);
+        ret.dedup();
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct IndexedAnchor {
+    envelope: AABB<[f64; 2]>,
+    node: usize,
+}
+
+impl RTreeObject for IndexedAnchor {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// Spatial index over the reflex vertices waiting to split the wavefront, used to find the vertices which may split a new wavefront edge.
+///
+/// Each vertex is indexed by the bounding box of its path from time 0 to `horizon`. A new edge can only be split by a vertex
+/// until `horizon` if the box swept by the edge until then meets the box of the vertex.
+/// The index is rebuilt whenever the horizon is extended.
+pub(crate) struct AnchorIndex {
+    tree: RTree<IndexedAnchor>,
+    /// Vertices whose boxes cannot be computed (e.g. because the horizon is infinite). They are always found.
+    unbounded: Vec<usize>,
+    horizon: f64,
+}
+
+impl AnchorIndex {
+    /// Creates an empty index.
+    pub(crate) fn new() -> Self {
+        Self {
+            tree: RTree::new(),
+            unbounded: Vec::new(),
+            horizon: -1.,
+        }
+    }
+
+    /// Empties the index so that it can be reused for another wavefront.
+    pub(crate) fn clear(&mut self) {
+        self.tree = RTree::new();
+        self.unbounded.clear();
+        self.horizon = -1.;
+    }
+
+    /// Indexes the vertices of the nodes `anchors` until `horizon`, replacing the vertices indexed before.
+    pub(crate) fn rebuild(
+        &mut self,
+        anchors: impl Iterator<Item = usize>,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        horizon: f64,
+    ) {
+        self.horizon = horizon;
+        self.unbounded.clear();
+        let mut bounded = Vec::new();
+        for node in anchors {
+            let anchor = &vertex_vector[vertex_queue.content[node].index.get_real_index()];
+            let envelope = match anchor {
+                VertexType::Tree { .. } if horizon.is_finite() => EdgeIndex::envelope_of(&[
+                    anchor.location_at(0.),
+                    anchor.location_at(inflate(horizon)),
+                ]),
+                _ => None,
+            };
+            match envelope {
+                Some(envelope) => bounded.push(IndexedAnchor { envelope, node }),
+                None => self.unbounded.push(node),
+            }
+        }
+        self.tree = RTree::bulk_load(bounded);
+    }
+
+    /// Collects into `ret` the nodes of the indexed vertices which may split the wavefront edge from `lv` to `rv` until the horizon.
+    pub(crate) fn locate(&self, lv: &VertexType, rv: &VertexType, ret: &mut Vec<usize>) {
+        ret.clear();
+        ret.extend_from_slice(&self.unbounded);
+        match EdgeIndex::swept_envelope(lv, rv, inflate(self.horizon)) {
+            Some(envelope) if self.horizon.is_finite() => ret.extend(
+                self.tree
+                    .locate_in_envelope_intersecting(&envelope)
+                    .map(|a| a.node),
+            ),
+            _ => ret.extend(self.tree.iter().map(|a| a.node)),
+        }
+    }
+}
+
+/// The earliest split time found so far in a split vertex search, used to cull the remaining candidates.
+///
+/// A candidate edge can only be split by the anchor vertex before the bound if the box swept by the edge
+/// meets the box swept by the anchor vertex until then. The boxes shrink whenever an earlier split is found.
+pub(crate) struct SplitBound {
+    time: f64,
+    anchor: AABB<[f64; 2]>,
+}
+
+impl SplitBound {
+    /// Creates a bound for splits of the reflex vertex `anchor` until time `time`,
+    /// or `None` if no useful bound exists.
+    pub(crate) fn new(anchor: &VertexType, time: f64) -> Option<Self> {
+        if !time.is_finite() {
+            return None;
+        }
+        let time = inflate(time);
+        Some(Self {
+            time,
+            anchor: EdgeIndex::envelope_of(&[anchor.location_at(0.), anchor.location_at(time)])?,
+        })
+    }
+
+    /// Lowers the bound to `time` if a split of `anchor` at that time is earlier than the current bound.
+    pub(crate) fn tighten(&mut self, anchor: &VertexType, time: f64) {
+        if let Some(bound) = Self::new(anchor, time) {
+            if bound.time < self.time {
+                *self = bound;
+            }
+        }
+    }
+
+    /// Checks whether the wavefront edge from `lv` to `rv` may be split before the bound.
+    pub(crate) fn may_split(&self, lv: &VertexType, rv: &VertexType) -> bool {
+        EdgeIndex::swept_envelope(lv, rv, self.time)
+            .is_none_or(|envelope| envelope.intersects(&self.anchor))
+    }
+}
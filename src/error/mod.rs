@@ -0,0 +1,89 @@
+use std::fmt;
+
+/// The errors of this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The bytes do not start with the header written by [`Skeleton::to_bytes`](crate::Skeleton::to_bytes).
+    NotASkeleton,
+    /// The bytes were written by a newer version of the skeleton format than this crate can read.
+    UnsupportedVersion(u16),
+    /// The bytes end before the skeleton they describe.
+    Truncated,
+    /// The checksum of the bytes does not match, i.e. the bytes have been corrupted.
+    ChecksumMismatch,
+    /// The bytes pass the checksum but do not describe a consistent skeleton.
+    MalformedSkeleton(&'static str),
+    /// The input is not valid WKT. Holds the message of the parser.
+    InvalidWkt(String),
+    /// The input is not valid GeoJSON. Holds the message of the parser.
+    InvalidGeoJson(String),
+    /// The input is a valid geometry, but neither a polygon nor a multi-polygon. Holds the type of the geometry in the input format.
+    UnsupportedGeometry(&'static str),
+    /// The flat coordinate arrays passed to the [`flat`](crate::flat) functions or the JavaScript bindings do not describe a multi-polygon.
+    /// Holds the reason.
+    InvalidFlatArrays(&'static str),
+    /// The tessellation of a path into triangles failed. Holds the message of the tessellator.
+    Tessellation(String),
+    /// The Arrow array passed to `buffer_arrow_polygons` does not hold GeoArrow polygons. Holds the reason.
+    InvalidArrowArray(&'static str),
+    /// The computation was cancelled through its [`CancelToken`](crate::CancelToken).
+    Cancelled,
+    /// The computation did not finish before its [deadline](crate::BufferOptions::deadline).
+    TimedOut,
+    /// The construction of the straight skeleton needed more events than the [limit](crate::BufferOptions::max_events). Holds the limit.
+    TooManyEvents(usize),
+    /// The number of widths does not match the number of vertices of the line string to buffer. Holds both numbers, in this order.
+    MismatchedWidths(usize, usize),
+    /// The number of distances does not match the number of members of the multi-polygon to buffer. Holds both numbers, in this order.
+    MismatchedDistances(usize, usize),
+    /// The construction of the straight skeleton broke down on a pathological input, e.g. through rounding errors.
+    /// Buffering computes the result by [`Algorithm::OffsetUnion`](crate::Algorithm::OffsetUnion) instead of returning this error.
+    NumericFailure,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotASkeleton => write!(f, "not a serialized skeleton"),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "unsupported skeleton format version {version}")
+            }
+            Error::Truncated => write!(f, "serialized skeleton is truncated"),
+            Error::ChecksumMismatch => write!(f, "serialized skeleton is corrupted"),
+            Error::MalformedSkeleton(reason) => write!(f, "malformed skeleton: {reason}"),
+            Error::InvalidWkt(message) => write!(f, "invalid WKT: {message}"),
+            Error::InvalidGeoJson(message) => write!(f, "invalid GeoJSON: {message}"),
+            Error::UnsupportedGeometry(kind) => {
+                write!(f, "expected a polygon or a multi-polygon, found a {kind}")
+            }
+            Error::InvalidFlatArrays(reason) => {
+                write!(f, "invalid flat coordinate arrays: {reason}")
+            }
+            Error::Tessellation(message) => write!(f, "tessellation failed: {message}"),
+            Error::InvalidArrowArray(reason) => {
+                write!(f, "invalid GeoArrow polygon array: {reason}")
+            }
+            Error::Cancelled => write!(f, "computation cancelled"),
+            Error::TimedOut => write!(f, "computation timed out"),
+            Error::TooManyEvents(limit) => {
+                write!(f, "straight skeleton needs more than {limit} events")
+            }
+            Error::MismatchedWidths(vertices, widths) => {
+                write!(
+                    f,
+                    "expected {vertices} widths, one per vertex, found {widths}"
+                )
+            }
+            Error::MismatchedDistances(members, distances) => {
+                write!(
+                    f,
+                    "expected {members} distances, one per member, found {distances}"
+                )
+            }
+            Error::NumericFailure => write!(f, "straight skeleton construction failed numerically"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
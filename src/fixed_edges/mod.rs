@@ -0,0 +1,102 @@
+use geo::winding_order::WindingOrder;
+use geo::Winding;
+use geo_types::{Coord, LineString, Polygon};
+
+/// Returns `input` with the edges listed in `edges` moved outwards by `shift` (inwards if `shift` is negative).
+///
+/// The edges are numbered along the exterior and then along each interior, and each vertex next to a moved edge
+/// moves to the intersection of the lines of its edges. Buffering the result by `-shift` brings the moved edges
+/// back to where they were, while every other edge moves by `-shift`.
+pub(crate) fn shift_edges(input: &Polygon, edges: &[usize], shift: f64) -> Polygon {
+    shift_edges_by(input, |i, _, _| {
+        let shift = if edges.contains(&i) { shift } else { 0. };
+        (shift, shift)
+    })
+}
+
+/// Returns `input` with each edge moved outwards by `shift(a) - base` at its start `a` and by `shift(b) - base` at its end `b`
+/// (inwards where these are negative), so that the edges tilt where `shift` varies along them.
+///
+/// Buffering the result by `base` moves each point of the edges of `input` by about `shift` at that point.
+pub(crate) fn shift_edges_along(
+    input: &Polygon,
+    shift: impl Fn(Coord) -> f64,
+    base: f64,
+) -> Polygon {
+    shift_edges_by(input, |_, a, b| (shift(a) - base, shift(b) - base))
+}
+
+/// Returns `input` with the `i`-th edge from `a` to `b` moved outwards by `s` at `a` and by `t` at `b`, where
+/// `(s, t) = shifts(i, a, b)`, and each vertex moved to the intersection of the lines of its edges.
+fn shift_edges_by(
+    input: &Polygon,
+    mut shifts: impl FnMut(usize, Coord, Coord) -> (f64, f64),
+) -> Polygon {
+    let mut first = 0;
+    let mut shift_ring = |ls: &LineString, interior: bool| {
+        let len = ls.0.len().saturating_sub(1);
+        let shifts: Vec<(f64, f64)> = (0..len)
+            .map(|i| shifts(first + i, ls.0[i], ls.0[i + 1]))
+            .collect();
+        first += len;
+        if shifts.iter().all(|&s| s == (0., 0.)) {
+            return ls.clone();
+        }
+        // The polygon lies on the left of the exterior if it is counter-clockwise, and of an interior if it is clockwise.
+        let ccw = ls.winding_order() == Some(WindingOrder::CounterClockwise);
+        let sign = if ccw != interior { 1. } else { -1. };
+        let line = |i: usize| {
+            let (a, b) = (ls.0[i], ls.0[i + 1]);
+            let d = b - a;
+            let n = f64::hypot(d.x, d.y);
+            // The outward normal is on the right of the direction of a counter-clockwise exterior.
+            let normal = Coord {
+                x: d.y / n,
+                y: -d.x / n,
+            } * sign;
+            let (s, t) = shifts[i];
+            (a + normal * s, b + normal * t)
+        };
+        // Edges of zero length (at repeated vertices) have no direction, so they are left out and their vertices merged.
+        let edges: Vec<usize> = (0..len).filter(|&i| ls.0[i] != ls.0[i + 1]).collect();
+        if edges.is_empty() {
+            return ls.clone();
+        }
+        let mut ring = Vec::with_capacity(edges.len() + 1);
+        for (k, &cur) in edges.iter().enumerate() {
+            let prv = edges[(k + edges.len() - 1) % edges.len()];
+            if shifts[prv].1 == 0. && shifts[cur].0 == 0. {
+                ring.push(ls.0[cur]);
+                continue;
+            }
+            let (a, b) = line(prv);
+            let (c, d) = line(cur);
+            match intersection(a, b, c, d) {
+                Some(p) => ring.push(p),
+                // Collinear edges moving apart are joined by a step.
+                None => ring.extend([b, c]),
+            }
+        }
+        let mut ls = LineString::new(ring);
+        ls.close();
+        ls
+    };
+    let exterior = shift_ring(input.exterior(), false);
+    let interiors = input
+        .interiors()
+        .iter()
+        .map(|ls| shift_ring(ls, true))
+        .collect();
+    Polygon::new(exterior, interiors)
+}
+
+/// Returns the intersection of the line through `a` and `b` with the line through `c` and `d`, unless they are parallel.
+fn intersection(a: Coord, b: Coord, c: Coord, d: Coord) -> Option<Coord> {
+    let (r, s) = (b - a, d - c);
+    let denom = r.x * s.y - r.y * s.x;
+    if denom.abs() <= 1e-12 * f64::hypot(r.x, r.y) * f64::hypot(s.x, s.y) {
+        return None;
+    }
+    let t = ((c.x - a.x) * s.y - (c.y - a.y) * s.x) / denom;
+    Some(a + r * t)
+}
@@ -0,0 +1,171 @@
+//! Buffering of multi-polygons given as flat coordinate arrays, for callers which keep their geometries in this layout
+//! (columnar formats, triangulators, foreign function interfaces) rather than as `geo_types`.
+//!
+//! A multi-polygon is described by three arrays:
+//!
+//! + `coords` holds the x and y coordinates of every vertex, one vertex after the other,
+//! + `ring_ends` holds, for each ring, the index of the vertex following its last vertex,
+//! + `polygon_ends` holds, for each polygon, the index of the ring following its last ring.
+//!
+//! The first ring of each polygon is its exterior and the others are its holes. Input rings may or may not repeat
+//! their first vertex at the end, output rings never do. Offsets starting with `0`, as in GeoArrow, are ends once their
+//! first element is left out, so `&offsets[1..]` can be passed as is.
+//!
+//! The result is written into a [`FlatBuffer`] owned by the caller, whose vectors keep their capacity from one call to the next.
+//!
+//! # Example
+//!
+//! ```
+//! use geo_buf::flat::{buffer_polygon, FlatBuffer};
+//! use geo_buf::BufferOptions;
+//!
+//! // The square from (0, 0) to (2, 2) with the hole from (0.5, 0.5) to (1.5, 1.5)
+//! let coords = [0., 0., 2., 0., 2., 2., 0., 2., 0.5, 0.5, 0.5, 1.5, 1.5, 1.5, 1.5, 0.5];
+//! let mut out = FlatBuffer::default();
+//! buffer_polygon(&coords, &[4, 8], 0.25, &BufferOptions::new(), &mut out).unwrap();
+//!
+//! assert_eq!(out.polygon_ends, vec![2]);
+//! assert_eq!(out.ring_ends, vec![4, 8]);
+//! assert_eq!(out.coords[..8], [-0.25, -0.25, 2.25, -0.25, 2.25, 2.25, -0.25, 2.25]);
+//! ```
+
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+
+use crate::{BufferOptions, Error};
+
+/// A multi-polygon in flat arrays, see the [module documentation](self).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FlatBuffer {
+    /// The x and y coordinates of every vertex.
+    pub coords: Vec<f64>,
+    /// The index of the vertex following the last vertex of each ring.
+    pub ring_ends: Vec<u32>,
+    /// The index of the ring following the last ring of each polygon.
+    pub polygon_ends: Vec<u32>,
+}
+
+impl FlatBuffer {
+    /// Removes every polygon, keeping the capacity of the arrays.
+    pub fn clear(&mut self) {
+        self.coords.clear();
+        self.ring_ends.clear();
+        self.polygon_ends.clear();
+    }
+}
+
+/// Buffers the polygon described by `coords` and `ring_ends` by `distance` with `options`, see [`buffer_polygon_with`](crate::buffer_polygon_with),
+/// and writes the result into `out`, replacing its content.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidFlatArrays`] if the arrays do not describe a polygon, leaving `out` untouched.
+pub fn buffer_polygon(
+    coords: &[f64],
+    ring_ends: &[u32],
+    distance: f64,
+    options: &BufferOptions,
+    out: &mut FlatBuffer,
+) -> Result<(), Error> {
+    let polygon_ends = [ring_ends.len() as u32];
+    buffer_multi_polygon(coords, ring_ends, &polygon_ends, distance, options, out)
+}
+
+/// Buffers the multi-polygon described by `coords`, `ring_ends` and `polygon_ends` by `distance` with `options`,
+/// see [`buffer_multi_polygon_with`](crate::buffer_multi_polygon_with), and writes the result into `out`, replacing its content.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidFlatArrays`] if the arrays do not describe a multi-polygon, leaving `out` untouched.
+pub fn buffer_multi_polygon(
+    coords: &[f64],
+    ring_ends: &[u32],
+    polygon_ends: &[u32],
+    distance: f64,
+    options: &BufferOptions,
+    out: &mut FlatBuffer,
+) -> Result<(), Error> {
+    let input = from_flat(coords, ring_ends, polygon_ends)?;
+    out.clear();
+    write_flat(&options.buffer(&input.0, distance), out);
+    Ok(())
+}
+
+/// Returns the multi-polygon described by `coords`, `ring_ends` and `polygon_ends`.
+pub(crate) fn from_flat(
+    coords: &[f64],
+    ring_ends: &[u32],
+    polygon_ends: &[u32],
+) -> Result<MultiPolygon, Error> {
+    let pairs = coords.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(Error::InvalidFlatArrays("odd number of coordinates"));
+    }
+    let vertices: Vec<Coord> = pairs.map(|c| Coord { x: c[0], y: c[1] }).collect();
+    let rings = split_at_ends(
+        &vertices,
+        ring_ends,
+        "ring ends out of order or out of range",
+    )?
+    .into_iter()
+    .map(|ring| {
+        let mut ls = LineString::new(ring.to_vec());
+        ls.close();
+        if ls.0.len() < 4 {
+            return Err(Error::InvalidFlatArrays(
+                "ring with fewer than three vertices",
+            ));
+        }
+        Ok(ls)
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+    let polygons = split_at_ends(
+        &rings,
+        polygon_ends,
+        "polygon ends out of order or out of range",
+    )?
+    .into_iter()
+    .map(|rings| match rings {
+        [exterior, interiors @ ..] => Ok(Polygon::new(exterior.clone(), interiors.to_vec())),
+        [] => Err(Error::InvalidFlatArrays("polygon without rings")),
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+    Ok(MultiPolygon::new(polygons))
+}
+
+/// Splits `items` before each of `ends`, which must be non-decreasing and end with the length of `items`.
+fn split_at_ends<'a, T>(
+    items: &'a [T],
+    ends: &[u32],
+    reason: &'static str,
+) -> Result<Vec<&'a [T]>, Error> {
+    if ends.last().map_or(0, |&end| end as usize) != items.len() {
+        return Err(Error::InvalidFlatArrays(reason));
+    }
+    let mut start = 0;
+    ends.iter()
+        .map(|&end| {
+            let end = end as usize;
+            let part = items
+                .get(start..end)
+                .ok_or(Error::InvalidFlatArrays(reason))?;
+            start = end;
+            Ok(part)
+        })
+        .collect()
+}
+
+/// Appends the polygons of `mp` to `out`, without repeating the first vertex of each ring at its end.
+pub(crate) fn write_flat(mp: &MultiPolygon, out: &mut FlatBuffer) {
+    for p in mp {
+        for ls in std::iter::once(p.exterior()).chain(p.interiors()) {
+            let open = if ls.is_closed() {
+                &ls.0[..ls.0.len() - 1]
+            } else {
+                &ls.0[..]
+            };
+            out.coords.extend(open.iter().flat_map(|c| [c.x, c.y]));
+            out.ring_ends.push((out.coords.len() / 2) as u32);
+        }
+        out.polygon_ends.push(out.ring_ends.len() as u32);
+    }
+}
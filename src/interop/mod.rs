@@ -0,0 +1,339 @@
+//! Conversions between the geometries of this crate and other geometry formats, behind the feature of each format.
+
+#[cfg(feature = "geojson")]
+use geo_types::MultiLineString;
+#[cfg(any(feature = "arrow", feature = "geojson"))]
+use geo_types::{MultiPolygon, Polygon};
+#[cfg(feature = "geojson")]
+use geojson::{GeoJson, Value};
+#[cfg(feature = "lyon")]
+use lyon_tessellation::path::Path;
+
+#[cfg(feature = "geojson")]
+use crate::buffer_multi_polygon;
+#[cfg(any(feature = "arrow", feature = "geojson", feature = "lyon"))]
+use crate::Error;
+#[cfg(any(feature = "geojson", feature = "geozero"))]
+use crate::Skeleton;
+
+/// Buffers every polygonal geometry of `geojson` by `distance` in place, keeping the properties of each feature.
+///
+/// Bounding boxes are removed, since they no longer hold for the buffered geometries.
+#[cfg(feature = "geojson")]
+pub(crate) fn buffer_geojson(geojson: &mut GeoJson, distance: f64) -> Result<(), Error> {
+    match geojson {
+        GeoJson::Geometry(geometry) => buffer_geojson_geometry(geometry, distance),
+        GeoJson::Feature(feature) => buffer_geojson_feature(feature, distance),
+        GeoJson::FeatureCollection(fc) => {
+            fc.bbox = None;
+            fc.features
+                .iter_mut()
+                .try_for_each(|feature| buffer_geojson_feature(feature, distance))
+        }
+    }
+}
+
+#[cfg(feature = "geojson")]
+fn buffer_geojson_feature(feature: &mut geojson::Feature, distance: f64) -> Result<(), Error> {
+    feature.bbox = None;
+    match &mut feature.geometry {
+        Some(geometry) => buffer_geojson_geometry(geometry, distance),
+        None => Ok(()),
+    }
+}
+
+#[cfg(feature = "geojson")]
+fn buffer_geojson_geometry(geometry: &mut geojson::Geometry, distance: f64) -> Result<(), Error> {
+    let input_multi_polygon = match &geometry.value {
+        value @ Value::Polygon(_) => MultiPolygon::new(vec![
+            Polygon::try_from(value).map_err(|e| Error::InvalidGeoJson(e.to_string()))?
+        ]),
+        value @ Value::MultiPolygon(_) => {
+            MultiPolygon::try_from(value).map_err(|e| Error::InvalidGeoJson(e.to_string()))?
+        }
+        value => return Err(Error::UnsupportedGeometry(value.type_name())),
+    };
+    geometry.bbox = None;
+    geometry.value = Value::from(&buffer_multi_polygon(&input_multi_polygon, distance));
+    Ok(())
+}
+
+#[cfg(feature = "geojson")]
+impl Skeleton {
+    /// Returns the segments of this skeleton as a GeoJSON `MultiLineString` geometry, see
+    /// [`skeleton_of_polygon_to_linestring`](crate::skeleton_of_polygon_to_linestring). Requires the `geojson` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior).to_geojson();
+    ///
+    /// assert_eq!(skel.value.type_name(), "MultiLineString");
+    /// ```
+    pub fn to_geojson(&self) -> geojson::Geometry {
+        geojson::Geometry::new(Value::from(&MultiLineString::new(self.to_linestring())))
+    }
+}
+
+/// The segments of the skeleton are processed as a `MultiLineString` of two-point line strings, in the order of
+/// [`skeleton_of_polygon_to_linestring`](crate::skeleton_of_polygon_to_linestring). Requires the `geozero` feature.
+///
+/// Buffered results are `geo_types` geometries, which geozero processes with its `with-geo` feature.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_polygon, Side};
+/// use geo::{Polygon, LineString};
+/// use geozero::ToJson;
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let json = skeleton_of_polygon(&p1, Side::Interior).to_json().unwrap();
+///
+/// assert!(json.starts_with(r#"{"type": "MultiLineString""#));
+/// ```
+#[cfg(feature = "geozero")]
+impl geozero::GeozeroGeometry for Skeleton {
+    fn process_geom<P: geozero::GeomProcessor>(
+        &self,
+        processor: &mut P,
+    ) -> geozero::error::Result<()> {
+        let mut segments = Vec::new();
+        self.for_each_segment(|a, b| segments.push([a, b]));
+        let multi = processor.multi_dim();
+        processor.multilinestring_begin(segments.len(), 0)?;
+        for (idx, segment) in segments.iter().enumerate() {
+            processor.linestring_begin(false, 2, idx)?;
+            for (i, c) in segment.iter().enumerate() {
+                if multi {
+                    processor.coordinate(c.x, c.y, None, None, None, None, i)?;
+                } else {
+                    processor.xy(c.x, c.y, i)?;
+                }
+            }
+            processor.linestring_end(false, idx)?;
+        }
+        processor.multilinestring_end(0)
+    }
+}
+
+/// Returns the rings of `input` as the closed subpaths of a `lyon` path, with the coordinates rounded to `f32`.
+#[cfg(feature = "lyon")]
+pub(crate) fn lyon_path_of_multi_polygon(input: &geo_types::MultiPolygon) -> Path {
+    let mut builder = Path::builder();
+    for ring in input
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+    {
+        let coords = &ring.0[..ring.0.len().saturating_sub(1)];
+        let Some((&first, rest)) = coords.split_first() else {
+            continue;
+        };
+        builder.begin(lyon_point(first));
+        for &c in rest {
+            builder.line_to(lyon_point(c));
+        }
+        builder.end(true);
+    }
+    builder.build()
+}
+
+/// Returns the rings of `input` as the closed subpaths of a `lyon` path, with the arcs drawn as cubic Bézier curves and the
+/// coordinates rounded to `f32`.
+#[cfg(feature = "lyon")]
+pub(crate) fn lyon_path_of_curved(input: &[crate::CurvedPolygon]) -> Path {
+    use crate::CurveSegment;
+
+    let mut builder = Path::builder();
+    for ring in input
+        .iter()
+        .flat_map(|p| std::iter::once(&p.exterior).chain(&p.interiors))
+    {
+        let Some(first) = ring.segments.first() else {
+            continue;
+        };
+        builder.begin(lyon_point(first.start()));
+        for segment in &ring.segments {
+            match segment {
+                CurveSegment::Line { end, .. } => {
+                    builder.line_to(lyon_point(*end));
+                }
+                CurveSegment::Arc(arc) => {
+                    for [p1, p2, p3] in arc.cubic_beziers() {
+                        builder.cubic_bezier_to(lyon_point(p1), lyon_point(p2), lyon_point(p3));
+                    }
+                }
+            }
+        }
+        builder.end(true);
+    }
+    builder.build()
+}
+
+#[cfg(feature = "lyon")]
+fn lyon_point(c: geo_types::Coord) -> lyon_tessellation::math::Point {
+    lyon_tessellation::math::point(c.x as f32, c.y as f32)
+}
+
+/// Returns the triangles filling `path` under the even-odd rule as a vertex buffer and an index buffer, three indices per
+/// triangle, with the curves flattened within `tolerance`.
+#[cfg(feature = "lyon")]
+pub(crate) fn tessellate(path: &Path, tolerance: f32) -> Result<(Vec<[f32; 2]>, Vec<u32>), Error> {
+    use lyon_tessellation::{
+        BuffersBuilder, FillOptions, FillTessellator, FillVertex, VertexBuffers,
+    };
+
+    let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+    FillTessellator::new()
+        .tessellate_path(
+            path,
+            &FillOptions::tolerance(tolerance),
+            &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| v.position().to_array()),
+        )
+        .map_err(|e| Error::Tessellation(e.to_string()))?;
+    Ok((buffers.vertices, buffers.indices))
+}
+
+/// Returns `input` as little-endian WKB, with the `MultiPolygon` type even for a single polygon.
+#[cfg(feature = "wkb")]
+pub(crate) fn wkb_of_multi_polygon(input: &geo_types::MultiPolygon) -> Vec<u8> {
+    const LITTLE_ENDIAN: u8 = 1;
+    const POLYGON: u32 = 3;
+    const MULTI_POLYGON: u32 = 6;
+
+    let mut wkb = vec![LITTLE_ENDIAN];
+    wkb.extend(MULTI_POLYGON.to_le_bytes());
+    wkb.extend((input.0.len() as u32).to_le_bytes());
+    for p in input {
+        wkb.push(LITTLE_ENDIAN);
+        wkb.extend(POLYGON.to_le_bytes());
+        wkb.extend((1 + p.interiors().len() as u32).to_le_bytes());
+        for ring in std::iter::once(p.exterior()).chain(p.interiors()) {
+            wkb.extend((ring.0.len() as u32).to_le_bytes());
+            for c in ring {
+                wkb.extend(c.x.to_le_bytes());
+                wkb.extend(c.y.to_le_bytes());
+            }
+        }
+    }
+    wkb
+}
+
+/// Returns the polygons of the GeoArrow polygon array `input`, `None` for its null slots.
+///
+/// The coordinates may be interleaved (a fixed size list of 2 `Float64`) or separated (a struct of `Float64` columns `x` and `y`);
+/// further dimensions and the validity of the rings and of the coordinates are ignored.
+#[cfg(feature = "arrow")]
+pub(crate) fn polygons_of_arrow(
+    input: &arrow_array::ListArray,
+) -> Result<Vec<Option<Polygon>>, Error> {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Float64Type;
+    use arrow_array::{Array, Float64Array};
+
+    let float = |array: Option<&std::sync::Arc<dyn Array>>| -> Result<Float64Array, Error> {
+        array
+            .and_then(|a| a.as_primitive_opt::<Float64Type>())
+            .cloned()
+            .ok_or(Error::InvalidArrowArray("coordinates are not Float64"))
+    };
+    let ring = |coords: &dyn Array| -> Result<geo_types::LineString, Error> {
+        if let Some(xy) = coords.as_fixed_size_list_opt() {
+            let dim = xy.value_length() as usize;
+            if dim < 2 {
+                return Err(Error::InvalidArrowArray(
+                    "coordinates have fewer than 2 dimensions",
+                ));
+            }
+            let values = float(Some(xy.values()))?;
+            Ok((0..xy.len())
+                .map(|i| (values.value(i * dim), values.value(i * dim + 1)))
+                .collect())
+        } else if let Some(columns) = coords.as_struct_opt() {
+            let (x, y) = (
+                float(columns.column_by_name("x"))?,
+                float(columns.column_by_name("y"))?,
+            );
+            Ok(x.values()
+                .iter()
+                .zip(y.values().iter())
+                .map(|(&x, &y)| (x, y))
+                .collect())
+        } else {
+            Err(Error::InvalidArrowArray(
+                "coordinates are neither interleaved nor separated",
+            ))
+        }
+    };
+    (0..input.len())
+        .map(|i| {
+            if input.is_null(i) {
+                return Ok(None);
+            }
+            let rings = input.value(i);
+            let rings = rings
+                .as_list_opt::<i32>()
+                .ok_or(Error::InvalidArrowArray("polygons are not lists of rings"))?;
+            let mut rings = (0..rings.len())
+                .map(|j| ring(rings.value(j).as_ref()))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter();
+            Ok(rings
+                .next()
+                .map(|exterior| Polygon::new(exterior, rings.collect())))
+        })
+        .collect()
+}
+
+/// Returns `rows` as a record batch with a single GeoArrow multi-polygon column `geometry`, with interleaved coordinates
+/// and null slots for `None`.
+#[cfg(feature = "arrow")]
+pub(crate) fn arrow_of_multi_polygons(rows: &[Option<MultiPolygon>]) -> arrow_array::RecordBatch {
+    use arrow_array::builder::{FixedSizeListBuilder, Float64Builder, ListBuilder};
+    use arrow_array::{Array, RecordBatch};
+    use arrow_schema::extension::EXTENSION_TYPE_NAME_KEY;
+    use arrow_schema::{Field, Schema};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    let mut builder = ListBuilder::new(ListBuilder::new(ListBuilder::new(
+        FixedSizeListBuilder::new(Float64Builder::new(), 2),
+    )));
+    for row in rows {
+        let Some(mp) = row else {
+            builder.append_null();
+            continue;
+        };
+        for p in mp {
+            let polygon = builder.values();
+            for ls in std::iter::once(p.exterior()).chain(p.interiors()) {
+                let ring = polygon.values();
+                for c in ls {
+                    let coord = ring.values();
+                    coord.values().append_value(c.x);
+                    coord.values().append_value(c.y);
+                    coord.append(true);
+                }
+                ring.append(true);
+            }
+            polygon.append(true);
+        }
+        builder.append(true);
+    }
+    let array = builder.finish();
+    let field =
+        Field::new("geometry", array.data_type().clone(), true).with_metadata(HashMap::from([(
+            EXTENSION_TYPE_NAME_KEY.to_owned(),
+            "geoarrow.multipolygon".to_owned(),
+        )]));
+    RecordBatch::try_new(Arc::new(Schema::new(vec![field])), vec![Arc::new(array)])
+        .expect("the field is built from the data type of the column")
+}
@@ -87,11 +87,11 @@
 //!
 //! ### Example 4
 //!
-//! If you want to apply this function to each member (and not want to unify them), just traversing over an iterator and collecting them will be fine.
-//! (You can get a vector of `MultiPolygon`s thanks to the 'turbofish' syntax:`::<>`.)
+//! If you want to apply this function to each member (and not want to unify them), use `buffer_multi_polygon_individual`.
+//! The `i`-th `MultiPolygon` of the result is the buffered `i`-th member of the input.
 //!
 //! ```
-//! use geo_buf::buffer_polygon;
+//! use geo_buf::{buffer_multi_polygon_individual, BufferOptions};
 //! use geo::{Polygon, MultiPolygon, LineString};
 //!
 //! let p1 = Polygon::new(
@@ -101,8 +101,9 @@
 //!     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
 //! );
 //! let mp1 = MultiPolygon::new(vec![p1, p2]);
-//! let mp2 = mp1.0.iter().map(|x| buffer_polygon(x, 0.9)).collect::<Vec<_>>();
+//! let mp2 = buffer_multi_polygon_individual(&mp1, 0.9, &BufferOptions::new());
 //!
+//! assert_eq!(mp2.len(), 2);
 //! ```
 //! <details>
 //! <summary style="cursor:pointer"> Result </summary>
@@ -132,21 +133,119 @@
 
 // Define submodules and re-exports
 
+#[cfg(feature = "proptest-support")]
+pub mod arbitrary;
+mod bands;
+#[cfg(feature = "cache")]
+mod cache;
+mod collapse;
+mod corridor;
+pub mod coverage;
+mod curve;
+#[cfg(feature = "debug_svg")]
+mod debug_svg;
+mod diagnostics;
+#[cfg(feature = "dxf")]
+mod dxf;
+mod edge_index;
+mod error;
+mod fixed_edges;
+pub mod flat;
+mod interop;
+mod monitor;
+mod offset_union;
+mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod params;
+mod postprocess;
 mod priority_queue;
+mod provenance;
+mod rectilinear;
+mod repair;
+pub mod sdf;
 pub mod skeleton;
+mod stream;
+pub mod toolpath;
+mod triangulate;
 pub mod util;
 mod vertex_queue;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
 
 use std::f64::consts::TAU;
 
-use geo::Point;
+pub use bands::OffsetRing;
+#[cfg(feature = "cache")]
+pub use cache::BufferCache;
+pub use collapse::{Collapse, CollapseComponent};
+pub use corridor::{Junction, StreetNetwork};
+pub use curve::{CircularArc, CurveSegment, CurvedPolygon, CurvedRing};
+pub use diagnostics::{Diagnostics, Warning};
+pub use error::Error;
+use geo::winding_order::WindingOrder;
+use geo::{Point, Winding};
+pub use monitor::{CancelToken, Progress};
+pub use options::{Algorithm, BufferOptions, OutputWinding};
+#[cfg(feature = "rayon")]
+pub use parallel::ParBuffer;
+pub use params::{BufferParams, EndCapStyle, JoinStyle};
+pub use provenance::VertexOrigin;
+pub use repair::RepairReport;
+#[doc(inline)]
+pub use skeleton::{Side, Skeleton, SkeletonBuilder, TieBreak};
 #[doc(inline)]
-pub use util::{Coordinate, Ray};
+#[allow(deprecated)]
+pub use util::Coordinate;
+pub use util::{CoordExt, Ray};
+
+// Every public type is `Send + Sync`, so that skeletons and results can be shared across threads (e.g. behind an `Arc`).
+// The caches inside `Skeleton` are behind a `Mutex` and a `OnceLock` for this reason; this fails to compile if a change breaks it.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Algorithm>();
+    assert_send_sync::<BufferOptions>();
+    assert_send_sync::<BufferParams>();
+    assert_send_sync::<CancelToken>();
+    assert_send_sync::<CircularArc>();
+    assert_send_sync::<Collapse>();
+    assert_send_sync::<CollapseComponent>();
+    assert_send_sync::<CurveSegment>();
+    assert_send_sync::<CurvedPolygon>();
+    assert_send_sync::<CurvedRing>();
+    assert_send_sync::<Diagnostics>();
+    assert_send_sync::<EndCapStyle>();
+    assert_send_sync::<Error>();
+    assert_send_sync::<JoinStyle>();
+    assert_send_sync::<Junction>();
+    assert_send_sync::<OffsetRing>();
+    assert_send_sync::<OutputWinding>();
+    assert_send_sync::<Progress>();
+    assert_send_sync::<Ray>();
+    assert_send_sync::<RepairReport>();
+    assert_send_sync::<Side>();
+    assert_send_sync::<Skeleton>();
+    assert_send_sync::<StreetNetwork>();
+    assert_send_sync::<TieBreak>();
+    assert_send_sync::<VertexOrigin>();
+    assert_send_sync::<Warning>();
+    assert_send_sync::<flat::FlatBuffer>();
+    assert_send_sync::<sdf::Grid>();
+    assert_send_sync::<skeleton::BoundaryProjection>();
+    assert_send_sync::<skeleton::Dcel>();
+    assert_send_sync::<skeleton::EventFrame>();
+    assert_send_sync::<skeleton::SkeletonEvent>();
+    assert_send_sync::<skeleton::SkeletonStats>();
+    #[cfg(feature = "cache")]
+    assert_send_sync::<BufferCache>();
+    #[cfg(feature = "wasm-bindgen")]
+    assert_send_sync::<wasm::FlatMultiPolygon>();
+};
 
 // Main functions in this module
 
-use geo_types::{LineString, MultiPolygon, Polygon};
-use skeleton::Skeleton;
+use geo_types::{Coord, Geometry, LineString, MultiLineString, MultiPolygon, Polygon, Triangle};
 
 /// This function returns the buffered (multi-)polygon of the given polygon. This function creates a miter-joint-like corners around each convex vertex.
 ///
@@ -172,220 +271,2079 @@ use skeleton::Skeleton;
 ///
 /// assert_eq!(&expected_exterior, p2.0[0].exterior())
 /// ```
+///
+/// # Output order
+///
+/// The result is deterministic: each ring starts at its lexicographically smallest coordinate, and the polygons (and the interiors of each polygon)
+/// are sorted lexicographically by their coordinates. Exteriors are counter-clockwise and interiors are clockwise, as in the OGC
+/// Simple Features specification, regardless of the winding order of the input (see [`BufferOptions::winding`] to change this).
+///
+/// ```
+/// use geo_buf::buffer_polygon;
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let p2: MultiPolygon = buffer_polygon(&p1, -0.45);
+///
+/// assert_eq!(p2.0.len(), 2);
+/// assert!(p2.0[0].exterior().0[0].x < p2.0[1].exterior().0[0].x);
+/// for p in &p2 {
+///     let ring = &p.exterior().0;
+///     assert!(ring.iter().all(|c| (c.x, c.y) >= (ring[0].x, ring[0].y)));
+/// }
+/// ```
+///
+/// # Self-touching rings
+///
+/// A ring which touches itself at a vertex (e.g. a figure-eight, as produced by raster-to-vector conversion) is split
+/// into valid polygons at the touching vertices before buffering, so the parts of a figure-eight separate when deflating.
+///
+/// ```
+/// use geo_buf::buffer_polygon;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (4., 2.), (4., 4.), (2., 4.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = buffer_polygon(&p1, -0.5);
+///
+/// assert_eq!(p2.0.len(), 2);
+/// assert_eq!(p2.0[1].exterior().0[0], (2.5, 2.5).into());
+/// ```
+///
+/// Likewise, an interior may touch the exterior (or another interior) at a vertex. When deflating, the interior breaks through the
+/// exterior there; when inflating, the interior is moved off the exterior by a negligible distance first, so that they move apart.
+///
+/// ```
+/// use geo_buf::buffer_polygon;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+///     vec![LineString::from(vec![(10., 10.), (9., 5.), (5., 9.)])],
+/// );
+/// assert_eq!(buffer_polygon(&p1, -0.5).0[0].interiors().len(), 0);
+///
+/// let p2 = buffer_polygon(&p1, 0.5);
+/// assert_eq!(p2.0[0].exterior().0[2], (10.5, 10.5).into());
+/// assert_eq!(p2.0[0].interiors().len(), 1);
+/// ```
+///
+/// # Degenerate rings
+///
+/// A ring with fewer than 4 coordinates (i.e. fewer than 3 vertices, as the ring of `POLYGON EMPTY`) encloses nothing and is skipped,
+/// together with its polygon if it is the exterior.
+///
+/// ```
+/// use geo_buf::buffer_polygon;
+/// use geo::{Polygon, LineString};
+///
+/// let empty = Polygon::new(LineString::new(vec![]), vec![]);
+/// assert!(buffer_polygon(&empty, 1.).0.is_empty());
+///
+/// let square = LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]);
+/// let p1 = Polygon::new(square.clone(), vec![LineString::from(vec![(1., 1.), (1.5, 1.)])]);
+/// assert_eq!(buffer_polygon(&p1, -0.5), buffer_polygon(&Polygon::new(square, vec![]), -0.5));
+/// ```
 #[must_use = "Use the newly buffered Polygon"]
 pub fn buffer_polygon(input_polygon: &Polygon, distance: f64) -> MultiPolygon {
-    let orientation = distance < 0.;
-    let offset_distance = f64::abs(distance);
-    let skel = Skeleton::skeleton_of_polygon(input_polygon, orientation);
-    let vq = skel.get_vertex_queue(offset_distance);
-    skel.apply_vertex_queue(&vq, offset_distance)
+    BufferOptions::new().buffer(std::slice::from_ref(input_polygon), distance)
 }
 
-/// This function returns the buffered (multi-)polygon of the given polygon, but creates a rounded corners around each convex vertex.
-/// Therefore, distance from each point on border of the buffered polygon to the closest points on the given polygon is (approximately) equal.
-/// Click 'Result' below to see how this function works.
+/// This function returns the buffered polygon of the given polygon as [`buffer_polygon`] does, if the result is a single polygon.
+///
+/// Otherwise, i.e. if the polygon vanishes or splits apart when deflating, the whole (multi-)polygon is returned as the error,
+/// so that nothing is lost.
 ///
 /// # Arguments
 ///
 /// + `input_polygon`: `Polygon` to buffer.
-/// + `distance`: determine how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
-///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
-///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `distance`: determines how the input polygon will scale as in [`buffer_polygon`].
 ///
 /// # Example
 ///
 /// ```
-/// use geo_buf::{buffer_polygon, buffer_polygon_rounded};
-/// use geo::{Polygon, MultiPolygon, LineString};
+/// use geo_buf::buffer_polygon_single;
+/// use geo::{Polygon, LineString};
 ///
 /// let p1 = Polygon::new(
-///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+///     LineString::from(vec![
+///         (0., 0.), (2., 0.), (2., 0.8), (3., 0.8), (3., 0.), (5., 0.),
+///         (5., 2.), (3., 2.), (3., 1.2), (2., 1.2), (2., 2.), (0., 2.),
+///     ]),
+///     vec![],
 /// );
-/// let p2: MultiPolygon = buffer_polygon_rounded(&p1, 0.2);
+/// let p2 = buffer_polygon_single(&p1, 0.1).unwrap();
+/// assert_eq!(p2.exterior().0.len(), 13);
+///
+/// // Deflating by 0.5 separates the two squares at the ends of the neck, and by 1.5 deflates the polygon away.
+/// assert_eq!(buffer_polygon_single(&p1, -0.5).unwrap_err().0.len(), 2);
+/// assert!(buffer_polygon_single(&p1, -1.5).unwrap_err().0.is_empty());
 /// ```
+#[must_use = "Use the newly buffered Polygon"]
+pub fn buffer_polygon_single(
+    input_polygon: &Polygon,
+    distance: f64,
+) -> Result<Polygon, MultiPolygon> {
+    let mut res = buffer_polygon(input_polygon, distance);
+    if res.0.len() == 1 {
+        Ok(res.0.remove(0))
+    } else {
+        Err(res)
+    }
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon as [`buffer_polygon`] does, taking the polygon by value.
 ///
-/// <details>
-/// <summary style="cursor:pointer"> Result </summary>
-/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex5.svg" style="padding: 25px 30%;"/>
-/// </details>
+/// The coordinate vectors of the rings of the input are reused for the rings of the result (while there are enough of them),
+/// which saves most of the allocations when buffering many small polygons which are not needed afterwards.
 ///
-#[must_use]
-pub fn buffer_polygon_rounded(input_polygon: &Polygon, distance: f64) -> MultiPolygon {
-    let orientation = distance < 0.;
-    let offset_distance = f64::abs(distance);
-    let skel = Skeleton::skeleton_of_polygon(input_polygon, orientation);
-    let vq = skel.get_vertex_queue(offset_distance);
-    skel.apply_vertex_queue_rounded(&vq, offset_distance)
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determines how the input polygon will scale as in [`buffer_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, buffer_polygon_owned};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let expected = buffer_polygon(&p1, -0.2);
+///
+/// assert_eq!(buffer_polygon_owned(p1, -0.2), expected);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_polygon_owned(input_polygon: Polygon, distance: f64) -> MultiPolygon {
+    let mut polygons = vec![input_polygon];
+    BufferOptions::new().buffer_in_place(&mut polygons, distance);
+    MultiPolygon::new(polygons)
 }
 
-/// This function returns the buffered (multi-)polygon of the given multi-polygon. This function creates a miter-joint-like corners around each convex vertex.
+/// This function returns the polygons of the buffer of the given polygon as [`buffer_polygon`] does, without wrapping them into a `MultiPolygon`.
 ///
 /// # Arguments
 ///
-/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determines how the input polygon will scale as in [`buffer_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, buffer_polygon_to_vec};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+///
+/// assert_eq!(buffer_polygon_to_vec(&p1, -0.45), buffer_polygon(&p1, -0.45).0);
+/// ```
+#[must_use = "Use the newly buffered Polygons"]
+pub fn buffer_polygon_to_vec(input_polygon: &Polygon, distance: f64) -> Vec<Polygon> {
+    BufferOptions::new()
+        .buffer(std::slice::from_ref(input_polygon), distance)
+        .0
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon, where the given edges stay in place while all other edges move.
+/// This function creates a miter-joint-like corners around each convex vertex, as [`buffer_polygon`] does.
+///
+/// This is the setback of a building from the edges of its lot except for the street frontage, which it is flush against.
+/// Each fixed edge is first moved by the opposite of `distance`, so that the buffer brings it back; the vertices next to it
+/// lie on its line. Hence the result is the offset of a weighted straight skeleton with zero weights on the fixed edges,
+/// as long as moving the fixed edges does not change the topology of the polygon.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
 /// + `distance`: determine how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
-///     - `+` for to enlarge (to add paddings, make bigger) the given polygon, and,
-///     - `-` for to deflate (to add margins, make smaller) the given polygon
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `fixed_edges`: the indices of the edges to keep in place. The edges of the exterior come first, then those of each interior,
+///   where the edge from the `i`-th to the `i + 1`-th coordinate of a ring is its `i`-th edge, even if it has zero length.
 ///
 /// # Example
 ///
 /// ```
-/// use geo_buf::buffer_multi_polygon;
-/// use geo::{Polygon, MultiPolygon, LineString};
+/// use geo_buf::buffer_polygon_with_fixed_edges;
+/// use geo::{Polygon, LineString};
 ///
 /// let p1 = Polygon::new(
-///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
 /// );
-/// let p2 = Polygon::new(
-///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// // The edge from (0, 0) to (10, 0) is the street frontage.
+/// let p2 = buffer_polygon_with_fixed_edges(&p1, -1., &[0]);
+///
+/// let expected_exterior = LineString::from(vec![(1., 0.), (9., 0.), (9., 9.), (1., 9.), (1., 0.)]);
+/// assert_eq!(&expected_exterior, p2.0[0].exterior());
+///
+/// // A repeated vertex adds an edge of zero length, which is left out.
+/// let p3 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
 /// );
-/// let mp1 = MultiPolygon::new(vec![p1, p2]);
-/// let mp2 = buffer_multi_polygon(&mp1, 1.);
-/// let expected_exterior = LineString::from(vec![(-1., -1.), (3., -1.), (3., 2.), (6., 2.), (6., 6.), (2., 6.), (2., 3.), (-1., 3.), (-1., -1.)]);
+/// assert_eq!(buffer_polygon_with_fixed_edges(&p3, -1., &[0]), p2);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_polygon_with_fixed_edges(
+    input_polygon: &Polygon,
+    distance: f64,
+    fixed_edges: &[usize],
+) -> MultiPolygon {
+    buffer_polygon(
+        &fixed_edges::shift_edges(input_polygon, fixed_edges, -distance),
+        distance,
+    )
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon, where the distance from each edge varies over space
+/// as given by a function of the position.
+/// This function creates a miter-joint-like corners around each convex vertex, as [`buffer_polygon`] does.
 ///
-/// assert_eq!(&expected_exterior, mp2.0[0].exterior())
+/// This is the noise buffer around a road whose width follows the traffic volume along it. The distance is sampled at each
+/// vertex of the polygon and interpolated linearly along each edge: each edge is first tilted by the difference between the
+/// samples at its ends and their mean, then the polygon is buffered by the mean. Hence the result is the offset of a weighted
+/// straight skeleton, as long as tilting the edges does not change the topology of the polygon.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: the distance at each position, whose sign at each vertex determines how the polygon scales there as in
+///   [`buffer_polygon`]. It should vary slowly compared to the size of the edges, and keep its sign.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_polygon_fn;
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 1.), (0., 1.)]), vec![],
+/// );
+/// // The buffer widens from 1 on the left to 2 on the right.
+/// let p2 = buffer_polygon_fn(&p1, |c| 1. + c.x / 10.);
+///
+/// let exterior = p2.0[0].exterior();
+/// assert!(exterior.0.iter().any(|c| (c.x - 12.).abs() < 1e-9));
+/// assert!(exterior.0.iter().any(|c| (c.x + 1.).abs() < 1e-9));
+/// // About a trapezoid of width 13 and heights 2.8 and 5.4.
+/// assert!((p2.unsigned_area() - 53.3).abs() < 0.5);
+///
+/// // A repeated vertex is sampled once, and its edge of zero length is left out.
+/// let p3 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 0.), (10., 1.), (0., 1.)]), vec![],
+/// );
+/// assert_eq!(buffer_polygon_fn(&p3, |c| 1. + c.x / 10.), p2);
 /// ```
 #[must_use = "Use the newly buffered MultiPolygon"]
-pub fn buffer_multi_polygon(input_multi_polygon: &MultiPolygon, distance: f64) -> MultiPolygon {
-    let orientation = distance < 0.;
-    let offset_distance = f64::abs(distance);
-    let skel = Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, orientation);
-    let vq = skel.get_vertex_queue(offset_distance);
-    skel.apply_vertex_queue(&vq, offset_distance)
+pub fn buffer_polygon_fn(input_polygon: &Polygon, distance: impl Fn(Coord) -> f64) -> MultiPolygon {
+    let samples: Vec<f64> = std::iter::once(input_polygon.exterior())
+        .chain(input_polygon.interiors())
+        .flat_map(|ls| ls.lines())
+        .filter(|l| l.start != l.end)
+        .map(|l| distance(l.start))
+        .collect();
+    if samples.is_empty() {
+        return MultiPolygon::new(vec![]);
+    }
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    buffer_polygon(
+        &fixed_edges::shift_edges_along(input_polygon, distance, mean),
+        mean,
+    )
 }
 
-/// This function returns the buffered (multi-)polygon of the given multi-polygon, but creates a rounded corners around each convex vertex.
+/// This function returns the buffered (multi-)polygon of the given polygon, but creates a rounded corners around each convex vertex.
 /// Therefore, distance from each point on border of the buffered polygon to the closest points on the given polygon is (approximately) equal.
-///
 /// Click 'Result' below to see how this function works.
 ///
 /// # Arguments
 ///
-/// + `input_multi_polygon`: `MultiPolygon` to buffer.
-/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determine how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
 ///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
 ///     - `-` to deflate (to add margins, make smaller) the given polygon.
 ///
 /// # Example
 ///
 /// ```
-/// use geo_buf::{buffer_polygon,buffer_multi_polygon};
+/// use geo_buf::{buffer_polygon, buffer_polygon_rounded};
 /// use geo::{Polygon, MultiPolygon, LineString};
 ///
 /// let p1 = Polygon::new(
-///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
-/// );
-/// let p2 = Polygon::new(
-///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
 /// );
-/// let mp1 = MultiPolygon::new(vec![p1, p2]);
-/// let mp2 = buffer_multi_polygon(&mp1, 1.);
+/// let p2: MultiPolygon = buffer_polygon_rounded(&p1, 0.2);
 /// ```
 ///
 /// <details>
 /// <summary style="cursor:pointer"> Result </summary>
-/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex6.svg" style="padding: 25px 30%;"/>
+/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex5.svg" style="padding: 25px 30%;"/>
 /// </details>
 ///
 #[must_use]
-pub fn buffer_multi_polygon_rounded(
-    input_multi_polygon: &MultiPolygon,
-    distance: f64,
-) -> MultiPolygon {
-    let orientation = distance < 0.;
-    let offset_distance = f64::abs(distance);
-    let skel = Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, orientation);
-    let vq = skel.get_vertex_queue(offset_distance);
-    skel.apply_vertex_queue_rounded(&vq, offset_distance)
+pub fn buffer_polygon_rounded(input_polygon: &Polygon, distance: f64) -> MultiPolygon {
+    BufferOptions::new()
+        .rounded(true)
+        .buffer(std::slice::from_ref(input_polygon), distance)
 }
 
-// pub fn skeleton_of_polygon(input_polygon: &Polygon, orientation: bool) -> Skeleton{
-//     Skeleton::skeleton_of_polygon(input_polygon, orientation)
-// }
+/// This function returns the buffered polygons of the given polygon with rounded corners as [`buffer_polygon_rounded`] does, but keeps
+/// each rounded corner as a true [`CircularArc`] instead of segmentizing it, for CAD/CAM consumers.
+///
+/// [`CurvedPolygon::approximate`] turns the result into ordinary polygons within a given tolerance. The result is the raw wavefront of the
+/// straight skeleton, without the postprocessing of [`BufferOptions`]. With the `kurbo` feature, `CurvedPolygon::to_bez_path` turns it
+/// into a path of cubic Bézier curves for vector graphics tools.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon_curved, CurveSegment};
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let p2 = buffer_polygon_curved(&p1, 1.);
+/// let arcs: Vec<_> = p2[0].exterior.segments.iter().filter_map(|s| match s {
+///     CurveSegment::Arc(arc) => Some(arc),
+///     CurveSegment::Line { .. } => None,
+/// }).collect();
+///
+/// assert_eq!(arcs.len(), 4);
+/// assert!(arcs.iter().all(|arc| (arc.radius - 1.).abs() < 1e-9));
+/// assert!(arcs.iter().all(|arc| (arc.end_angle - arc.start_angle - std::f64::consts::FRAC_PI_2).abs() < 1e-9));
+/// let area = p2[0].approximate(1e-6).unsigned_area();
+/// assert!((area - (5. + std::f64::consts::PI)).abs() < 1e-5);
+/// ```
+#[must_use]
+pub fn buffer_polygon_curved(input_polygon: &Polygon, distance: f64) -> Vec<CurvedPolygon> {
+    curve::buffer(std::slice::from_ref(input_polygon), distance)
+}
 
-// pub fn skeleton_of_multi_polygon(input_multi_polygon: &MultiPolygon, orientation: bool) -> Skeleton{
-//     Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, orientation)
-// }
+/// This function returns the buffered polygons of the given multi-polygon with rounded corners kept as true [`CircularArc`]s.
+/// See [`buffer_polygon_curved`] for the details.
+#[must_use]
+pub fn buffer_multi_polygon_curved(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+) -> Vec<CurvedPolygon> {
+    curve::buffer(&input_multi_polygon.0, distance)
+}
 
-/// This function returns a set of `LineSting` which represents an instantiated straight skeleton of the given polygon.
-/// Each segment of the straight skeleton is represented as a single `LineString`, and the returned vector is a set of these `LineString`s.
-/// If either endpoints of a `LineString` is infinitely far from the other, then this `LineString` will be clipped to one which has shorter length.
-/// The order of these `LineString`s is arbitrary. (There is no gauranteed order on segments of the straight skeleton.)
-///
-/// Click 'Result' below to see how this function works.
+/// This function returns the buffered (multi-)polygon of the given polygon, with the given [`BufferOptions`].
 ///
 /// # Arguments
 ///
-/// + `input_polygon`: `Polygon` to get the straight skeleton.
-/// + `orientation`: determines the region where the straight skeleton created. The value of this `boolean` variable will be:
-///     * `true` to create the staright skeleton on the inward region of the polygon, and,
-///     * `false` to create on the outward region of the polygon.
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determine how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `options`: determines how the result is built. See [`BufferOptions`] for each option.
 ///
 /// # Example
 ///
 /// ```
-/// use geo_buf::buffer_polygon;
-/// use geo_buf::skeleton_of_polygon_to_linestring;
+/// use geo_buf::{buffer_polygon_with, BufferOptions};
 /// use geo::{Polygon, MultiPolygon, LineString};
 ///
 /// let p1 = Polygon::new(
-///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
 /// );
-/// let ls1: Vec<LineString> = skeleton_of_polygon_to_linestring(&p1, true);
+/// let p2: MultiPolygon = buffer_polygon_with(&p1, 0.2, &BufferOptions::new().rounded(true));
+/// ```
+#[must_use = "Use the newly buffered Polygon"]
+pub fn buffer_polygon_with(
+    input_polygon: &Polygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> MultiPolygon {
+    options.buffer(std::slice::from_ref(input_polygon), distance)
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon with the given [`BufferOptions`] as [`buffer_polygon_with`] does,
+/// or an error if the computation is interrupted through [`BufferOptions::cancel_token`] or [`BufferOptions::deadline`].
+///
+/// # Errors
+///
+/// Returns [`Error::Cancelled`] if the token of the options is cancelled, and [`Error::TimedOut`] if the deadline of the options passes,
+/// before the computation ends.
+///
+/// # Example
+///
 /// ```
+/// use std::time::{Duration, Instant};
+/// use geo_buf::{try_buffer_polygon_with, BufferOptions};
+/// use geo::{Polygon, LineString};
 ///
-/// <details>
-/// <summary style="cursor:pointer"> Result </summary>
-/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex7.svg" style="padding: 25px 30%;"/>
-/// </details>
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let options = BufferOptions::new().deadline(Instant::now() + Duration::from_secs(1));
+/// let p2 = try_buffer_polygon_with(&p1, 0.2, &options).unwrap();
 ///
-pub fn skeleton_of_polygon_to_linestring(
+/// assert_eq!(p2.0.len(), 1);
+/// ```
+pub fn try_buffer_polygon_with(
     input_polygon: &Polygon,
-    orientation: bool,
-) -> Vec<LineString> {
-    Skeleton::skeleton_of_polygon(input_polygon, orientation).to_linestring()
+    distance: f64,
+    options: &BufferOptions,
+) -> Result<MultiPolygon, Error> {
+    options.try_buffer(std::slice::from_ref(input_polygon), distance)
 }
 
-/// This function returns a set of `LineSting` which represents an instantiated straight skeleton of the given multi-polygon.
-/// Each segment of the straight skeleton is represented as a single `LineString`, and the returned vector is a set of these `LineString`s.
-/// If either endpoints of a `LineString` is infinitely far from the other, then this `LineString` will be clipped to one which has shorter length.
-/// The order of these `LineString`s is arbitrary. (There is no gauranteed order on segments of the straight skeleton.)
+/// This function returns the buffered (multi-)polygon of the given polygon with the given [`BufferOptions`] as [`buffer_polygon_with`] does,
+/// together with a [`RepairReport`] of the validation of the result.
 ///
-/// Click 'Result' below to see how this function works.
+/// The result is always validated, but only repaired if [`BufferOptions::repair`] is set.
 ///
-/// # Arguments
+/// # Example
 ///
-/// + `input_multi_polygon`: `MultiPolygon` to get the straight skeleton.
-/// + `orientation`: determines the region where the straight skeleton created. The value of this `boolean` variable will be:
-///     * `true` to create the staright skeleton on the inward region of the polygon, and,
-///     * `false` to create on the outward region of the polygon.
+/// ```
+/// use geo_buf::{buffer_polygon_with_report, BufferOptions};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let (p2, report) = buffer_polygon_with_report(&p1, -0.2, &BufferOptions::new());
+///
+/// assert!(report.is_valid());
+/// ```
+pub fn buffer_polygon_with_report(
+    input_polygon: &Polygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, RepairReport) {
+    options.buffer_with_report(std::slice::from_ref(input_polygon), distance, true)
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon with the given [`BufferOptions`] as [`buffer_polygon_with`] does,
+/// together with [`Diagnostics`] of the result: the [`Warning`]s about it and the [`RepairReport`] of its validation.
+///
+/// The result is always validated, but only repaired if [`BufferOptions::repair`] is set.
 ///
 /// # Example
 ///
 /// ```
-/// use geo_buf::{buffer_polygon, skeleton_of_multi_polygon_to_linestring};
-/// use geo::{Polygon, MultiPolygon, LineString};
+/// use geo_buf::{buffer_polygon_with_diagnostics, BufferOptions, BufferParams, EndCapStyle, JoinStyle, Warning};
+/// use geo::{Polygon, LineString};
 ///
 /// let p1 = Polygon::new(
-///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+///     LineString::from(vec![(0., 0.), (10., 0.), (0., 1.)]), vec![],
 /// );
-/// let p2 = Polygon::new(
-///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
-/// );
-/// let mp1 = MultiPolygon::new(vec![p1, p2]);
-/// let ls: Vec<LineString> = skeleton_of_multi_polygon_to_linestring(&mp1, false);
+/// let options = BufferParams::geos_like(8, EndCapStyle::Round, JoinStyle::Mitre, 5.).to_options();
+/// let (_, diagnostics) = buffer_polygon_with_diagnostics(&p1, 1., &options);
+///
+/// // The mitre at the sharp vertex at (10, 0) reaches farther than 5 times the distance.
+/// assert!(diagnostics.repair.is_valid());
+/// assert_eq!(diagnostics.warnings, vec![Warning::MiterLimited { vertex: (10., 0.).into() }]);
+/// assert_eq!(diagnostics.warnings[0].to_string(), "miter limited at vertex (10, 0)");
+///
+/// let (p2, diagnostics) = buffer_polygon_with_diagnostics(&p1, 1., &BufferOptions::new().min_output_area(100.));
+/// assert!(p2.0.is_empty());
+/// assert!(matches!(diagnostics.warnings[..], [Warning::PolygonBelowMinArea { polygon: 0, .. }]));
 /// ```
+pub fn buffer_polygon_with_diagnostics(
+    input_polygon: &Polygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Diagnostics) {
+    options.buffer_with_diagnostics(std::slice::from_ref(input_polygon), distance, true)
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon with the given [`BufferOptions`] as [`buffer_polygon_with`] does,
+/// together with the [`VertexOrigin`] of each of its vertices: the input vertex or the input edges it was generated from.
 ///
-/// <details>
-/// <summary style="cursor:pointer"> Result </summary>
-/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex8.svg" style="padding: 25px 30%;"/>
-/// </details>
+/// The origins are grouped by polygon and by ring (the exterior first) as the coordinates of the result, including the closing vertex of each ring.
+/// This helps to transfer the attributes of the input onto the result, and to find which part of the input a wrong-looking part of the result comes from.
+/// Vertices which the postprocessing creates, and all vertices when the buffer is not computed along the straight skeleton, are [`VertexOrigin::Unknown`].
 ///
-pub fn skeleton_of_multi_polygon_to_linestring(
-    input_multi_polygon: &MultiPolygon,
-    orientation: bool,
-) -> Vec<LineString> {
-    Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, orientation).to_linestring()
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon_with_provenance, BufferOptions, VertexOrigin};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let (p2, origins) = buffer_polygon_with_provenance(&p1, 1., &BufferOptions::new());
+///
+/// assert_eq!(origins[0][0].len(), p2.0[0].exterior().0.len());
+/// let i = p2.0[0].exterior().0.iter().position(|c| *c == (-1., -1.).into()).unwrap();
+/// assert_eq!(origins[0][0][i], VertexOrigin::Miter { vertex: 0 });
+///
+/// let (p3, origins) = buffer_polygon_with_provenance(&p1, 1., &BufferOptions::new().rounded(true));
+/// assert!(origins[0][0].contains(&VertexOrigin::Arc { vertex: 1 }));
+/// assert!(!origins[0][0].contains(&VertexOrigin::Unknown));
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_polygon_with_provenance(
+    input_polygon: &Polygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Vec<Vec<Vec<VertexOrigin>>>) {
+    options.buffer_with_provenance(std::slice::from_ref(input_polygon), distance)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// together with the [`VertexOrigin`] of each of its vertices. See [`buffer_polygon_with_provenance`] for the details.
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon_with_provenance(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Vec<Vec<Vec<VertexOrigin>>>) {
+    options.buffer_with_provenance(&input_multi_polygon.0, distance)
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon as [`buffer_polygon`] does, except that the area covered by
+/// the given mask is removed from the result. (E.g. the buffer of a parcel which must not expand into a lake.)
+///
+/// Only the members of the mask near the result are taken into account, so a large mask layer can be passed as a whole.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `mask`: `MultiPolygon` which the result must not overlap.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_polygon_masked;
+/// use geo::{Area, Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let lake = Polygon::new(
+///     LineString::from(vec![(3., -5.), (9., -5.), (9., 5.), (3., 5.)]), vec![],
+/// );
+/// let p2 = buffer_polygon_masked(&p1, 2., &MultiPolygon::new(vec![lake]));
+///
+/// assert!((p2.unsigned_area() - 30.).abs() < 1e-9);
+/// ```
+#[must_use = "Use the newly buffered Polygon"]
+pub fn buffer_polygon_masked(
+    input_polygon: &Polygon,
+    distance: f64,
+    mask: &MultiPolygon,
+) -> MultiPolygon {
+    let mut res = buffer_polygon(input_polygon, distance);
+    postprocess::mask(&mut res, mask);
+    postprocess::orient(&mut res, WindingOrder::CounterClockwise);
+    res
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon. This function creates a miter-joint-like corners around each convex vertex.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determine how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` for to enlarge (to add paddings, make bigger) the given polygon, and,
+///     - `-` for to deflate (to add margins, make smaller) the given polygon
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_multi_polygon;
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let mp2 = buffer_multi_polygon(&mp1, 1.);
+/// let expected_exterior = LineString::from(vec![(-1., -1.), (3., -1.), (3., 2.), (6., 2.), (6., 6.), (2., 6.), (2., 3.), (-1., 3.), (-1., -1.)]);
+///
+/// assert_eq!(&expected_exterior, mp2.0[0].exterior())
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon(input_multi_polygon: &MultiPolygon, distance: f64) -> MultiPolygon {
+    BufferOptions::new().buffer(&input_multi_polygon.0, distance)
+}
+
+/// This function buffers the given multi-polygon as [`buffer_multi_polygon`] does, and replaces it with the result.
+///
+/// The allocations of the input are reused for the result where possible: the vector of its polygons, and the coordinate vectors of its rings
+/// for the rings of the result (while there are enough of them). This saves most of the allocations when buffering many small multi-polygons.
+///
+/// # Arguments
+///
+/// + `multi_polygon`: `MultiPolygon` to buffer, which is replaced by the result.
+/// + `distance`: determines how the input multi-polygon will scale as in [`buffer_multi_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon, buffer_multi_polygon_in_place};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mut mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let expected = buffer_multi_polygon(&mp1, 1.);
+/// buffer_multi_polygon_in_place(&mut mp1, 1.);
+///
+/// assert_eq!(mp1, expected);
+/// ```
+pub fn buffer_multi_polygon_in_place(multi_polygon: &mut MultiPolygon, distance: f64) {
+    BufferOptions::new().buffer_in_place(&mut multi_polygon.0, distance);
+}
+
+/// This function returns the polygons of the buffer of the given multi-polygon as [`buffer_multi_polygon`] does, without wrapping them
+/// into a `MultiPolygon`.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determines how the input multi-polygon will scale as in [`buffer_multi_polygon`].
+#[must_use = "Use the newly buffered Polygons"]
+pub fn buffer_multi_polygon_to_vec(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+) -> Vec<Polygon> {
+    BufferOptions::new()
+        .buffer(&input_multi_polygon.0, distance)
+        .0
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon, but creates a rounded corners around each convex vertex.
+/// Therefore, distance from each point on border of the buffered polygon to the closest points on the given polygon is (approximately) equal.
+///
+/// Click 'Result' below to see how this function works.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon,buffer_multi_polygon};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let mp2 = buffer_multi_polygon(&mp1, 1.);
+/// ```
+///
+/// <details>
+/// <summary style="cursor:pointer"> Result </summary>
+/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex6.svg" style="padding: 25px 30%;"/>
+/// </details>
+///
+#[must_use]
+pub fn buffer_multi_polygon_rounded(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+) -> MultiPolygon {
+    BufferOptions::new()
+        .rounded(true)
+        .buffer(&input_multi_polygon.0, distance)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon, with the given [`BufferOptions`].
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `options`: determines how the result is built. See [`BufferOptions`] for each option.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_with, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let mp2 = buffer_multi_polygon_with(&mp1, 1., &BufferOptions::new().rounded(true).min_segment_length(0.01));
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon_with(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> MultiPolygon {
+    options.buffer(&input_multi_polygon.0, distance)
+}
+
+/// This function returns the polygons of the buffer of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// collected into any collection of polygons, e.g. a `Vec<Polygon>` or a `VecDeque<Polygon>`.
+///
+/// Collecting into a `Vec<Polygon>` reuses the vector of the polygons of the result, so nothing is copied.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determines how the input multi-polygon will scale as in [`buffer_multi_polygon`].
+/// + `options`: determines how the result is built. See [`BufferOptions`] for each option.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::VecDeque;
+/// use geo_buf::{buffer_multi_polygon_collect, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let polygons: VecDeque<Polygon> = buffer_multi_polygon_collect(&mp1, -0.5, &BufferOptions::new());
+///
+/// assert_eq!(polygons.len(), 2);
+/// ```
+#[must_use = "Use the newly buffered Polygons"]
+pub fn buffer_multi_polygon_collect<C: FromIterator<Polygon>>(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> C {
+    options
+        .buffer(&input_multi_polygon.0, distance)
+        .0
+        .into_iter()
+        .collect()
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// or an error if the computation is interrupted through [`BufferOptions::cancel_token`] or [`BufferOptions::deadline`].
+///
+/// # Errors
+///
+/// Returns [`Error::Cancelled`] if the token of the options is cancelled, and [`Error::TimedOut`] if the deadline of the options passes,
+/// before the computation ends.
+pub fn try_buffer_multi_polygon_with(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> Result<MultiPolygon, Error> {
+    options.try_buffer(&input_multi_polygon.0, distance)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// together with a [`RepairReport`] of the validation of the result.
+///
+/// The result is always validated, but only repaired if [`BufferOptions::repair`] is set.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_with_report, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let (mp2, report) = buffer_multi_polygon_with_report(&mp1, 1., &BufferOptions::new().repair(true));
+///
+/// assert!(report.is_valid());
+/// assert_eq!(mp2.0.len(), 1);
+/// ```
+pub fn buffer_multi_polygon_with_report(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, RepairReport) {
+    options.buffer_with_report(&input_multi_polygon.0, distance, true)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// together with [`Diagnostics`] of the result, as [`buffer_polygon_with_diagnostics`] does.
+pub fn buffer_multi_polygon_with_diagnostics(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Diagnostics) {
+    options.buffer_with_diagnostics(&input_multi_polygon.0, distance, true)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// calling `progress` with the [`Progress`] of the construction of the straight skeleton every few hundred events, and once at the end.
+///
+/// This lets GUI applications show a progress bar when buffering large multi-polygons.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_with_progress, BufferOptions, Progress};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1]);
+/// let mut last = None;
+/// let mp2 = buffer_multi_polygon_with_progress(&mp1, -0.5, &BufferOptions::new(), |p| last = Some(p));
+///
+/// assert_eq!(last.unwrap().queued_events, 0);
+/// assert_eq!(mp2.0.len(), 1);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon_with_progress(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+    mut progress: impl FnMut(Progress),
+) -> MultiPolygon {
+    options.buffer_with_progress(&input_multi_polygon.0, distance, &mut progress)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// together with the [`Collapse`] of each member of the multi-polygon which vanished entirely, in the order of the members.
+///
+/// Only deflation makes members vanish, so the list is empty for non-negative distances. E.g. the lots which cannot accommodate
+/// a setback of 5 meters are the collapses of deflating the lots by 5 meters.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_with_collapses, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(5., 0.), (7., 0.), (7., 1.), (5., 1.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let (mp2, collapses) = buffer_multi_polygon_with_collapses(&mp1, -1., &BufferOptions::new());
+///
+/// assert_eq!(mp2.0.len(), 1);
+/// assert_eq!(collapses.len(), 1);
+/// assert_eq!(collapses[0].index, 1);
+/// assert!((collapses[0].distance - 0.5).abs() < 1e-9);
+/// assert!((collapses[0].location.y - 0.5).abs() < 1e-9);
+/// ```
+pub fn buffer_multi_polygon_with_collapses(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Vec<Collapse>) {
+    collapse::buffer_with_collapses(options, &input_multi_polygon.0, distance)
+}
+
+/// This function returns the buffer of each member of the given multi-polygon with the given [`BufferOptions`], in the order of the members,
+/// keeping the members which vanish entirely as the lines (or the point) they collapse to rather than dropping them.
+///
+/// Each member is buffered on its own, so the buffers of the members do not merge when inflating. A member which survives the buffer,
+/// even in part, is a [`Geometry::MultiPolygon`]. A member which vanishes is the part of its straight skeleton between the nodes,
+/// i.e. its spine, as a [`Geometry::MultiLineString`], or the point where it vanished as a [`Geometry::Point`] if its spine is empty.
+/// E.g. a narrow parcel deflated by a setback it cannot accommodate is still represented by its center line.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_keeping_collapsed, BufferOptions};
+/// use geo::{Geometry, Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(5., 0.), (9., 0.), (9., 1.), (5., 1.)]), vec![],
+/// );
+/// let p3 = Polygon::new(
+///     LineString::from(vec![(10., 0.), (11., 0.), (11., 1.), (10., 1.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2, p3]);
+/// let res = buffer_multi_polygon_keeping_collapsed(&mp1, -1., &BufferOptions::new());
+///
+/// assert!(matches!(res[0], Geometry::MultiPolygon(_)));
+/// // The rectangle collapses to its center line, and the square to its center.
+/// let Geometry::MultiLineString(spine) = &res[1] else { panic!() };
+/// assert_eq!(spine.0, vec![LineString::from(vec![(5.5, 0.5), (8.5, 0.5)])]);
+/// assert_eq!(res[2], Geometry::Point((10.5, 0.5).into()));
+/// ```
+#[must_use = "Use the newly buffered geometries"]
+pub fn buffer_multi_polygon_keeping_collapsed(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> Vec<Geometry> {
+    collapse::buffer_keeping_collapsed(options, &input_multi_polygon.0, distance)
+}
+
+/// This function returns the history of the given polygon deflated until it vanishes, as a tree of [`CollapseComponent`]s:
+/// the polygon itself, then each part it splits into, in the order they split off, with the deflation distances at which they split or vanish.
+///
+/// This is the erosion hierarchy of the shape: the longer a part lives, the more prominent the ridge or branch it stands for.
+/// The result is empty if the polygon is empty.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::shrink_until_collapse;
+/// use geo::{Polygon, LineString};
+///
+/// // Two squares joined by a corridor of width 1.
+/// let p1 = Polygon::new(
+///     LineString::from(vec![
+///         (0., 0.), (4., 0.), (4., 1.5), (6., 1.5), (6., 0.), (10., 0.),
+///         (10., 4.), (6., 4.), (6., 2.5), (4., 2.5), (4., 4.), (0., 4.),
+///     ]),
+///     vec![],
+/// );
+/// let components = shrink_until_collapse(&p1);
+///
+/// assert_eq!(components.len(), 3);
+/// assert_eq!(components[0].children, vec![1, 2]);
+/// assert!((components[0].death - 0.5).abs() < 1e-9);
+/// for c in &components[1..] {
+///     assert_eq!(c.parent, Some(0));
+///     assert!((c.birth - 0.5).abs() < 1e-9 && (c.death - 2.).abs() < 1e-9);
+/// }
+/// ```
+#[must_use]
+pub fn shrink_until_collapse(input_polygon: &Polygon) -> Vec<CollapseComponent> {
+    collapse::shrink_until_collapse(input_polygon)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon with the given [`BufferOptions`] as [`buffer_multi_polygon_with`] does,
+/// together with the indices of the members of the given multi-polygon each polygon of the result comes from, in increasing order.
+///
+/// When inflating, a polygon of the result comes from several members if their buffers merged, e.g. the facilities a merged risk zone
+/// stems from. When deflating, each polygon of the result comes from the one member it lies in.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_with_sources, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString, Translate};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1.clone(), p1.translate(10., 0.), p1.translate(2., 0.)]);
+/// let (mp2, sources) = buffer_multi_polygon_with_sources(&mp1, 1., &BufferOptions::new());
+///
+/// assert_eq!(mp2.0.len(), 2);
+/// assert_eq!(sources, vec![vec![0, 2], vec![1]]);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon_with_sources(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> (MultiPolygon, Vec<Vec<usize>>) {
+    let res = options.buffer(&input_multi_polygon.0, distance);
+    let sources = postprocess::sources(&res, &input_multi_polygon.0);
+    (res, sources)
+}
+
+/// This function returns the buffered (multi-)polygon of each member of the given multi-polygon, with the given [`BufferOptions`].
+/// Unlike [`buffer_multi_polygon_with`], members which grow into each other are not unified.
+///
+/// The `i`-th `MultiPolygon` of the result is the buffered `i`-th member of `input_multi_polygon`, which is empty if the member vanished.
+/// With the `rayon` feature, the members are buffered in parallel, on the [thread pool](BufferOptions::thread_pool) of `options` if it has one.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `options`: determines how the result is built. See [`BufferOptions`] for each option.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon_individual, BufferOptions};
+/// use geo::{Area, Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 0.), (5., 0.), (5., 2.), (3., 2.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let mp2: Vec<MultiPolygon> = buffer_multi_polygon_individual(&mp1, 1., &BufferOptions::new());
+///
+/// assert_eq!(mp2.len(), 2);
+/// assert!((mp2[0].unsigned_area() - 16.).abs() < 1e-9);
+/// assert!((mp2[1].unsigned_area() - 16.).abs() < 1e-9);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygons"]
+pub fn buffer_multi_polygon_individual(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+    options: &BufferOptions,
+) -> Vec<MultiPolygon> {
+    #[cfg(feature = "rayon")]
+    {
+        input_multi_polygon.0.par_buffer_with(distance, options)
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut builder = options.skeleton_builder();
+        input_multi_polygon
+            .0
+            .iter()
+            .map(|p| options.buffer_with_builder(&mut builder, std::slice::from_ref(p), distance))
+            .collect()
+    }
+}
+
+/// This function returns the union of the members of the given multi-polygon, each buffered by its own distance,
+/// e.g. the risk zones of facilities of which each has its own radius.
+///
+/// Members with the same distance are buffered together as [`buffer_multi_polygon`] does, and the results of the different
+/// distances are unified, so that overlapping zones merge into one polygon. If all distances are equal, the result is the
+/// same as the one of [`buffer_multi_polygon`].
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to buffer.
+/// + `distances`: the distance of each member of `input_multi_polygon`, in the same order. The sign of each distance will be:
+///     - `+` to inflate (to add paddings, make bigger) the member, and,
+///     - `-` to deflate (to add margins, make smaller) the member.
+///
+/// # Errors
+///
+/// Returns [`Error::MismatchedDistances`] if there is not exactly one distance per member.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_multi_polygon_per;
+/// use geo::{Area, Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(4., 0.), (6., 0.), (6., 2.), (4., 2.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// // The first square grows into the second one, which only grows by a half.
+/// let mp2 = buffer_multi_polygon_per(&mp1, &[2., 0.5]).unwrap();
+///
+/// assert_eq!(mp2.0.len(), 1);
+/// assert!((mp2.unsigned_area() - (36. + 9. - 1.5)).abs() < 1e-9);
+/// assert!(buffer_multi_polygon_per(&mp1, &[1.]).is_err());
+/// ```
+pub fn buffer_multi_polygon_per(
+    input_multi_polygon: &MultiPolygon,
+    distances: &[f64],
+) -> Result<MultiPolygon, Error> {
+    if distances.len() != input_multi_polygon.0.len() {
+        return Err(Error::MismatchedDistances(
+            input_multi_polygon.0.len(),
+            distances.len(),
+        ));
+    }
+    Ok(BufferOptions::new().buffer_per(&input_multi_polygon.0, distances))
+}
+
+/// This function returns the buffered (multi-)polygons of a stream of polygons, buffering them a few at a time so that the whole
+/// input never needs to be held in memory, e.g. for the parcels of a whole country read from a file.
+///
+/// The polygons are buffered in groups: polygons whose bounding rectangles, expanded by `distance`, intersect (directly or through
+/// other polygons) form one group, which is buffered as [`buffer_multi_polygon`] does, so that their results are merged. Each item of
+/// the returned iterator is the result of one group; groups which vanish entirely yield nothing.
+///
+/// The polygons should come sorted by the least x-coordinate of their bounding rectangles. A group is yielded as soon as a polygon
+/// starts to the right of it, so only the groups which the current polygon may still join are kept in memory. The result of a polygon
+/// which comes out of order is still correct by itself, but it may overlap the results of groups which were yielded before it.
+///
+/// # Arguments
+///
+/// + `input_polygons`: the polygons to buffer, sorted by the least x-coordinate of their bounding rectangles.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_polygons_iter;
+/// use geo::{Area, Polygon, MultiPolygon, LineString};
+///
+/// let square = |x: f64| Polygon::new(
+///     LineString::from(vec![(x, 0.), (x + 2., 0.), (x + 2., 2.), (x, 2.)]), vec![],
+/// );
+/// // The first two squares grow into each other, while the third one stays apart.
+/// let results: Vec<MultiPolygon> = buffer_polygons_iter([square(0.), square(3.), square(10.)], 1.).collect();
+///
+/// assert_eq!(results.len(), 2);
+/// assert_eq!(results[0].0.len(), 1);
+/// assert!((results[0].unsigned_area() - 28.).abs() < 1e-9);
+/// assert!((results[1].unsigned_area() - 16.).abs() < 1e-9);
+/// ```
+#[must_use = "Use the iterator of the newly buffered MultiPolygons"]
+pub fn buffer_polygons_iter<I>(
+    input_polygons: I,
+    distance: f64,
+) -> impl Iterator<Item = MultiPolygon>
+where
+    I: IntoIterator<Item = Polygon>,
+{
+    stream::BufferPolygons::new(input_polygons.into_iter(), distance)
+}
+
+/// This function renders the given polygon, its straight skeleton and its buffers by each of the given distances into an SVG document,
+/// for debugging. Requires the `debug_svg` feature.
+///
+/// The polygon is filled in gray, the skeleton is drawn in red and the buffers are drawn in blue. The inward skeleton is drawn
+/// if some distance is negative, and the outward one if some distance is non-negative.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to render.
+/// + `distances`: the distances of the buffers to render, as in [`buffer_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::debug_svg_of_polygon;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let svg: String = debug_svg_of_polygon(&p1, &[-0.2, -0.45, 0.5]);
+///
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[cfg(feature = "debug_svg")]
+pub fn debug_svg_of_polygon(input_polygon: &Polygon, distances: &[f64]) -> String {
+    debug_svg::render(&MultiPolygon::new(vec![input_polygon.clone()]), distances)
+}
+
+/// This function renders the given multi-polygon, its straight skeleton and its buffers by each of the given distances into an SVG document,
+/// for debugging. Requires the `debug_svg` feature. See [`debug_svg_of_polygon`] for the details.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to render.
+/// + `distances`: the distances of the buffers to render, as in [`buffer_multi_polygon`].
+#[cfg(feature = "debug_svg")]
+pub fn debug_svg_of_multi_polygon(input_multi_polygon: &MultiPolygon, distances: &[f64]) -> String {
+    debug_svg::render(input_multi_polygon, distances)
+}
+
+/// This function renders a frame of the construction of a straight skeleton (see [`Skeleton::frames`]) into an SVG document,
+/// for debugging. Requires the `debug_svg` feature.
+///
+/// The wavefront is drawn in blue and the location of the event is marked in red, with the type and the time of the event as its tooltip.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{debug_svg_of_frame, skeleton_of_polygon, Side};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let svgs: Vec<String> = skeleton_of_polygon(&p1, Side::Interior).frames().iter().map(debug_svg_of_frame).collect();
+/// ```
+#[cfg(feature = "debug_svg")]
+pub fn debug_svg_of_frame(frame: &skeleton::EventFrame) -> String {
+    debug_svg::render_frame(frame)
+}
+
+/// This function writes the given polygon, its straight skeleton and its buffers by each of the given distances into a DXF document
+/// (AutoCAD R12), for CAD tools. Requires the `dxf` feature.
+///
+/// The polygon goes to the `INPUT` layer, the skeleton to the `SKELETON` layer as lines, and the buffer by the `i`-th distance to the
+/// `OFFSET_i` layer, each ring as a closed polyline. The inward skeleton is written if some distance is negative, and the outward one
+/// if some distance is non-negative.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to write.
+/// + `distances`: the distances of the buffers to write, as in [`buffer_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::dxf_of_polygon;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let dxf: String = dxf_of_polygon(&p1, &[-0.2, -0.45]);
+///
+/// assert!(dxf.contains("OFFSET_1"));
+/// assert!(dxf.ends_with("EOF\n"));
+/// ```
+#[cfg(feature = "dxf")]
+pub fn dxf_of_polygon(input_polygon: &Polygon, distances: &[f64]) -> String {
+    dxf::render(&MultiPolygon::new(vec![input_polygon.clone()]), distances)
+}
+
+/// This function writes the given multi-polygon, its straight skeleton and its buffers by each of the given distances into a DXF
+/// document (AutoCAD R12), for CAD tools. Requires the `dxf` feature. See [`dxf_of_polygon`] for the details.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to write.
+/// + `distances`: the distances of the buffers to write, as in [`buffer_multi_polygon`].
+#[cfg(feature = "dxf")]
+pub fn dxf_of_multi_polygon(input_multi_polygon: &MultiPolygon, distances: &[f64]) -> String {
+    dxf::render(input_multi_polygon, distances)
+}
+
+/// This function buffers every polygon of the given GeoArrow polygon array (`geoarrow.polygon`) and returns the results as a
+/// record batch with a single GeoArrow multi-polygon column (`geoarrow.multipolygon`) with interleaved coordinates, for
+/// analytics pipelines on Arrow or Parquet. Requires the `arrow` feature, which builds on `arrow-array` and `arrow-schema` 58.
+///
+/// The polygons are buffered in parallel with the `rayon` feature. Null slots stay null, and empty polygons give empty
+/// multi-polygons. The input coordinates may be interleaved or separated, and only their first two dimensions are read.
+/// The column is named `geometry`, and its field has the extension name `geoarrow.multipolygon` but no extension metadata:
+/// the CRS of the input, which belongs to the field of the input array, is left to the caller.
+///
+/// # Arguments
+///
+/// + `input_array`: the polygons to buffer, as a list of rings, each of them a list of coordinates.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `options`: the options of the buffers, see [`BufferOptions`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArrowArray`] if `input_array` does not hold polygons with `Float64` coordinates.
+///
+/// # Example
+///
+/// ```
+/// use arrow_array::builder::{FixedSizeListBuilder, Float64Builder, ListBuilder};
+/// use arrow_array::Array;
+/// use geo_buf::{buffer_arrow_polygons, BufferOptions};
+///
+/// let mut builder = ListBuilder::new(ListBuilder::new(FixedSizeListBuilder::new(Float64Builder::new(), 2)));
+/// for (x, y) in [(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)] {
+///     builder.values().values().values().append_slice(&[x, y]);
+///     builder.values().values().append(true);
+/// }
+/// builder.values().append(true);
+/// builder.append(true);
+/// builder.append_null();
+///
+/// let buffered = buffer_arrow_polygons(&builder.finish(), -0.25, &BufferOptions::new()).unwrap();
+///
+/// assert_eq!(buffered.num_rows(), 2);
+/// assert!(buffered.column(0).is_null(1));
+/// assert_eq!(buffered.schema().field(0).extension_type_name(), Some("geoarrow.multipolygon"));
+/// ```
+#[cfg(feature = "arrow")]
+pub fn buffer_arrow_polygons(
+    input_array: &arrow_array::ListArray,
+    distance: f64,
+    options: &BufferOptions,
+) -> Result<arrow_array::RecordBatch, Error> {
+    let polygons = interop::polygons_of_arrow(input_array)?;
+    let buffer = |builder: &mut SkeletonBuilder, p: &Option<Polygon>| {
+        p.as_ref()
+            .map(|p| options.buffer_with_builder(builder, std::slice::from_ref(p), distance))
+    };
+    #[cfg(feature = "rayon")]
+    let buffered: Vec<_> = {
+        use rayon::prelude::*;
+
+        options.install(|| {
+            polygons
+                .par_iter()
+                .map_init(|| options.skeleton_builder(), buffer)
+                .collect()
+        })
+    };
+    #[cfg(not(feature = "rayon"))]
+    let buffered: Vec<_> = {
+        let mut builder = options.skeleton_builder();
+        polygons.iter().map(|p| buffer(&mut builder, p)).collect()
+    };
+    Ok(interop::arrow_of_multi_polygons(&buffered))
+}
+
+/// This function returns the given multi-polygon (e.g. a buffer) as WKB, to insert it into a spatial database such as PostGIS
+/// without an intermediate text format. Requires the `wkb` feature.
+///
+/// The bytes are little-endian and describe a `MULTIPOLYGON` without Z or M coordinates, with closed rings as in the input.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, wkb_of_multi_polygon};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let wkb = wkb_of_multi_polygon(&buffer_polygon(&p1, -0.25));
+///
+/// // The byte order, the type, one polygon, of one ring, of 5 points, of 16 bytes each.
+/// assert_eq!(&wkb[..5], &[1, 6, 0, 0, 0]);
+/// assert_eq!(wkb.len(), 1 + 4 + 4 + (1 + 4 + 4) + (4 + 5 * 16));
+/// ```
+#[cfg(feature = "wkb")]
+#[must_use]
+pub fn wkb_of_multi_polygon(input_multi_polygon: &MultiPolygon) -> Vec<u8> {
+    interop::wkb_of_multi_polygon(input_multi_polygon)
+}
+
+/// This function parses the given WKT polygon or multi-polygon, buffers it as [`buffer_multi_polygon`] does, and returns the result as WKT.
+/// Requires the `wkt` feature.
+///
+/// # Arguments
+///
+/// + `input_wkt`: `POLYGON` or `MULTIPOLYGON` to buffer, in WKT.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidWkt`] if `input_wkt` cannot be parsed, and [`Error::UnsupportedGeometry`] if it is another type of geometry.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_wkt, Error};
+///
+/// let p2 = buffer_wkt("POLYGON((0 0,1 0,1 1,0 1,0 0))", -0.25).unwrap();
+/// assert_eq!(p2, "MULTIPOLYGON(((0.25 0.25,0.75 0.25,0.75 0.75,0.25 0.75,0.25 0.25)))");
+///
+/// assert_eq!(buffer_wkt("POINT(0 0)", 1.), Err(Error::UnsupportedGeometry("POINT")));
+/// ```
+#[cfg(feature = "wkt")]
+pub fn buffer_wkt(input_wkt: &str, distance: f64) -> Result<String, Error> {
+    use geo_types::Geometry;
+    use wkt::{ToWkt, TryFromWkt};
+
+    let geometry = Geometry::<f64>::try_from_wkt_str(input_wkt)
+        .map_err(|e| Error::InvalidWkt(e.to_string()))?;
+    let input_multi_polygon = match geometry {
+        Geometry::Polygon(p) => MultiPolygon::new(vec![p]),
+        Geometry::MultiPolygon(mp) => mp,
+        Geometry::Point(_) => return Err(Error::UnsupportedGeometry("POINT")),
+        Geometry::Line(_) | Geometry::LineString(_) => {
+            return Err(Error::UnsupportedGeometry("LINESTRING"))
+        }
+        Geometry::MultiPoint(_) => return Err(Error::UnsupportedGeometry("MULTIPOINT")),
+        Geometry::MultiLineString(_) => return Err(Error::UnsupportedGeometry("MULTILINESTRING")),
+        Geometry::GeometryCollection(_) => {
+            return Err(Error::UnsupportedGeometry("GEOMETRYCOLLECTION"))
+        }
+        Geometry::Rect(r) => MultiPolygon::new(vec![r.to_polygon()]),
+        Geometry::Triangle(t) => MultiPolygon::new(vec![t.to_polygon()]),
+    };
+    Ok(buffer_multi_polygon(&input_multi_polygon, distance).wkt_string())
+}
+
+/// This function parses the given GeoJSON, buffers each of its polygons and multi-polygons as [`buffer_multi_polygon`] does,
+/// and returns the result as GeoJSON. Requires the `geojson` feature.
+///
+/// The input may be a geometry, a feature or a feature collection. The id and the properties of each feature are kept,
+/// features without geometry are left as they are, and bounding boxes are removed.
+///
+/// # Arguments
+///
+/// + `input_geojson`: GeoJSON to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the result polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidGeoJson`] if `input_geojson` cannot be parsed, and [`Error::UnsupportedGeometry`] if it contains
+/// another type of geometry.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_geojson;
+///
+/// let input = r#"{"type":"Feature","properties":{"name":"parcel"},"geometry":{"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,1],[0,0]]]}}"#;
+/// let output = buffer_geojson(input, -0.25).unwrap();
+///
+/// let feature: geojson::Feature = output.parse().unwrap();
+/// assert_eq!(feature.property("name").unwrap(), "parcel");
+/// assert_eq!(feature.geometry.unwrap().value.type_name(), "MultiPolygon");
+/// ```
+#[cfg(feature = "geojson")]
+pub fn buffer_geojson(input_geojson: &str, distance: f64) -> Result<String, Error> {
+    let mut geojson: geojson::GeoJson = input_geojson
+        .parse()
+        .map_err(|e: geojson::Error| Error::InvalidGeoJson(e.to_string()))?;
+    interop::buffer_geojson(&mut geojson, distance)?;
+    Ok(geojson.to_string())
+}
+
+/// This function returns the given multi-polygon (e.g. a buffer) as a [`lyon`](https://docs.rs/lyon) path with one closed subpath
+/// per ring, to render it with `lyon` based renderers. Requires the `lyon` feature.
+///
+/// `lyon` works in `f32`, so the coordinates are rounded: translate geographic coordinates close to the origin first.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, lyon_path_of_multi_polygon};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let path = lyon_path_of_multi_polygon(&buffer_polygon(&p1, 0.5));
+///
+/// assert_eq!(path.iter().count(), 5);
+/// ```
+#[cfg(feature = "lyon")]
+#[must_use]
+pub fn lyon_path_of_multi_polygon(
+    input_multi_polygon: &MultiPolygon,
+) -> lyon_tessellation::path::Path {
+    interop::lyon_path_of_multi_polygon(input_multi_polygon)
+}
+
+/// This function returns the given curved polygons (see [`buffer_polygon_curved`]) as a [`lyon`](https://docs.rs/lyon) path with
+/// one closed subpath per ring, the arcs drawn as cubic Bézier curves. Requires the `lyon` feature.
+///
+/// `lyon` works in `f32`, so the coordinates are rounded: translate geographic coordinates close to the origin first.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon_curved, lyon_path_of_curved};
+/// use geo::{Polygon, LineString};
+/// use lyon_tessellation::path::Event;
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let path = lyon_path_of_curved(&buffer_polygon_curved(&p1, 0.5));
+///
+/// let curves = path.iter().filter(|e| matches!(e, Event::Cubic { .. })).count();
+/// assert_eq!(curves, 4);
+/// ```
+#[cfg(feature = "lyon")]
+#[must_use]
+pub fn lyon_path_of_curved(input: &[CurvedPolygon]) -> lyon_tessellation::path::Path {
+    interop::lyon_path_of_curved(input)
+}
+
+/// This function returns the triangles filling the given `lyon` path as a vertex buffer and an index buffer (three indices per
+/// triangle), ready to upload to the GPU. Requires the `lyon` feature.
+///
+/// The path is filled under the even-odd rule, so the holes of the paths of [`lyon_path_of_multi_polygon`] and
+/// [`lyon_path_of_curved`] stay empty.
+///
+/// # Arguments
+///
+/// + `path`: the path to fill.
+/// + `tolerance`: the maximum distance between the curves of the path and the segments replacing them.
+///
+/// # Errors
+///
+/// Returns [`Error::Tessellation`] if the tessellator fails, e.g. on non-finite coordinates.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon_curved, lyon_path_of_curved, tessellate_lyon_path};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let path = lyon_path_of_curved(&buffer_polygon_curved(&p1, 0.5));
+/// let (vertices, indices) = tessellate_lyon_path(&path, 0.01).unwrap();
+///
+/// let area: f32 = indices
+///     .chunks(3)
+///     .map(|t| {
+///         let [a, b, c] = [vertices[t[0] as usize], vertices[t[1] as usize], vertices[t[2] as usize]];
+///         ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.
+///     })
+///     .sum();
+/// assert!((area - (3. + std::f32::consts::PI / 4.)).abs() < 0.05);
+/// ```
+#[cfg(feature = "lyon")]
+pub fn tessellate_lyon_path(
+    path: &lyon_tessellation::path::Path,
+    tolerance: f32,
+) -> Result<(Vec<[f32; 2]>, Vec<u32>), Error> {
+    interop::tessellate(path, tolerance)
+}
+
+/// This function returns the wavefront at the fraction `t` of the way from the given polygon to its buffer by the given distance,
+/// i.e. the buffer by `t * distance`, where `t` is clamped to `[0, 1]`.
+///
+/// To animate the growth of a polygon, build its [`Skeleton`] once with [`skeleton_of_polygon`] and call [`Skeleton::interpolate`]
+/// for each frame.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distance`: determines how distant from each edge of original polygon to each edge of the target polygon. The sign will be:
+///     - `+` to inflate (to add paddings, make bigger) the given polygon, and,
+///     - `-` to deflate (to add margins, make smaller) the given polygon.
+/// + `t`: the fraction of the way from the given polygon (`0`) to the target polygon (`1`).
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::interpolate;
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = interpolate(&p1, -0.8, 0.5);
+///
+/// assert!((p2.unsigned_area() - 1.44).abs() < 1e-9);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn interpolate(input_polygon: &Polygon, distance: f64, t: f64) -> MultiPolygon {
+    skeleton_of_polygon(input_polygon, Side::of_distance(distance)).interpolate(distance.abs(), t)
+}
+
+/// This function returns a triangulation of the given polygon, built from the faces of its inward straight skeleton.
+///
+/// Each face of the skeleton is cut into triangles on its own, so every triangle lies within the face of one edge of the polygon.
+/// If the faces overlap, which happens for some degenerate polygons (e.g. with many collinear edges), the polygon is
+/// triangulated by ear clipping instead. Either way, the triangles cover the polygon without overlapping.
+/// The triangles are counter-clockwise. This is meant for rendering (e.g. the results of buffering) on the GPU.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::triangulate;
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 2.), (0., 2.)]), vec![],
+/// );
+/// let triangles = triangulate(&p1);
+///
+/// assert_eq!(triangles.len(), 6);
+/// assert!((triangles.iter().map(|t| t.signed_area()).sum::<f64>() - 8.).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn triangulate(input_polygon: &Polygon) -> Vec<Triangle> {
+    triangulate::triangulate(input_polygon)
+}
+
+/// This function returns, for each of the given distances, the ring-shaped band between the buffer of the given polygon by that distance
+/// and the buffer by the next of the given distances towards zero (or the given polygon itself, if there is none). These are the
+/// isobands of the distance from the polygon, as drawn on isochrone maps.
+///
+/// The bands are labeled with their distances and returned in the order of `distances`. All inflations share one straight skeleton,
+/// and so do all deflations.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distances`: the distances to buffer by. The sign of each will be:
+///     - `+` for a band outside of the given polygon, and,
+///     - `-` for a band inside of the given polygon.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_rings;
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let bands = buffer_rings(&p1, &[1., 2.]);
+///
+/// assert_eq!(bands[0].0, 1.);
+/// assert_eq!(bands[0].1.0[0].interiors().len(), 1);
+/// // The square from -1 to 3 without the input square, and the square from -2 to 4 without the square from -1 to 3
+/// assert!((bands[0].1.unsigned_area() - 12.).abs() < 1e-9);
+/// assert!((bands[1].1.unsigned_area() - 20.).abs() < 1e-9);
+/// ```
+#[must_use = "Use the newly buffered MultiPolygons"]
+pub fn buffer_rings(input_polygon: &Polygon, distances: &[f64]) -> Vec<(f64, MultiPolygon)> {
+    bands::buffer_rings(input_polygon, distances)
+}
+
+/// This function returns the rings of the buffers of the given polygon by each of the given distances as a tree of [`OffsetRing`]s,
+/// where the parent of each ring is the smallest ring containing it, whichever buffer it belongs to.
+///
+/// These are nested isochrones: filling the rings of odd depth with the even-odd rule on top of those of even depth, or styling
+/// each ring by its distance and depth, draws the bands of [`buffer_rings`] with their holes and islands in one pass.
+/// The rings are sorted by decreasing area, so each ring comes before the rings it contains.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to buffer.
+/// + `distances`: the distances to buffer by, as in [`buffer_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_ring_tree;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+///     vec![LineString::from(vec![(2., 2.), (2., 8.), (8., 8.), (8., 2.)])],
+/// );
+/// let tree = buffer_ring_tree(&p1, &[1., 2.]);
+///
+/// // The outer ring by 2 contains the outer ring by 1, which contains the hole by 1, which contains the hole by 2.
+/// assert_eq!(tree.len(), 4);
+/// assert_eq!(tree.iter().map(|r| r.distance).collect::<Vec<_>>(), vec![2., 1., 1., 2.]);
+/// assert_eq!(tree.iter().map(|r| r.parent).collect::<Vec<_>>(), vec![None, Some(0), Some(1), Some(2)]);
+/// assert_eq!(tree.iter().map(|r| r.hole).collect::<Vec<_>>(), vec![false, false, true, true]);
+/// assert_eq!(tree[3].depth, 3);
+/// ```
+#[must_use]
+pub fn buffer_ring_tree(input_polygon: &Polygon, distances: &[f64]) -> Vec<OffsetRing> {
+    bands::buffer_ring_tree(input_polygon, distances)
+}
+
+/// This function returns the band of the given width centered on the boundary of the given polygon, i.e. the area within `width / 2`
+/// of the boundary. This function creates a miter-joint-like corners around each vertex, as [`buffer_polygon`] does.
+///
+/// This draws a fat outline of the polygon as a fill. The band around each ring is a polygon with a hole, and the bands of
+/// two rings merge where the polygon is narrower than `width`. Exteriors are counter-clockwise and interiors are clockwise.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::stroke_boundary;
+/// use geo::{Area, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+/// );
+/// let p2 = stroke_boundary(&p1, 1.);
+///
+/// // The square from -0.5 to 4.5 without the square from 0.5 to 3.5
+/// assert_eq!(p2.0.len(), 1);
+/// assert_eq!(p2.0[0].interiors().len(), 1);
+/// assert!((p2.unsigned_area() - 16.).abs() < 1e-9);
+/// ```
+#[must_use = "Use the newly stroked MultiPolygon"]
+pub fn stroke_boundary(input_polygon: &Polygon, width: f64) -> MultiPolygon {
+    bands::stroke_boundary(&BufferOptions::new(), input_polygon, width)
+}
+
+/// This function returns the band of the given width centered on the boundary of the given polygon, as [`stroke_boundary`] does,
+/// where both sides of the band are built with the given [`BufferOptions`] (e.g. to round the joins with [`BufferOptions::rounded`]).
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{stroke_boundary_with, BufferOptions};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+/// );
+/// let p2 = stroke_boundary_with(&p1, 1., &BufferOptions::new().rounded(true));
+///
+/// assert!(p2.0[0].exterior().0.len() > 5);
+/// assert_eq!(p2.0[0].interiors()[0].0.len(), 5);
+/// ```
+#[must_use = "Use the newly stroked MultiPolygon"]
+pub fn stroke_boundary_with(
+    input_polygon: &Polygon,
+    width: f64,
+    options: &BufferOptions,
+) -> MultiPolygon {
+    bands::stroke_boundary(options, input_polygon, width)
+}
+
+/// This function returns the rings offset from the given closed ring by the given distance towards the given side,
+/// without wrapping the ring into a `Polygon`. This function creates a miter-joint-like corners around each convex vertex.
+///
+/// The ring may or may not repeat its first coordinate at the end, and the resulting rings always do. Exteriors of the result
+/// have the winding order of the given ring, and interiors (e.g. where an inflated ring closes around a bay) the opposite one.
+///
+/// # Arguments
+///
+/// + `ring`: closed `LineString` to offset.
+/// + `distance`: determines how distant from each edge of the given ring to each edge of the resulting rings.
+/// + `side`: determines where the resulting rings go:
+///     - [`Side::Interior`] to offset into the region enclosed by the ring (to deflate it), and,
+///     - [`Side::Exterior`] to offset out of it (to inflate it).
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{offset_closed_ring, Side};
+/// use geo::LineString;
+///
+/// // A clockwise ring
+/// let ring = LineString::from(vec![(0., 0.), (0., 1.), (1., 1.), (1., 0.), (0., 0.)]);
+/// let rings = offset_closed_ring(&ring, 0.2, Side::Interior);
+///
+/// let expected = LineString::from(vec![(0.2, 0.2), (0.2, 0.8), (0.8, 0.8), (0.8, 0.2), (0.2, 0.2)]);
+/// assert_eq!(rings, vec![expected]);
+/// ```
+#[must_use = "Use the newly offset rings"]
+pub fn offset_closed_ring(ring: &LineString, distance: f64, side: Side) -> Vec<LineString> {
+    let distance = match side {
+        Side::Interior => -distance,
+        Side::Exterior => distance,
+    };
+    let mut input = MultiPolygon::new(vec![Polygon::new(ring.clone(), vec![])]);
+    let winding = ring
+        .winding_order()
+        .unwrap_or(WindingOrder::CounterClockwise);
+    postprocess::orient(&mut input, WindingOrder::CounterClockwise);
+    let mut res = buffer_multi_polygon(&input, distance);
+    postprocess::orient(&mut res, winding);
+    res.into_iter()
+        .flat_map(|p| {
+            let (exterior, interiors) = p.into_inner();
+            std::iter::once(exterior).chain(interiors)
+        })
+        .collect()
+}
+
+/// This function returns the straight skeleton of the given polygon, which can buffer the polygon by several distances
+/// without building the skeleton again. See [`Skeleton`] for an example.
+///
+/// Rings with fewer than 4 coordinates are skipped, see [`buffer_polygon`], so the skeleton of an empty polygon is empty.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to get the straight skeleton.
+/// + `side`: determines the region where the straight skeleton created. The value of this [`Side`] will be:
+///     * [`Side::Interior`] to create the straight skeleton on the inward region of the polygon (to deflate the polygon), and,
+///     * [`Side::Exterior`] to create on the outward region of the polygon (to inflate the polygon).
+///
+///   The former `bool` flag is still accepted, `true` being [`Side::Interior`] and `false` being [`Side::Exterior`].
+#[must_use]
+pub fn skeleton_of_polygon(input_polygon: &Polygon, side: impl Into<Side>) -> Skeleton {
+    Skeleton::skeleton_of_polygon(input_polygon, side.into())
+}
+
+/// This function returns the straight skeleton of the given multi-polygon, which can buffer the multi-polygon by several distances
+/// without building the skeleton again. See [`Skeleton`] for an example.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to get the straight skeleton.
+/// + `side`: determines the region where the straight skeleton created. The value of this [`Side`] will be:
+///     * [`Side::Interior`] to create the straight skeleton on the inward region of the multi-polygon (to deflate the multi-polygon), and,
+///     * [`Side::Exterior`] to create on the outward region of the multi-polygon (to inflate the multi-polygon).
+///
+///   The former `bool` flag is still accepted, as in [`skeleton_of_polygon`].
+#[must_use]
+pub fn skeleton_of_multi_polygon(
+    input_multi_polygon: &MultiPolygon,
+    side: impl Into<Side>,
+) -> Skeleton {
+    Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, side.into())
+}
+
+/// This function returns the straight skeleton of the given multi-polygon as [`skeleton_of_multi_polygon`] does,
+/// or an error instead of panicking if the construction breaks down on a pathological input.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to get the straight skeleton.
+/// + `side`: determines the region where the straight skeleton created, as in [`skeleton_of_multi_polygon`].
+///
+/// # Errors
+///
+/// Returns [`Error::NumericFailure`] if the construction of the straight skeleton fails. Note that the panic message is still
+/// printed by the panic hook, and that nothing is caught if the crate is built with `panic = "abort"`.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{try_skeleton_of_multi_polygon, Side};
+/// use geo::{MultiPolygon, Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let skel = try_skeleton_of_multi_polygon(&MultiPolygon::new(vec![p1]), Side::Interior).unwrap();
+/// assert_eq!(skel.side(), Side::Interior);
+/// ```
+pub fn try_skeleton_of_multi_polygon(
+    input_multi_polygon: &MultiPolygon,
+    side: impl Into<Side>,
+) -> Result<Skeleton, Error> {
+    SkeletonBuilder::new().try_skeleton_of_multi_polygon(input_multi_polygon, side)
+}
+
+/// This function returns the buffered (multi-)polygon of the given polygon as [`buffer_polygon`] does, together with the straight skeleton
+/// it has been buffered by.
+///
+/// The skeleton is the one [`skeleton_of_polygon`] returns for the side of the polygon the buffer moves to, so it can draw the skeleton
+/// under the result (see [`Skeleton::to_linestring`]) or buffer the polygon by further distances of the same sign without building it again.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, buffer_polygon_with_skeleton, BufferOptions};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let (p2, skel) = buffer_polygon_with_skeleton(&p1, -0.2);
+///
+/// assert_eq!(p2, buffer_polygon(&p1, -0.2));
+/// assert_eq!(skel.buffer(0.45, &BufferOptions::new()), buffer_polygon(&p1, -0.45));
+/// assert!(!skel.to_linestring().is_empty());
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_polygon_with_skeleton(
+    input_polygon: &Polygon,
+    distance: f64,
+) -> (MultiPolygon, Skeleton) {
+    let skel = Skeleton::skeleton_of_polygon(input_polygon, Side::of_distance(distance));
+    (skel.buffer(distance.abs(), &BufferOptions::new()), skel)
+}
+
+/// This function returns the buffered (multi-)polygon of the given multi-polygon as [`buffer_multi_polygon`] does, together with the
+/// straight skeleton it has been buffered by, see [`buffer_polygon_with_skeleton`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_multi_polygon, buffer_multi_polygon_with_skeleton};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let (mp2, _) = buffer_multi_polygon_with_skeleton(&mp1, 0.9);
+///
+/// assert_eq!(mp2, buffer_multi_polygon(&mp1, 0.9));
+/// ```
+#[must_use = "Use the newly buffered MultiPolygon"]
+pub fn buffer_multi_polygon_with_skeleton(
+    input_multi_polygon: &MultiPolygon,
+    distance: f64,
+) -> (MultiPolygon, Skeleton) {
+    let skel =
+        Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, Side::of_distance(distance));
+    (skel.buffer(distance.abs(), &BufferOptions::new()), skel)
+}
+
+/// This function returns a set of `LineSting` which represents an instantiated straight skeleton of the given polygon.
+/// Each segment of the straight skeleton is represented as a single `LineString`, and the returned vector is a set of these `LineString`s.
+/// If either endpoints of a `LineString` is infinitely far from the other, then this `LineString` will be clipped to one which has shorter length.
+/// The order of these `LineString`s is arbitrary. (There is no gauranteed order on segments of the straight skeleton.)
+///
+/// Click 'Result' below to see how this function works.
+///
+/// # Arguments
+///
+/// + `input_polygon`: `Polygon` to get the straight skeleton.
+/// + `side`: determines the region where the straight skeleton created. The value of this [`Side`] will be:
+///     * [`Side::Interior`] to create the straight skeleton on the inward region of the polygon, and,
+///     * [`Side::Exterior`] to create on the outward region of the polygon.
+///
+///   The former `bool` flag is still accepted, as in [`skeleton_of_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_polygon_to_linestring, Side};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let ls1: Vec<LineString> = skeleton_of_polygon_to_linestring(&p1, Side::Interior);
+///
+/// // The former `bool` flag builds the same skeleton.
+/// assert_eq!(ls1, skeleton_of_polygon_to_linestring(&p1, true));
+/// ```
+///
+/// <details>
+/// <summary style="cursor:pointer"> Result </summary>
+/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex7.svg" style="padding: 25px 30%;"/>
+/// </details>
+///
+pub fn skeleton_of_polygon_to_linestring(
+    input_polygon: &Polygon,
+    side: impl Into<Side>,
+) -> Vec<LineString> {
+    Skeleton::skeleton_of_polygon(input_polygon, side.into()).to_linestring()
+}
+
+/// This function returns a set of `LineSting` which represents an instantiated straight skeleton of the given multi-polygon.
+/// Each segment of the straight skeleton is represented as a single `LineString`, and the returned vector is a set of these `LineString`s.
+/// If either endpoints of a `LineString` is infinitely far from the other, then this `LineString` will be clipped to one which has shorter length.
+/// The order of these `LineString`s is arbitrary. (There is no gauranteed order on segments of the straight skeleton.)
+///
+/// Click 'Result' below to see how this function works.
+///
+/// # Arguments
+///
+/// + `input_multi_polygon`: `MultiPolygon` to get the straight skeleton.
+/// + `side`: determines the region where the straight skeleton created. The value of this [`Side`] will be:
+///     * [`Side::Interior`] to create the straight skeleton on the inward region of the polygon, and,
+///     * [`Side::Exterior`] to create on the outward region of the polygon.
+///
+///   The former `bool` flag is still accepted, as in [`skeleton_of_polygon`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_multi_polygon_to_linestring, Side};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(3., 3.), (5., 3.), (5., 5.), (3., 5.)]), vec![],
+/// );
+/// let mp1 = MultiPolygon::new(vec![p1, p2]);
+/// let ls: Vec<LineString> = skeleton_of_multi_polygon_to_linestring(&mp1, Side::Exterior);
+/// ```
+///
+/// <details>
+/// <summary style="cursor:pointer"> Result </summary>
+/// <img src="https://raw.githubusercontent.com/1011-git/geo-buffer/main/assets/ex8.svg" style="padding: 25px 30%;"/>
+/// </details>
+///
+pub fn skeleton_of_multi_polygon_to_linestring(
+    input_multi_polygon: &MultiPolygon,
+    side: impl Into<Side>,
+) -> Vec<LineString> {
+    Skeleton::skeleton_of_polygon_vector(&input_multi_polygon.0, side.into()).to_linestring()
+}
+
+/// This function returns the corridor along the given line string, whose width changes linearly from each vertex to the next one,
+/// e.g. to model a river or a flight corridor. The ends and the joins of the corridor are rounded.
+///
+/// # Arguments
+///
+/// + `input_line_string`: `LineString` to buffer.
+/// + `widths`: the width of the corridor at each vertex of the line string, i.e. twice the distance from the vertex to the boundary.
+///   Negative widths count as zero.
+/// + `resolution`: how many sides the n-gons approximating the circles around the vertices have, as in [`buffer_point`].
+///
+/// # Errors
+///
+/// Returns [`Error::MismatchedWidths`] if there is not exactly one width per vertex.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_line_string_tapered;
+/// use geo::{Area, BoundingRect, LineString, Rect};
+///
+/// let ls = LineString::from(vec![(0., 0.), (10., 0.)]);
+/// let corridor = buffer_line_string_tapered(&ls, &[2., 4.], 4).unwrap();
+///
+/// assert_eq!(corridor.bounding_rect().unwrap(), Rect::new((-1., -2.), (12., 2.)));
+/// assert!(buffer_line_string_tapered(&ls, &[2.], 4).is_err());
+/// ```
+pub fn buffer_line_string_tapered(
+    input_line_string: &LineString,
+    widths: &[f64],
+    resolution: usize,
+) -> Result<MultiPolygon, Error> {
+    if widths.len() != input_line_string.0.len() {
+        return Err(Error::MismatchedWidths(
+            input_line_string.0.len(),
+            widths.len(),
+        ));
+    }
+    let half_widths: Vec<f64> = widths.iter().map(|w| w / 2.).collect();
+    Ok(corridor::tapered(
+        input_line_string,
+        &half_widths,
+        resolution,
+    ))
+}
+
+/// This function returns the buffer of the given street network, split into the carriageway of each line string and the junctions,
+/// so that a renderer can style them differently. The ends and the joins of the buffer are rounded.
+///
+/// A junction is a vertex where three or more segments of the input meet (e.g. the shared end of three line strings, or a vertex
+/// shared by two line strings crossing each other). Its area is the part of the buffer within `width` of the vertex, and the
+/// carriageways are the buffers of the line strings without the junctions. Together, they cover the buffer of the whole network.
+///
+/// # Arguments
+///
+/// + `input_multi_line_string`: `MultiLineString` of streets, which meet at shared vertices.
+/// + `width`: the width of the streets, i.e. twice the distance from each street to the boundary of its carriageway.
+/// + `resolution`: how many sides the n-gons approximating the circles around the vertices have, as in [`buffer_point`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::buffer_street_network;
+/// use geo::{Area, LineString, MultiLineString};
+///
+/// // A T-junction at (0, 0)
+/// let streets = MultiLineString::new(vec![
+///     LineString::from(vec![(-10., 0.), (0., 0.)]),
+///     LineString::from(vec![(0., 0.), (10., 0.)]),
+///     LineString::from(vec![(0., 0.), (0., 10.)]),
+/// ]);
+/// let network = buffer_street_network(&streets, 2., 16);
+///
+/// assert_eq!(network.carriageways.len(), 3);
+/// assert_eq!(network.junctions.len(), 1);
+/// assert_eq!(network.junctions[0].location, (0., 0.).into());
+/// assert!(network.junctions[0].area.unsigned_area() > 0.);
+/// ```
+#[must_use = "Use the newly buffered street network"]
+pub fn buffer_street_network(
+    input_multi_line_string: &MultiLineString,
+    width: f64,
+    resolution: usize,
+) -> StreetNetwork {
+    corridor::street_network(input_multi_line_string, width / 2., resolution)
 }
 
 /// This function returns the buffered n-gon of the given point.
@@ -0,0 +1,158 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::Error;
+
+/// A flag to cancel running buffer computations from another thread, see [`BufferOptions::cancel_token`](crate::BufferOptions::cancel_token).
+///
+/// Clones of a token share the flag, so a token can be handed to the computation while a clone is kept to cancel it.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{try_buffer_polygon_with, BufferOptions, CancelToken, Error};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let token = CancelToken::new();
+/// let options = BufferOptions::new().cancel_token(token.clone());
+/// assert!(try_buffer_polygon_with(&p1, 0.2, &options).is_ok());
+///
+/// token.cancel();
+/// assert_eq!(try_buffer_polygon_with(&p1, 0.2, &options), Err(Error::Cancelled));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the computations using this token (or a clone of it). A cancelled token stays cancelled.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether [`CancelToken::cancel`] has been called on this token or a clone of it.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Two tokens are equal if they share their flag.
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The progress of the construction of a straight skeleton, see [`buffer_multi_polygon_with_progress`](crate::buffer_multi_polygon_with_progress).
+///
+/// Processing an event may queue new events, so the total number of events is not known in advance.
+/// The construction is done when no events are queued anymore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of events taken from the queue so far.
+    pub processed_events: usize,
+    /// The number of events in the queue.
+    pub queued_events: usize,
+    /// The number of events removed from the queue without being processed, because the wavefront changed before they happened.
+    pub retired_events: usize,
+}
+
+/// Watches the construction of a skeleton: aborts it on the conditions of the options, and reports its progress.
+#[derive(Default)]
+pub(crate) struct Monitor<'c, 'p> {
+    cancel: Option<&'c CancelToken>,
+    deadline: Option<Instant>,
+    max_events: Option<usize>,
+    progress: Option<&'p mut dyn FnMut(Progress)>,
+    /// The number of calls to [`Monitor::poll`] until the conditions are checked again.
+    countdown: usize,
+}
+
+impl<'c, 'p> Monitor<'c, 'p> {
+    /// The number of calls to [`Monitor::poll`] between two checks, since reading the clock is not free.
+    const CHECK_INTERVAL: usize = 256;
+
+    pub(crate) fn new(
+        cancel: Option<&'c CancelToken>,
+        deadline: Option<Instant>,
+        max_events: Option<usize>,
+        progress: Option<&'p mut dyn FnMut(Progress)>,
+    ) -> Self {
+        Self {
+            cancel,
+            deadline,
+            max_events,
+            progress,
+            countdown: 0,
+        }
+    }
+
+    /// Returns an error if the computation is to be aborted.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        self.watcher().check()
+    }
+
+    /// Returns the conditions to abort the computation on, which can be checked from several threads at once.
+    pub(crate) const fn watcher(&self) -> Watcher<'c> {
+        Watcher {
+            cancel: self.cancel,
+            deadline: self.deadline,
+        }
+    }
+
+    /// Checks the conditions as [`Monitor::check`] does and reports `progress`, but only on every [`Monitor::CHECK_INTERVAL`]-th call.
+    ///
+    /// The limit on the number of events is checked on every call.
+    pub(crate) fn poll(&mut self, progress: Progress) -> Result<(), Error> {
+        if let Some(max_events) = self.max_events {
+            if progress.processed_events + progress.queued_events > max_events {
+                return Err(Error::TooManyEvents(max_events));
+            }
+        }
+        if self.countdown == 0 {
+            self.countdown = Self::CHECK_INTERVAL;
+            self.check()?;
+            self.report(progress);
+        }
+        self.countdown -= 1;
+        Ok(())
+    }
+
+    /// Reports `progress` unconditionally, e.g. at the end of the construction.
+    pub(crate) fn report(&mut self, progress: Progress) {
+        if let Some(f) = &mut self.progress {
+            f(progress);
+        }
+    }
+}
+
+/// The cancellation and the deadline of a [`Monitor`], shared by the threads of a parallel part of the computation.
+#[derive(Clone, Copy)]
+pub(crate) struct Watcher<'c> {
+    cancel: Option<&'c CancelToken>,
+    deadline: Option<Instant>,
+}
+
+impl Watcher<'_> {
+    /// Returns an error if the computation is to be aborted.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        if self.cancel.is_some_and(CancelToken::is_cancelled) {
+            return Err(Error::Cancelled);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(Error::TimedOut);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+//! Buffering without a straight skeleton, by uniting the buffers of the edges of the input with it (or subtracting them from it)
+//! as GEOS does, see [`Algorithm::OffsetUnion`](crate::Algorithm::OffsetUnion).
+
+use std::f64::consts::TAU;
+
+use geo::winding_order::WindingOrder;
+use geo::Winding;
+use geo_types::{Coord, LineString, MultiPolygon, Point, Polygon};
+use i_overlay::core::overlay_rule::OverlayRule;
+
+use crate::skeleton::Corners;
+use crate::util::CoordExt;
+use crate::{buffer_point, postprocess, repair};
+
+/// Returns the polygons `input` buffered by `distance`, with the corners drawn as `corners` says.
+///
+/// Each edge of the input is swept by `|distance|` to the side the buffer grows to, the gaps between the sweeps of consecutive
+/// edges are filled by corner pieces, and the union of these pieces is added to the input (or subtracted from it when deflating).
+pub(crate) fn buffer(input: &[Polygon], distance: f64, corners: Corners) -> MultiPolygon {
+    let mut subject = MultiPolygon::new(input.to_vec());
+    // The inside of every ring is then to the left of its edges.
+    postprocess::orient(&mut subject, WindingOrder::CounterClockwise);
+    let offset = distance.abs();
+    // The side of the edges the buffer grows to: `-1` for the right side (the outside), `1` for the left side.
+    let side = if distance > 0. { -1. } else { 1. };
+    let mut pieces = Vec::new();
+    for ring in subject
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+    {
+        let mut coords = ring.0.clone();
+        coords.dedup();
+        if coords.len() > 1 && coords.first() == coords.last() {
+            coords.pop();
+        }
+        let n = coords.len();
+        if n < 2 || offset == 0. {
+            continue;
+        }
+        for i in 0..n {
+            let (a, b, c) = (coords[i], coords[(i + 1) % n], coords[(i + 2) % n]);
+            let direction = (b - a) / (b - a).norm();
+            let normal = Coord {
+                x: -direction.y,
+                y: direction.x,
+            } * (side * offset);
+            pieces.push(vec![a, b, b + normal, a + normal]);
+            // The sweeps of `a`-`b` and `b`-`c` leave a gap on the outer side of the turn at `b`.
+            if (b - a).outer_product(&(c - b)) * side < 0. {
+                pieces.extend(join_piece(a, b, c, offset, corners));
+            }
+        }
+    }
+    let mut band = MultiPolygon::new(
+        pieces
+            .into_iter()
+            .map(|ring| {
+                let mut ls = LineString::from(ring);
+                ls.close();
+                ls.make_ccw_winding();
+                Polygon::new(ls, vec![])
+            })
+            .collect(),
+    );
+    repair::repair(&mut band);
+    let rule = if distance > 0. {
+        OverlayRule::Union
+    } else {
+        OverlayRule::Difference
+    };
+    repair::overlay(&subject, &band, rule)
+}
+
+/// Returns the piece filling the gap between the buffers of the segments `a`-`v` and `v`-`c` on the outer side of the turn
+/// at `v`, drawn as `corners` says, or `None` if the segments do not turn. Round corners are whole discs around `v`.
+pub(crate) fn join_piece(
+    a: Coord,
+    v: Coord,
+    c: Coord,
+    distance: f64,
+    corners: Corners,
+) -> Option<Vec<Coord>> {
+    if let Corners::Round(step) = corners {
+        let sides = (TAU / step).round().max(3.) as usize;
+        let (exterior, _) = buffer_point(&Point(v), distance, sides).into_inner();
+        return Some(exterior.0);
+    }
+    let (u1, u2) = ((v - a) / (v - a).norm(), (c - v) / (c - v).norm());
+    let turn = u1.outer_product(&u2);
+    if turn == 0. {
+        return None;
+    }
+    // The normals on the outer side, which is the right side of a left turn.
+    let side = if turn > 0. { -1. } else { 1. };
+    let n1 = Coord { x: -u1.y, y: u1.x } * side;
+    let n2 = Coord { x: -u2.y, y: u2.x } * side;
+    let (p1, p2) = (v + n1 * distance, v + n2 * distance);
+    let limit = match corners {
+        Corners::Miter => f64::INFINITY,
+        Corners::MiterLimit(limit) if limit > 1. => limit,
+        _ => return Some(vec![v, p1, p2]),
+    };
+    let mitre = (n1 + n2) / (1. + n1.inner_product(&n2));
+    let ratio = mitre.norm();
+    if ratio <= limit {
+        return Some(vec![v, p1, v + mitre * distance, p2]);
+    }
+    // Each offset edge is cut where it reaches `limit * distance` along the bisector.
+    let bisector = mitre / ratio;
+    let reach = limit * distance;
+    let cut = |p: Coord, direction: Coord| {
+        p + direction
+            * ((reach - (p - v).inner_product(&bisector)) / direction.inner_product(&bisector))
+    };
+    Some(vec![v, p1, cut(p1, u1), cut(p2, -u2), p2])
+}
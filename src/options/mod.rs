@@ -0,0 +1,1414 @@
+use std::borrow::Cow;
+#[cfg(feature = "rayon")]
+use std::fmt;
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+use std::time::Instant;
+
+use geo::winding_order::WindingOrder;
+use geo::{CoordsIter, Densify, Euclidean, Simplify, Translate, Winding};
+use geo_types::{Coord, LineString, MultiPolygon, Polygon, Rect};
+
+use crate::monitor::{CancelToken, Monitor, Progress};
+use crate::offset_union;
+use crate::postprocess;
+use crate::provenance::{self, VertexOrigin};
+use crate::rectilinear;
+use crate::repair::{self, RepairReport};
+use crate::skeleton::{Corners, Side, Skeleton, SkeletonBuilder, TieBreak, ARC_STEP};
+use crate::{Diagnostics, Error, Warning};
+
+/// Options for [`buffer_polygon_with`](crate::buffer_polygon_with) and [`buffer_multi_polygon_with`](crate::buffer_multi_polygon_with).
+///
+/// The default options produce the same result as [`buffer_polygon`](crate::buffer_polygon) (resp. [`buffer_multi_polygon`](crate::buffer_multi_polygon)).
+/// Each option is set by a chained method call.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon_with, BufferOptions};
+/// use geo::{Polygon, MultiPolygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+/// );
+/// let options = BufferOptions::new().rounded(true).min_segment_length(0.05);
+/// let p2: MultiPolygon = buffer_polygon_with(&p1, 0.2, &options);
+/// ```
+///
+/// # Precision
+///
+/// Inputs far from the origin compared with their extent (e.g. buildings in Web-Mercator meters) are translated near the origin
+/// before the straight skeleton is built, and the result is translated back, so that the magnitude of the coordinates does not
+/// eat up the precision of the construction.
+///
+/// ```
+/// use geo_buf::{buffer_polygon_with, BufferOptions};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(2e7, 1e7), (2e7 + 10., 1e7), (2e7 + 10., 1e7 + 10.), (2e7, 1e7 + 10.)]), vec![],
+/// );
+/// let p2 = buffer_polygon_with(&p1, -1., &BufferOptions::new());
+///
+/// let expected_exterior = LineString::from(vec![
+///     (2e7 + 1., 1e7 + 1.), (2e7 + 9., 1e7 + 1.), (2e7 + 9., 1e7 + 9.), (2e7 + 1., 1e7 + 9.), (2e7 + 1., 1e7 + 1.),
+/// ]);
+/// assert_eq!(&expected_exterior, p2.0[0].exterior());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BufferOptions {
+    algorithm: Algorithm,
+    corners: Corners,
+    min_segment_length: Option<f64>,
+    min_output_area: Option<f64>,
+    min_hole_area: Option<f64>,
+    min_width: Option<f64>,
+    simplify: Option<f64>,
+    repair: bool,
+    winding: OutputWinding,
+    clip: Option<Rect>,
+    output_scale: Option<f64>,
+    cancel: Option<CancelToken>,
+    deadline: Option<Instant>,
+    max_events: Option<usize>,
+    preserve_holes: bool,
+    merge_degenerate_vertices: bool,
+    input_scale: Option<f64>,
+    densify: Option<f64>,
+    exact_vertices: bool,
+    keep_collinear_vertices: bool,
+    tie_break: TieBreak,
+    resolve_overlaps: bool,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<SharedThreadPool>,
+}
+
+/// A thread pool shared by clones of [`BufferOptions`]. Two handles are equal if they share their pool.
+#[cfg(feature = "rayon")]
+#[derive(Clone)]
+struct SharedThreadPool(Arc<rayon::ThreadPool>);
+
+#[cfg(feature = "rayon")]
+impl fmt::Debug for SharedThreadPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThreadPool")
+            .field("threads", &self.0.current_num_threads())
+            .finish()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl PartialEq for SharedThreadPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The winding order of the rings of the result, see [`BufferOptions::winding`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputWinding {
+    /// Counter-clockwise exteriors and clockwise interiors, as the OGC Simple Features specification requires.
+    #[default]
+    Ogc,
+    /// Clockwise exteriors and counter-clockwise interiors, as in ESRI shapefiles.
+    Reversed,
+    /// The winding order of the exterior of the (first) input polygon for exteriors, and the opposite one for interiors.
+    /// If the input exterior has no winding order (e.g. it is degenerate), this is the same as [`OutputWinding::Ogc`].
+    MatchInput,
+}
+
+/// The algorithm computing the buffer, see [`BufferOptions::algorithm`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Moving the boundary of the input along its straight skeleton.
+    #[default]
+    StraightSkeleton,
+    /// Uniting the input with the regions swept by its edges (or subtracting them from it when deflating), as GEOS does.
+    OffsetUnion,
+    /// Uniting the input with the rectangles swept by its edges (or subtracting them from it when deflating) if every edge is
+    /// parallel to an axis, as floor plans and circuit board outlines mostly are, and moving the boundary along the straight
+    /// skeleton otherwise.
+    Rectilinear,
+}
+
+impl BufferOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether to create rounded corners around each convex vertex (as [`buffer_polygon_rounded`](crate::buffer_polygon_rounded) does)
+    /// instead of miter-joint-like corners. The default is `false`.
+    #[must_use]
+    pub fn rounded(mut self, rounded: bool) -> Self {
+        self.corners = if rounded {
+            Corners::Round(ARC_STEP)
+        } else {
+            Corners::Miter
+        };
+        self
+    }
+
+    /// Sets how to draw the corners around the convex vertices, see [`BufferParams`](crate::BufferParams).
+    pub(crate) fn corners(mut self, corners: Corners) -> Self {
+        self.corners = corners;
+        self
+    }
+
+    /// Removes output vertices closer than `tolerance` to the previous vertex of their ring, so that no segment of the result
+    /// is shorter than `tolerance`. Rings left with fewer than three vertices are removed, together with the polygon if it is the exterior.
+    ///
+    /// Such micro segments show up in rounded corners and around split events. By default, no vertex is removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 0.2, &BufferOptions::new().rounded(true).min_segment_length(0.05));
+    ///
+    /// for w in p2.0[0].exterior().0.windows(2) {
+    ///     assert!(f64::hypot(w[1].x - w[0].x, w[1].y - w[0].y) >= 0.05);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn min_segment_length(mut self, tolerance: f64) -> Self {
+        self.min_segment_length = Some(tolerance);
+        self
+    }
+
+    /// Removes the rings of the result enclosing less than `area`: polygons whose exterior does, and interiors which do.
+    /// By default, no ring is removed.
+    ///
+    /// Deflating crenellated boundaries (e.g. coastlines) leaves many tiny slivers, which this removes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, -0.45, &BufferOptions::new());
+    /// assert_eq!(p2.0.len(), 2);
+    ///
+    /// // Each of the two parts encloses about 1.42.
+    /// let p3 = buffer_polygon_with(&p1, -0.45, &BufferOptions::new().min_output_area(2.));
+    /// assert_eq!(p3.0.len(), 0);
+    /// ```
+    #[must_use]
+    pub fn min_output_area(mut self, area: f64) -> Self {
+        self.min_output_area = Some(area);
+        self
+    }
+
+    /// Removes the interiors of the result enclosing less than `area`, as [`BufferOptions::min_output_area`] does,
+    /// but keeps all exteriors. By default, no interior is removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+    ///     vec![LineString::from(vec![(3., 3.), (3., 7.), (7., 7.), (7., 3.)])],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 1.5, &BufferOptions::new().min_hole_area(2.));
+    ///
+    /// assert_eq!(p2.0.len(), 1);
+    /// assert_eq!(p2.0[0].interiors().len(), 0);
+    /// ```
+    #[must_use]
+    pub fn min_hole_area(mut self, area: f64) -> Self {
+        self.min_hole_area = Some(area);
+        self
+    }
+
+    /// Removes the parts of the result narrower than `width`: thin spikes and slivers vanish, and necks thinner than `width`
+    /// are cut, so that every point of the result lies in a disk of diameter `width` within the result. By default, nothing is removed.
+    ///
+    /// This is an opening of the result by a disk: it is deflated by `width / 2` and inflated back with rounded corners, so the
+    /// convex corners of the result are rounded with a radius of `width / 2`. 3D printers and cutters cannot reproduce features
+    /// narrower than their nozzle or tool, which is the width to give.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// // Two squares joined by a corridor of width 0.5.
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![
+    ///         (0., 0.), (4., 0.), (4., 1.75), (6., 1.75), (6., 0.), (10., 0.),
+    ///         (10., 4.), (6., 4.), (6., 2.25), (4., 2.25), (4., 4.), (0., 4.),
+    ///     ]),
+    ///     vec![],
+    /// );
+    /// assert_eq!(buffer_polygon_with(&p1, -0.1, &BufferOptions::new()).0.len(), 1);
+    ///
+    /// // The corridor shrinks to a width of 0.3, which is cut.
+    /// let p2 = buffer_polygon_with(&p1, -0.1, &BufferOptions::new().min_width(1.));
+    /// assert_eq!(p2.0.len(), 2);
+    /// ```
+    #[must_use]
+    pub fn min_width(mut self, width: f64) -> Self {
+        self.min_width = Some(width);
+        self
+    }
+
+    /// Simplifies each ring of the result by the [Ramer–Douglas–Peucker](https://en.wikipedia.org/wiki/Ramer–Douglas–Peucker_algorithm) algorithm
+    /// (see [`Simplify`](geo::Simplify)), so that each removed vertex is within `tolerance` of the simplified ring.
+    ///
+    /// Since the simplified boundary is within `tolerance` of the buffered boundary, it is never closer to the input than `|distance| - tolerance`
+    /// (nor farther than `|distance| + tolerance`). Rounded buffers in particular have far more vertices than needed for display.
+    /// By default, the result is not simplified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{CoordsIter, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let rounded = BufferOptions::new().rounded(true);
+    /// let p2 = buffer_polygon_with(&p1, 0.2, &rounded);
+    /// let p3 = buffer_polygon_with(&p1, 0.2, &rounded.simplify(0.01));
+    ///
+    /// assert!(p3.coords_count() < p2.coords_count());
+    /// ```
+    #[must_use]
+    pub fn simplify(mut self, tolerance: f64) -> Self {
+        self.simplify = Some(tolerance);
+        self
+    }
+
+    /// Subdivides the edges of the input longer than `max_segment_length` into equal pieces no longer than it before
+    /// buffering, see [`Densify`](geo::Densify). By default, the input is not densified.
+    ///
+    /// The buffer of a densified polygon is the same, with more vertices along its edges, which then follow anything applied
+    /// to the vertices of the result afterwards: e.g. projecting it to geographic coordinates, where long straight chords
+    /// misrepresent great circles, or moving its vertices by a varying distance. Nothing is subdivided unless
+    /// `max_segment_length` is positive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Area, CoordsIter, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 1., &BufferOptions::new());
+    /// let p3 = buffer_polygon_with(&p1, 1., &BufferOptions::new().densify(2.5));
+    ///
+    /// assert_eq!(p2.coords_count(), 5);
+    /// assert_eq!(p3.coords_count(), 17);
+    /// assert!((p3.unsigned_area() - p2.unsigned_area()).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn densify(mut self, max_segment_length: f64) -> Self {
+        self.densify = Some(max_segment_length);
+        self
+    }
+
+    /// Sets whether to validate the result and repair it if it is invalid. The default is `false`.
+    ///
+    /// The result is checked for crossing or overlapping segments, for interiors outside of their exterior and for polygons inside other polygons.
+    /// If any of them is found, the result is replaced by the union of the regions enclosed by its rings (counted by winding number).
+    /// Use [`buffer_polygon_with_report`](crate::buffer_polygon_with_report) to find out what has been found and fixed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with_report, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let (p2, report) = buffer_polygon_with_report(&p1, 0.5, &BufferOptions::new().repair(true));
+    ///
+    /// assert!(report.is_valid());
+    /// assert!(!report.repaired);
+    /// ```
+    #[must_use]
+    pub fn repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// Sets the winding order of the rings of the result. The default is [`OutputWinding::Ogc`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions, OutputWinding};
+    /// use geo::{Polygon, LineString, Winding};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+    ///     vec![LineString::from(vec![(3., 3.), (3., 7.), (7., 7.), (7., 3.)])],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 1., &BufferOptions::new());
+    /// assert!(p2.0[0].exterior().is_ccw());
+    /// assert!(p2.0[0].interiors()[0].is_cw());
+    ///
+    /// let p3 = buffer_polygon_with(&p1, 1., &BufferOptions::new().winding(OutputWinding::Reversed));
+    /// assert!(p3.0[0].exterior().is_cw());
+    /// assert!(p3.0[0].interiors()[0].is_ccw());
+    /// ```
+    #[must_use]
+    pub fn winding(mut self, winding: OutputWinding) -> Self {
+        self.winding = winding;
+        self
+    }
+
+    /// Sets whether to unify the polygons of the result, so that no two of them overlap and no polygon overlaps itself.
+    /// The default is `false`.
+    ///
+    /// Inflating highly non-convex polygons may produce polygons which overlap each other, or a single polygon whose exterior runs over
+    /// itself. The union keeps every region covered by the result once, as [`BufferOptions::repair`] does, but whether or not the
+    /// validation finds errors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 1., &BufferOptions::new().resolve_overlaps(true));
+    ///
+    /// assert_eq!(p2.0.len(), 1);
+    /// assert!(p2.unsigned_area() > p1.unsigned_area());
+    /// ```
+    #[must_use]
+    pub fn resolve_overlaps(mut self, resolve_overlaps: bool) -> Self {
+        self.resolve_overlaps = resolve_overlaps;
+        self
+    }
+
+    /// Clips the result to the rectangle `rect`, e.g. the tile of a tile-based renderer.
+    ///
+    /// Input polygons whose buffer cannot reach `rect` are skipped before the straight skeleton is built, which saves most of the work
+    /// for features far outside the tile. This holds for deflation and for rounded inflation, while miter-joint-like corners can reach
+    /// arbitrarily far, so all polygons are buffered then. By default, the result is not clipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Area, BoundingRect, Polygon, LineString, Rect};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+    /// );
+    /// let tile = Rect::new((2., 2.), (10., 10.));
+    /// let p2 = buffer_polygon_with(&p1, 1., &BufferOptions::new().clip_to(tile));
+    ///
+    /// assert!((p2.unsigned_area() - 9.).abs() < 1e-9);
+    /// assert_eq!(p2.bounding_rect().unwrap(), Rect::new((2., 2.), (5., 5.)));
+    /// ```
+    #[must_use]
+    pub fn clip_to(mut self, rect: Rect) -> Self {
+        self.clip = Some(rect);
+        self
+    }
+
+    /// Rounds the coordinates of the result to `decimals` decimal places, as [`output_grid`](BufferOptions::output_grid) does
+    /// with a grid of size `10^-decimals`. By default, the coordinates are not rounded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `decimals` is outside `-307..=308`, where `10^decimals` is zero, infinite or too small to be divided by.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{CoordsIter, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 0.2, &BufferOptions::new().rounded(true).output_precision(2));
+    ///
+    /// for c in p2.coords_iter() {
+    ///     assert_eq!(c.x, (c.x * 100.).round() / 100.);
+    ///     assert_eq!(c.y, (c.y * 100.).round() / 100.);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn output_precision(mut self, decimals: i32) -> Self {
+        assert!(
+            (-307..=308).contains(&decimals),
+            "the number of decimal places must be within -307..=308"
+        );
+        self.output_scale = Some(10f64.powi(decimals));
+        self
+    }
+
+    /// Snaps the coordinates of the result to the nearest points of a square grid of cells of size `size`, e.g. the
+    /// coordinate space of a vector tile. By default, the coordinates are not snapped.
+    ///
+    /// This is the last stage before the rings are wound, so that the rings are closed on the grid. Vertices which become
+    /// coincident are merged, and rings left with fewer than three vertices are removed, together with the polygon if it is the exterior.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not positive and finite, or so small (subnormal) that the coordinates cannot be divided by it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0.1, 0.1), (3.9, 0.2), (3.8, 4.1), (0.2, 3.9)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 0.4, &BufferOptions::new().rounded(true).output_grid(1.));
+    ///
+    /// assert!(p2.0[0].exterior().coords().all(|c| c.x.fract() == 0. && c.y.fract() == 0.));
+    /// assert!(p2.0[0].exterior().0.windows(2).all(|w| w[0] != w[1]));
+    /// ```
+    #[must_use]
+    pub fn output_grid(mut self, size: f64) -> Self {
+        assert!(
+            size.is_normal() && size > 0.,
+            "the size of the output grid must be positive and finite"
+        );
+        self.output_scale = Some(size.recip());
+        self
+    }
+
+    /// Sets a token to cancel the computation with. The construction of the straight skeleton stops shortly after the token is cancelled.
+    ///
+    /// The `try_` functions (e.g. [`try_buffer_polygon_with`](crate::try_buffer_polygon_with)) then return [`Error::Cancelled`],
+    /// while the others return an empty `MultiPolygon`. By default, the computation cannot be cancelled.
+    #[must_use]
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Sets a point in time to abort the computation at. The construction of the straight skeleton stops shortly after the deadline.
+    ///
+    /// The `try_` functions (e.g. [`try_buffer_polygon_with`](crate::try_buffer_polygon_with)) then return [`Error::TimedOut`],
+    /// while the others return an empty `MultiPolygon`. By default, there is no deadline.
+    ///
+    /// There is no clock on `wasm32-unknown-unknown`, so deadlines must not be set there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use geo_buf::{try_buffer_polygon_with, BufferOptions, Error};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let expired = BufferOptions::new().deadline(Instant::now());
+    /// assert_eq!(try_buffer_polygon_with(&p1, 0.2, &expired), Err(Error::TimedOut));
+    ///
+    /// let generous = BufferOptions::new().deadline(Instant::now() + Duration::from_secs(60));
+    /// assert!(try_buffer_polygon_with(&p1, 0.2, &generous).is_ok());
+    /// ```
+    #[must_use]
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets the maximal number of events of the construction of the straight skeleton, counting the events still in the queue.
+    ///
+    /// The number of events usually grows linearly with the number of vertices of the input, but adversarial inputs can make it grow
+    /// much faster. When the limit is exceeded, the `try_` functions (e.g. [`try_buffer_polygon_with`](crate::try_buffer_polygon_with))
+    /// return [`Error::TooManyEvents`], while the others return an empty `MultiPolygon`. By default, there is no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{try_buffer_polygon_with, BufferOptions, Error};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let options = BufferOptions::new().max_events(2);
+    /// assert_eq!(try_buffer_polygon_with(&p1, -0.2, &options), Err(Error::TooManyEvents(2)));
+    ///
+    /// let options = BufferOptions::new().max_events(1000);
+    /// assert!(try_buffer_polygon_with(&p1, -0.2, &options).is_ok());
+    /// ```
+    #[must_use]
+    pub fn max_events(mut self, max_events: usize) -> Self {
+        self.max_events = Some(max_events);
+        self
+    }
+
+    /// Sets whether to buffer the exteriors only, copying the interiors (holes) of the input into the result as they are.
+    /// The default is `false`.
+    ///
+    /// Each hole goes into the polygon of the result whose exterior contains it. A hole crossing the boundary of the result
+    /// (e.g. when deflating) is cut out of the result instead, and a hole outside of the result is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+    ///     vec![LineString::from(vec![(3., 3.), (3., 7.), (7., 7.), (7., 3.), (3., 3.)])],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, 1., &BufferOptions::new().preserve_holes(true));
+    ///
+    /// assert_eq!(p2.0[0].exterior().0[0], (-1., -1.).into());
+    /// assert_eq!(p2.0[0].interiors()[0], p1.interiors()[0]);
+    /// ```
+    #[must_use]
+    pub fn preserve_holes(mut self, preserve_holes: bool) -> Self {
+        self.preserve_holes = preserve_holes;
+        self
+    }
+
+    /// Sets whether to remove the degenerate vertices of the input before buffering it. The default is `false`.
+    ///
+    /// A vertex is degenerate if it repeats the previous vertex, or if it lies on the line through its neighbours
+    /// (including the tip of a spike of zero width). The bisector of the edges at such a vertex is undefined, so it can
+    /// make the straight skeleton, and hence the result, go astray. Rings left with fewer than three vertices are removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (5., 0.), (5., 0.), (10., 0.), (10., 10.), (12., 10.), (10., 10.), (0., 10.)]),
+    ///     vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, -1., &BufferOptions::new().merge_degenerate_vertices(true));
+    ///
+    /// let expected_exterior = LineString::from(vec![(1., 1.), (9., 1.), (9., 9.), (1., 9.), (1., 1.)]);
+    /// assert_eq!(&expected_exterior, p2.0[0].exterior());
+    /// ```
+    #[must_use]
+    pub fn merge_degenerate_vertices(mut self, merge_degenerate_vertices: bool) -> Self {
+        self.merge_degenerate_vertices = merge_degenerate_vertices;
+        self
+    }
+
+    /// Snaps the vertices of the input to the nearest points of a square grid of cells of size `size` before buffering it,
+    /// then removes the degenerate vertices this creates as [`merge_degenerate_vertices`](BufferOptions::merge_degenerate_vertices) does.
+    /// By default, the input is not snapped.
+    ///
+    /// Vertices a rounding error away from coinciding, or from lying on the line through their neighbours, make the events of the
+    /// straight skeleton happen in an arbitrary order. Snapping turns them into exactly coincident or collinear vertices, which are
+    /// then removed. The input moves by at most `size / sqrt(2)`, so the grid should be well below the precision of the data.
+    /// With a power of two as `size`, the snapped coordinates are exactly multiples of it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not positive and finite, or so small (subnormal) that the coordinates cannot be divided by it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (5., 1e-12), (10., 0.), (10., 10.), (1e-13, 10.)]), vec![],
+    /// );
+    /// let p2 = buffer_polygon_with(&p1, -1., &BufferOptions::new().snap_input(2f64.powi(-30)));
+    ///
+    /// let expected_exterior = LineString::from(vec![(1., 1.), (9., 1.), (9., 9.), (1., 9.), (1., 1.)]);
+    /// assert_eq!(&expected_exterior, p2.0[0].exterior());
+    /// ```
+    #[must_use]
+    pub fn snap_input(mut self, size: f64) -> Self {
+        assert!(
+            size.is_normal() && size > 0.,
+            "the size of the input grid must be positive and finite"
+        );
+        self.input_scale = Some(size.recip());
+        self
+    }
+
+    /// Sets whether to emit the vertices of the result which are offsets of vertices of the input bit-identically,
+    /// exactly as the straight skeleton places them. The default is `false`.
+    ///
+    /// [`repair`](BufferOptions::repair), [`resolve_overlaps`](BufferOptions::resolve_overlaps) and [`clip_to`](BufferOptions::clip_to)
+    /// compute the result with boolean operations, which round its coordinates slightly. With this option, the vertices rounded away from
+    /// such an offset are moved back onto it, so that snapping the result to other geometries stays stable over repeated buffers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon, buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString, Rect};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0.1, 0.2), (3.3, 0.7), (2.9, 3.1), (0.3, 2.3)]), vec![],
+    /// );
+    /// let tile = Rect::new((-1., -1.), (3., 5.));
+    /// let p2 = buffer_polygon(&p1, 0.3);
+    /// let p3 = buffer_polygon_with(&p1, 0.3, &BufferOptions::new().clip_to(tile).exact_vertices(true));
+    ///
+    /// // The vertices of the unclipped buffer inside the tile are kept as they are.
+    /// for c in p2.0[0].exterior().coords().filter(|c| c.x < 3.) {
+    ///     assert!(p3.0[0].exterior().coords().any(|d| d == c));
+    /// }
+    /// ```
+    #[must_use]
+    pub fn exact_vertices(mut self, exact_vertices: bool) -> Self {
+        self.exact_vertices = exact_vertices;
+        self
+    }
+
+    /// Sets whether to keep the offsets of vertices of the input lying on the line through their neighbours in the result.
+    /// The default is `false`. This implies [`exact_vertices`](BufferOptions::exact_vertices).
+    ///
+    /// The buffer keeps such collinear vertices, but [`simplify`](BufferOptions::simplify) and the boolean operations of
+    /// [`repair`](BufferOptions::repair), [`resolve_overlaps`](BufferOptions::resolve_overlaps) and [`clip_to`](BufferOptions::clip_to)
+    /// merge the segments through them. With this option, they are inserted into the segments again afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (5., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+    /// );
+    /// let simplified = BufferOptions::new().simplify(0.01);
+    /// let p2 = buffer_polygon_with(&p1, 1., &simplified);
+    /// let p3 = buffer_polygon_with(&p1, 1., &simplified.keep_collinear_vertices(true));
+    ///
+    /// assert_eq!(p2.0[0].exterior().0.len(), 5);
+    /// assert_eq!(p3.0[0].exterior().0.len(), 6);
+    /// assert!(p3.0[0].exterior().0.contains(&(5., -1.).into()));
+    /// ```
+    #[must_use]
+    pub fn keep_collinear_vertices(mut self, keep_collinear_vertices: bool) -> Self {
+        self.keep_collinear_vertices = keep_collinear_vertices;
+        self
+    }
+
+    /// Sets how the construction of the straight skeleton orders events happening at the same time. The default is
+    /// [`TieBreak::OriginDistance`].
+    ///
+    /// With [`TieBreak::Location`], the order only depends on the geometry of the input, so identical shapes whose events tie
+    /// exactly give identical results whichever vertex their rings start at.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions, TieBreak};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let ring = vec![(0., 0.), (3., 0.), (3., 1.), (2., 1.), (2., 3.), (1., 3.), (1., 1.), (0., 1.)];
+    /// let options = BufferOptions::new().tie_break(TieBreak::Location);
+    /// let p2 = buffer_polygon_with(&Polygon::new(LineString::from(ring.clone()), vec![]), -0.5, &options);
+    /// for k in 1..ring.len() {
+    ///     let mut rotated = ring.clone();
+    ///     rotated.rotate_left(k);
+    ///     let p3 = buffer_polygon_with(&Polygon::new(LineString::from(rotated), vec![]), -0.5, &options);
+    ///     assert_eq!(p2, p3);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Sets the algorithm computing the buffer. The default is [`Algorithm::StraightSkeleton`].
+    ///
+    /// [`Algorithm::OffsetUnion`] builds no straight skeleton: it sweeps each edge of the input by the distance, fills the gaps
+    /// at the vertices with the corners, and unites the swept regions with the input (or subtracts them from it) by a boolean
+    /// operation. It is slower, but it is independent of the straight skeleton, so the two algorithms can be checked against
+    /// each other. [`exact_vertices`](BufferOptions::exact_vertices) and [`keep_collinear_vertices`](BufferOptions::keep_collinear_vertices)
+    /// have no effect with it, and neither do the cancel token, deadline and maximal number of events.
+    ///
+    /// [`Algorithm::Rectilinear`] is a faster special case for input whose edges are all parallel to the axes: the buffer of such
+    /// a polygon is its offset in the L∞ norm, whose corners are square whatever [`corners`](BufferOptions::corners) says,
+    /// and it takes a single boolean operation on the rectangles swept by the edges, in O(n log n) for n edges.
+    /// Any other input is buffered along its straight skeleton. The same options as with [`Algorithm::OffsetUnion`] have no effect.
+    ///
+    /// If the construction of the straight skeleton panics on a pathological input, or its result has non-finite coordinates,
+    /// the buffer is computed by [`Algorithm::OffsetUnion`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, Algorithm, BufferOptions};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 1.), (1., 1.), (1., 3.), (0., 3.)]), vec![],
+    /// );
+    /// let offset_union = BufferOptions::new().algorithm(Algorithm::OffsetUnion);
+    /// for distance in [0.5, -0.25] {
+    ///     let p2 = buffer_polygon_with(&p1, distance, &BufferOptions::new());
+    ///     let p3 = buffer_polygon_with(&p1, distance, &offset_union);
+    ///     assert!((p2.unsigned_area() - p3.unsigned_area()).abs() < 1e-9);
+    /// }
+    ///
+    /// let rectilinear = BufferOptions::new().algorithm(Algorithm::Rectilinear);
+    /// let p4 = buffer_polygon_with(&p1, 0.5, &rectilinear);
+    /// assert_eq!(p4.0[0].exterior().0.len(), 7);
+    /// assert!((p4.unsigned_area() - 14.).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the thread pool to run the parallel parts of the computation on, instead of the global pool of `rayon`.
+    /// Requires the `rayon` feature.
+    ///
+    /// This confines the work to a bounded pool, e.g. one built with a maximal number of threads by
+    /// [`ThreadPoolBuilder::num_threads`](rayon::ThreadPoolBuilder::num_threads), which clones of these options share.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use geo_buf::{buffer_multi_polygon_individual, BufferOptions};
+    /// use geo::{MultiPolygon, Polygon, LineString};
+    ///
+    /// let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap());
+    /// let options = BufferOptions::new().thread_pool(pool);
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let mp2 = buffer_multi_polygon_individual(&MultiPolygon::new(vec![p1.clone(), p1]), 0.2, &options);
+    /// assert_eq!(mp2.len(), 2);
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn thread_pool(mut self, thread_pool: Arc<rayon::ThreadPool>) -> Self {
+        self.thread_pool = Some(SharedThreadPool(thread_pool));
+        self
+    }
+
+    /// Runs `op` on the thread pool of these options, or on the current thread if there is none, so that the parallel
+    /// iterators in `op` run on that pool.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match &self.thread_pool {
+            Some(pool) => pool.0.install(op),
+            None => op(),
+        }
+    }
+
+    /// Returns a [`SkeletonBuilder`] building the straight skeletons as buffering with these options does, i.e. with their
+    /// [`tie_break`](BufferOptions::tie_break) and [`thread_pool`](BufferOptions::thread_pool).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, BufferOptions, Side, TieBreak};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let options = BufferOptions::new().tie_break(TieBreak::Location);
+    /// let mut builder = options.skeleton_builder();
+    /// let skel = builder.skeleton_of_polygon(&p1, Side::Interior);
+    ///
+    /// assert_eq!(skel.buffer(0.45, &options), skeleton_of_polygon(&p1, Side::Interior).buffer(0.45, &options));
+    /// ```
+    #[must_use]
+    pub fn skeleton_builder(&self) -> SkeletonBuilder {
+        let builder = SkeletonBuilder::new().tie_break(self.tie_break);
+        #[cfg(feature = "rayon")]
+        if let Some(pool) = &self.thread_pool {
+            return builder.thread_pool(pool.0.clone());
+        }
+        builder
+    }
+
+    /// Returns an upper bound of the distance between the boundary of the result of buffering by `distance` with these options
+    /// and the exact offset curve, i.e. of their Hausdorff distance.
+    ///
+    /// With [`rounded`](BufferOptions::rounded) corners, each arc of radius `|distance|` is approximated by chords, which cut
+    /// into it by at most `|distance| * (1 - cos(step / 2))` for the angular step between the vertices of the arc. Other corners
+    /// are exact. [`simplify`](BufferOptions::simplify) and [`min_segment_length`](BufferOptions::min_segment_length) may move
+    /// the boundary further, by at most their tolerances, which are added to the bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{buffer_polygon_with, BufferOptions};
+    /// use geo::{Distance, Euclidean, Point, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let options = BufferOptions::new().rounded(true);
+    /// let deviation = options.max_arc_deviation(2.);
+    /// let p2 = buffer_polygon_with(&p1, 2., &options);
+    ///
+    /// assert!(deviation > 0. && deviation < 0.01);
+    /// for w in p2.0[0].exterior().0.windows(2) {
+    ///     let midpoint = Point::from((w[0] + w[1]) / 2.);
+    ///     assert!(Euclidean::distance(&midpoint, &p1) >= 2. - deviation - 1e-9);
+    /// }
+    /// assert_eq!(BufferOptions::new().max_arc_deviation(2.), 0.);
+    /// ```
+    #[must_use]
+    pub fn max_arc_deviation(&self, distance: f64) -> f64 {
+        let arcs = if let Corners::Round(step) = self.corners {
+            distance.abs() * (1. - (step / 2.).cos())
+        } else {
+            0.
+        };
+        arcs + self.simplify.unwrap_or(0.) + self.min_segment_length.unwrap_or(0.)
+    }
+
+    /// Buffers the polygons `input` by `distance`. The result is empty if the computation is interrupted.
+    pub(crate) fn buffer(&self, input: &[Polygon], distance: f64) -> MultiPolygon {
+        self.buffer_with_builder(&mut self.skeleton_builder(), input, distance)
+    }
+
+    /// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, building the straight skeleton with `builder`.
+    pub(crate) fn buffer_with_builder(
+        &self,
+        builder: &mut SkeletonBuilder,
+        input: &[Polygon],
+        distance: f64,
+    ) -> MultiPolygon {
+        self.try_buffer_with_report(builder, input, distance, self.repair, None)
+            .map_or_else(|_| MultiPolygon::new(vec![]), |(res, _)| res)
+    }
+
+    /// Buffers each polygon of `input` by the distance of the same index in `distances`, and merges the results.
+    ///
+    /// The polygons with the same distance are buffered together, so that they merge as in [`BufferOptions::buffer`];
+    /// the results of different distances are unified afterwards.
+    pub(crate) fn buffer_per(&self, input: &[Polygon], distances: &[f64]) -> MultiPolygon {
+        let mut order: Vec<usize> = (0..input.len().min(distances.len())).collect();
+        order.sort_by(|&i, &j| distances[i].total_cmp(&distances[j]));
+        let mut groups = order.chunk_by(|&i, &j| distances[i] == distances[j]);
+        let mut builder = self.skeleton_builder();
+        let mut buffer_group = |group: &[usize]| {
+            let members: Vec<Polygon> = group.iter().map(|&i| input[i].clone()).collect();
+            self.buffer_with_builder(&mut builder, &members, distances[group[0]])
+        };
+        let Some(first) = groups.next() else {
+            return MultiPolygon::new(vec![]);
+        };
+        let mut res = buffer_group(first);
+        let mut merged = false;
+        for group in groups {
+            res.0.extend(buffer_group(group));
+            merged = true;
+        }
+        if merged {
+            // The union needs counter-clockwise exteriors, so the winding of the results is restored afterwards.
+            let winding = res
+                .0
+                .first()
+                .and_then(|p| p.exterior().winding_order())
+                .unwrap_or(WindingOrder::CounterClockwise);
+            postprocess::orient(&mut res, WindingOrder::CounterClockwise);
+            repair::repair(&mut res);
+            postprocess::orient(&mut res, winding);
+        }
+        res
+    }
+
+    /// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, validating the result if `validate` is `true`.
+    pub(crate) fn buffer_with_report(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+        validate: bool,
+    ) -> (MultiPolygon, RepairReport) {
+        let (res, diagnostics) = self.buffer_with_diagnostics(input, distance, validate);
+        (res, diagnostics.repair)
+    }
+
+    /// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, together with the [`Diagnostics`] of the result.
+    pub(crate) fn buffer_with_diagnostics(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+        validate: bool,
+    ) -> (MultiPolygon, Diagnostics) {
+        self.try_buffer_with_report(
+            &mut self.skeleton_builder(),
+            input,
+            distance,
+            validate,
+            None,
+        )
+        .unwrap_or_else(|_| (MultiPolygon::new(vec![]), Diagnostics::default()))
+    }
+
+    /// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, together with the [`VertexOrigin`] of each vertex
+    /// of the result, grouped by polygon and by ring as its coordinates.
+    pub(crate) fn buffer_with_provenance(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+    ) -> (MultiPolygon, Vec<Vec<Vec<VertexOrigin>>>) {
+        let skel = if self.skips_skeleton(input) {
+            Err(Error::NumericFailure)
+        } else {
+            self.try_skeleton(&mut self.skeleton_builder(), input, distance, None)
+        };
+        let Ok(skel) = skel else {
+            // Without a straight skeleton, no vertex can be traced back.
+            let res = self.buffer(input, distance);
+            let origins = res
+                .iter()
+                .map(|p| {
+                    std::iter::once(p.exterior())
+                        .chain(p.interiors())
+                        .map(|ls| vec![VertexOrigin::Unknown; ls.0.len()])
+                        .collect()
+                })
+                .collect();
+            return (res, origins);
+        };
+        let Some((skel, holes, shift)) = skel else {
+            return (MultiPolygon::new(vec![]), Vec::new());
+        };
+        let offset_distance = f64::abs(distance);
+        let (res, _) = self.buffer_skeleton_with_report(
+            &skel,
+            offset_distance,
+            self.repair,
+            &holes,
+            shift,
+            &mut Vec::new(),
+        );
+        // The vertices of the result may have moved by the rounding of the validation or onto the output grid.
+        let extent = res
+            .coords_iter()
+            .fold(1f64, |m, c| m.max(c.x.abs()).max(c.y.abs()));
+        let tolerance = self.output_scale.map_or(0., f64::recip).max(extent * 1e-9);
+        let origins = provenance::trace(
+            &skel,
+            offset_distance,
+            self.corners,
+            shift,
+            input,
+            &res,
+            tolerance,
+        );
+        (res, origins)
+    }
+
+    /// Buffers the polygons `input` by `distance`, or returns the reason why the computation was interrupted.
+    pub(crate) fn try_buffer(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+    ) -> Result<MultiPolygon, Error> {
+        let (res, _) = self.try_buffer_with_report(
+            &mut self.skeleton_builder(),
+            input,
+            distance,
+            self.repair,
+            None,
+        )?;
+        Ok(res)
+    }
+
+    /// Buffers the polygons `input` by `distance` as [`BufferOptions::buffer`] does, reporting the progress of the construction
+    /// of the straight skeleton to `progress`.
+    pub(crate) fn buffer_with_progress(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+        progress: &mut dyn FnMut(Progress),
+    ) -> MultiPolygon {
+        self.try_buffer_with_report(
+            &mut self.skeleton_builder(),
+            input,
+            distance,
+            self.repair,
+            Some(progress),
+        )
+        .map_or_else(|_| MultiPolygon::new(vec![]), |(res, _)| res)
+    }
+
+    fn try_buffer_with_report(
+        &self,
+        builder: &mut SkeletonBuilder,
+        input: &[Polygon],
+        distance: f64,
+        validate: bool,
+        progress: Option<&mut dyn FnMut(Progress)>,
+    ) -> Result<(MultiPolygon, Diagnostics), Error> {
+        if self.skips_skeleton(input) {
+            return Ok(self.buffer_offset_union(input, distance, validate, Vec::new()));
+        }
+        let skel = match self.try_skeleton(builder, input, distance, progress) {
+            Err(Error::NumericFailure) => {
+                return Ok(self.buffer_offset_union(
+                    input,
+                    distance,
+                    validate,
+                    vec![Warning::FellBack],
+                ))
+            }
+            skel => skel?,
+        };
+        let Some((skel, holes, shift)) = skel else {
+            return Ok((MultiPolygon::new(vec![]), Diagnostics::default()));
+        };
+        Ok(self.buffer_skeleton_with_report(
+            &skel,
+            f64::abs(distance),
+            validate,
+            &holes,
+            shift,
+            &mut Vec::new(),
+        ))
+    }
+
+    /// Buffers the polygons `polygons` by `distance` as [`BufferOptions::buffer`] does, and replaces them with the result.
+    ///
+    /// The coordinate vectors of the rings of the input are reused for the rings of the result, as far as there are enough of them.
+    pub(crate) fn buffer_in_place(&self, polygons: &mut Vec<Polygon>, distance: f64) {
+        let skel = if self.skips_skeleton(polygons) {
+            Err(Error::NumericFailure)
+        } else {
+            self.try_skeleton(&mut self.skeleton_builder(), polygons, distance, None)
+        };
+        if let Err(Error::NumericFailure) = skel {
+            let (res, _) = self.buffer_offset_union(polygons, distance, self.repair, Vec::new());
+            *polygons = res.0;
+            return;
+        }
+        let mut spare: Vec<Vec<Coord>> = polygons
+            .drain(..)
+            .flat_map(|p| {
+                let (exterior, interiors) = p.into_inner();
+                std::iter::once(exterior).chain(interiors)
+            })
+            .map(|ls| {
+                let mut coords = ls.0;
+                coords.clear();
+                coords
+            })
+            .collect();
+        if let Ok(Some((skel, holes, shift))) = skel {
+            let (res, _) = self.buffer_skeleton_with_report(
+                &skel,
+                f64::abs(distance),
+                self.repair,
+                &holes,
+                shift,
+                &mut spare,
+            );
+            polygons.extend(res);
+        }
+    }
+
+    /// Checks whether these options compute the buffer of `input` without a straight skeleton.
+    fn skips_skeleton(&self, input: &[Polygon]) -> bool {
+        match self.algorithm {
+            Algorithm::StraightSkeleton => false,
+            Algorithm::OffsetUnion => true,
+            Algorithm::Rectilinear => rectilinear::is_rectilinear(input),
+        }
+    }
+
+    /// Buffers the polygons `input` by `distance` with [`Algorithm::OffsetUnion`], or with [`Algorithm::Rectilinear`] if these
+    /// options ask for it and the input is rectilinear, validating the result if `validate` is `true`.
+    ///
+    /// `warnings` holds the warnings of the stages before.
+    fn buffer_offset_union(
+        &self,
+        input: &[Polygon],
+        distance: f64,
+        validate: bool,
+        warnings: Vec<Warning>,
+    ) -> (MultiPolygon, Diagnostics) {
+        let (members, holes) = self.members(input, distance);
+        let res =
+            if self.algorithm == Algorithm::Rectilinear && rectilinear::is_rectilinear(&members) {
+                rectilinear::buffer(&members, distance)
+            } else {
+                offset_union::buffer(&members, distance, self.corners)
+            };
+        let clockwise_input = input.first().and_then(|p| p.exterior().winding_order())
+            == Some(WindingOrder::Clockwise);
+        self.finish(res, validate, &holes, None, clockwise_input, warnings)
+    }
+
+    /// Returns the polygons of `input` to buffer by `distance` after the preprocessing these options ask for,
+    /// together with the holes to copy into the result.
+    fn members<'a>(
+        &self,
+        input: &'a [Polygon],
+        distance: f64,
+    ) -> (Cow<'a, [Polygon]>, Vec<LineString>) {
+        let offset_distance = f64::abs(distance);
+        // How far the buffer reaches beyond the input, unless it is unbounded.
+        let reach = match (Side::of_distance(distance), self.corners) {
+            (Side::Interior, _) => Some(0.),
+            (Side::Exterior, Corners::Miter) => None,
+            (Side::Exterior, Corners::MiterLimit(limit)) => {
+                Some((limit.max(1.) + 1.) * offset_distance)
+            }
+            (Side::Exterior, Corners::Round(_) | Corners::Bevel) => Some(offset_distance),
+        };
+        let mut members = match (self.clip, reach) {
+            (Some(rect), Some(reach)) => Cow::Owned(postprocess::polygons_near(input, rect, reach)),
+            _ => Cow::Borrowed(input),
+        };
+        if let Some(scale) = self.input_scale {
+            members = Cow::Owned(repair::snap_input(&members, scale));
+        }
+        if self.merge_degenerate_vertices {
+            members = Cow::Owned(repair::merge_degenerate_vertices(&members));
+        }
+        if let Some(max_segment_length) = self.densify.filter(|&length| length > 0.) {
+            members = Cow::Owned(
+                members
+                    .iter()
+                    .map(|p| p.densify::<Euclidean>(max_segment_length))
+                    .collect(),
+            );
+        }
+        // The holes to copy into the result, and the input without them.
+        let mut holes = Vec::new();
+        if self.preserve_holes {
+            holes = members
+                .iter()
+                .flat_map(|p| p.interiors())
+                .cloned()
+                .collect();
+            members = Cow::Owned(
+                members
+                    .iter()
+                    .map(|p| Polygon::new(p.exterior().clone(), vec![]))
+                    .collect(),
+            );
+        }
+        (members, holes)
+    }
+
+    /// Builds the straight skeleton to buffer the polygons `input` by `distance` with, together with the holes to copy into the result
+    /// and the translation from the coordinates of the skeleton to the ones of `input`, see [`repair::normalizing_shift`].
+    ///
+    /// Returns `None` if there is nothing to buffer.
+    pub(crate) fn try_skeleton(
+        &self,
+        builder: &mut SkeletonBuilder,
+        input: &[Polygon],
+        distance: f64,
+        progress: Option<&mut dyn FnMut(Progress)>,
+    ) -> Result<Option<(Skeleton, Vec<LineString>, Coord)>, Error> {
+        let (mut members, holes) = self.members(input, distance);
+        if members.is_empty() {
+            return Ok(None);
+        }
+        let shift = repair::normalizing_shift(&members);
+        if shift != Coord::zero() {
+            for p in members.to_mut() {
+                p.translate_mut(-shift.x, -shift.y);
+            }
+        }
+        let monitor = Monitor::new(
+            self.cancel.as_ref(),
+            self.deadline,
+            self.max_events,
+            progress,
+        );
+        let mut skel = builder.try_skeleton_of_polygon_vector(
+            &members,
+            Side::of_distance(distance),
+            monitor,
+        )?;
+        if members.len() < input.len() {
+            skel.clockwise_input = input.first().and_then(|p| p.exterior().winding_order())
+                == Some(WindingOrder::Clockwise);
+        }
+        Ok(Some((skel, holes, shift)))
+    }
+
+    /// Buffers the input of the skeleton `skel` by the (absolute) distance `offset_distance`.
+    pub(crate) fn buffer_skeleton(&self, skel: &Skeleton, offset_distance: f64) -> MultiPolygon {
+        self.buffer_shifted_skeleton(skel, offset_distance, Coord::zero())
+    }
+
+    /// Buffers the input of the skeleton `skel` by the (absolute) distance `offset_distance`, where `skel` has been built
+    /// by [`BufferOptions::try_skeleton`] on the input translated by the opposite of `shift`.
+    pub(crate) fn buffer_shifted_skeleton(
+        &self,
+        skel: &Skeleton,
+        offset_distance: f64,
+        shift: Coord,
+    ) -> MultiPolygon {
+        let (res, _) = self.buffer_skeleton_with_report(
+            skel,
+            offset_distance,
+            self.repair,
+            &[],
+            shift,
+            &mut Vec::new(),
+        );
+        res
+    }
+
+    /// Buffers the input of the skeleton `skel` by the (absolute) distance `offset_distance`, and translates the result by `shift`
+    /// before the postprocessing.
+    fn buffer_skeleton_with_report(
+        &self,
+        skel: &Skeleton,
+        offset_distance: f64,
+        validate: bool,
+        holes: &[LineString],
+        shift: Coord,
+        spare: &mut Vec<Vec<Coord>>,
+    ) -> (MultiPolygon, Diagnostics) {
+        let vq = skel.get_vertex_queue(offset_distance);
+        let mut anchors = (self.exact_vertices || self.keep_collinear_vertices)
+            .then(|| skel.input_vertex_offsets(&vq, offset_distance));
+        let mut res = match self.corners {
+            Corners::Miter => skel.apply_vertex_queue(&vq, offset_distance, spare),
+            corners => skel.apply_vertex_queue_with_corners(&vq, offset_distance, corners, spare),
+        };
+        let mut warnings = match self.corners {
+            Corners::MiterLimit(limit) => skel
+                .limited_miters(&vq, limit)
+                .into_iter()
+                .map(|vertex| Warning::MiterLimited { vertex })
+                .collect(),
+            _ => Vec::new(),
+        };
+        if !res
+            .coords_iter()
+            .all(|c| c.x.is_finite() && c.y.is_finite())
+        {
+            // The straight skeleton of a degenerate input went astray, so the buffer is computed without it.
+            let input = skel.input_polygons();
+            let distance = match skel.side() {
+                Side::Interior => -offset_distance,
+                Side::Exterior => offset_distance,
+            };
+            res = offset_union::buffer(&input.0, distance, self.corners);
+            anchors = None;
+            warnings = vec![Warning::FellBack];
+        }
+        if shift != Coord::zero() {
+            res.translate_mut(shift.x, shift.y);
+            for c in anchors.iter_mut().flatten() {
+                *c = *c + shift;
+            }
+            for warning in &mut warnings {
+                if let Warning::MiterLimited { vertex } = warning {
+                    *vertex = *vertex + shift;
+                }
+            }
+        }
+        self.finish(
+            res,
+            validate,
+            holes,
+            anchors.as_deref(),
+            skel.clockwise_input,
+            warnings,
+        )
+    }
+
+    /// Applies the postprocessing these options ask for to the raw buffer `res`, validating it if `validate` is `true`.
+    ///
+    /// `anchors` holds the offsets of the vertices of the input to move the vertices of the result back onto,
+    /// `clockwise_input` whether the exterior of the (first) input polygon is clockwise, and `warnings` the warnings of the stages before.
+    fn finish(
+        &self,
+        mut res: MultiPolygon,
+        validate: bool,
+        holes: &[LineString],
+        anchors: Option<&[Coord]>,
+        clockwise_input: bool,
+        mut warnings: Vec<Warning>,
+    ) -> (MultiPolygon, Diagnostics) {
+        if let Some(width) = self.min_width.filter(|&width| width > 0.) {
+            postprocess::remove_thin_parts(&mut res, width);
+        }
+        if let Some(tolerance) = self.simplify {
+            res = res.simplify(&tolerance);
+        }
+        if let Some(tolerance) = self.min_segment_length {
+            postprocess::remove_micro_segments(&mut res, tolerance, &mut warnings);
+        }
+        if self.min_output_area.is_some() || self.min_hole_area.is_some() {
+            postprocess::remove_small_rings(
+                &mut res,
+                self.min_output_area,
+                self.min_hole_area,
+                &mut warnings,
+            );
+        }
+        let mut report = RepairReport::default();
+        if validate {
+            report = repair::validate(&res);
+            if self.repair && !report.is_valid() {
+                repair::repair(&mut res);
+                report.repaired = true;
+            }
+        }
+        if !holes.is_empty() {
+            postprocess::restore_holes(&mut res, holes);
+        }
+        if self.resolve_overlaps {
+            repair::repair(&mut res);
+        }
+        if let Some(rect) = self.clip {
+            postprocess::clip(&mut res, rect);
+        }
+        if let Some(anchors) = anchors {
+            postprocess::restore_anchors(&mut res, anchors, self.keep_collinear_vertices);
+        }
+        if let Some(scale) = self.output_scale {
+            postprocess::snap_to_grid(&mut res, scale, &mut warnings);
+        }
+        let exterior_winding = match self.winding {
+            OutputWinding::Ogc => WindingOrder::CounterClockwise,
+            OutputWinding::Reversed => WindingOrder::Clockwise,
+            OutputWinding::MatchInput if clockwise_input => WindingOrder::Clockwise,
+            OutputWinding::MatchInput => WindingOrder::CounterClockwise,
+        };
+        postprocess::orient(&mut res, exterior_winding);
+        (
+            res,
+            Diagnostics {
+                warnings,
+                repair: report,
+            },
+        )
+    }
+}
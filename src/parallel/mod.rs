@@ -0,0 +1,55 @@
+//! Parallel buffering of many polygons, behind the `rayon` feature.
+
+use geo_types::{MultiPolygon, Polygon};
+use rayon::prelude::*;
+
+use crate::BufferOptions;
+
+/// Buffers each polygon of a slice on its own, in parallel with the work stealing of `rayon`.
+///
+/// This is the fastest way to buffer a batch of unrelated polygons, e.g. the parcels of a map: the result holds the buffer of
+/// each polygon in the order of the input, and the buffers of overlapping polygons are not unified.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, ParBuffer};
+/// use geo::{Polygon, LineString};
+///
+/// let polygons: Vec<Polygon> = (0..100)
+///     .map(|i| {
+///         let x = i as f64;
+///         Polygon::new(LineString::from(vec![(x, 0.), (x + 1., 0.), (x + 1., 1.), (x, 1.)]), vec![])
+///     })
+///     .collect();
+/// let buffered = polygons.par_buffer(0.25);
+///
+/// assert_eq!(buffered.len(), 100);
+/// assert_eq!(buffered[42], buffer_polygon(&polygons[42], 0.25));
+/// ```
+pub trait ParBuffer {
+    /// Returns the buffer of each polygon by `distance` as [`buffer_polygon`](crate::buffer_polygon) does, in the order of the input.
+    fn par_buffer(&self, distance: f64) -> Vec<MultiPolygon> {
+        self.par_buffer_with(distance, &BufferOptions::new())
+    }
+
+    /// Returns the buffer of each polygon by `distance` with `options` as [`buffer_polygon_with`](crate::buffer_polygon_with) does,
+    /// in the order of the input. The work runs on the [thread pool](BufferOptions::thread_pool) of `options` if it has one.
+    fn par_buffer_with(&self, distance: f64, options: &BufferOptions) -> Vec<MultiPolygon>;
+}
+
+impl ParBuffer for [Polygon] {
+    fn par_buffer_with(&self, distance: f64, options: &BufferOptions) -> Vec<MultiPolygon> {
+        options.install(|| {
+            // The polygons of each job of rayon share one skeleton builder.
+            self.par_iter()
+                .map_init(
+                    || options.skeleton_builder(),
+                    |builder, p| {
+                        options.buffer_with_builder(builder, std::slice::from_ref(p), distance)
+                    },
+                )
+                .collect()
+        })
+    }
+}
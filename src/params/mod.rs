@@ -0,0 +1,234 @@
+//! Buffer parameters in the terms of GEOS and JTS, to ease migrating code and tests written against them (e.g. through `geos-rs`).
+
+use std::f64::consts::FRAC_PI_2;
+
+use geo::Winding;
+use geo_types::{Coord, LineString, MultiPolygon, Point, Polygon, Rect};
+
+use crate::skeleton::Corners;
+use crate::util::CoordExt;
+use crate::{buffer_point, offset_union, repair, BufferOptions};
+
+/// The shape of the ends of a buffered line string or point, as the end cap styles of GEOS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EndCapStyle {
+    /// A half circle around each end.
+    #[default]
+    Round,
+    /// A straight edge through each end, perpendicular to the line string. Points vanish.
+    Flat,
+    /// A half square around each end, i.e. the line string is extended by the distance before a flat end.
+    Square,
+}
+
+/// The shape of the corners of a buffer around the convex vertices (the reflex ones when deflating), as the join styles of GEOS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum JoinStyle {
+    /// An arc around the vertex.
+    #[default]
+    Round,
+    /// The offset edges extended until they meet, cut off where they reach farther than the mitre limit.
+    Mitre,
+    /// A straight edge between the ends of the offset edges.
+    Bevel,
+}
+
+/// The buffer parameters of GEOS (and JTS), see [`BufferParams::geos_like`].
+///
+/// The default parameters are the ones of GEOS: 8 segments per quarter circle, round end caps, round joins and a mitre limit of 5.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BufferParams {
+    /// The number of segments approximating a quarter circle. With `0`, round joins become bevels and round end caps become flat.
+    pub quad_segments: usize,
+    /// The shape of the ends of line strings and points.
+    pub end_cap: EndCapStyle,
+    /// The shape of the corners.
+    pub join: JoinStyle,
+    /// How many times the distance a mitre may reach out from its vertex before it is cut off.
+    pub mitre_limit: f64,
+}
+
+impl Default for BufferParams {
+    fn default() -> Self {
+        Self::geos_like(8, EndCapStyle::Round, JoinStyle::Round, 5.)
+    }
+}
+
+impl BufferParams {
+    /// Creates the parameters which `BufferParams::setQuadrantSegments`, `setEndCapStyle`, `setJoinStyle` and `setMitreLimit`
+    /// of GEOS set, so that buffering with them gives (up to the rounding of the coordinates) the results of GEOS.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{BufferParams, EndCapStyle, JoinStyle};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let mitre = BufferParams::geos_like(8, EndCapStyle::Round, JoinStyle::Mitre, 5.);
+    /// assert_eq!(mitre.buffer_polygon(&p1, 1.).0[0].exterior().0.len(), 5);
+    ///
+    /// // The mitres reach sqrt(2) times the distance out, beyond a limit of 1.2, so they are cut off.
+    /// let limited = BufferParams::geos_like(8, EndCapStyle::Round, JoinStyle::Mitre, 1.2);
+    /// assert_eq!(limited.buffer_polygon(&p1, 1.).0[0].exterior().0.len(), 9);
+    ///
+    /// let round = BufferParams::default().buffer_polygon(&p1, 1.);
+    /// assert!((round.unsigned_area() - (5. + std::f64::consts::PI)).abs() < 0.05);
+    /// ```
+    #[must_use]
+    pub const fn geos_like(
+        quad_segments: usize,
+        end_cap: EndCapStyle,
+        join: JoinStyle,
+        mitre_limit: f64,
+    ) -> Self {
+        Self {
+            quad_segments,
+            end_cap,
+            join,
+            mitre_limit,
+        }
+    }
+
+    /// Returns the options to buffer polygons with these parameters by [`buffer_polygon_with`](crate::buffer_polygon_with).
+    /// End caps do not apply to polygons.
+    #[must_use]
+    pub fn to_options(&self) -> BufferOptions {
+        BufferOptions::new().corners(self.corners())
+    }
+
+    fn corners(&self) -> Corners {
+        match self.join {
+            JoinStyle::Round if self.quad_segments == 0 => Corners::Bevel,
+            JoinStyle::Round => Corners::Round(FRAC_PI_2 / self.quad_segments as f64),
+            JoinStyle::Mitre => Corners::MiterLimit(self.mitre_limit),
+            JoinStyle::Bevel => Corners::Bevel,
+        }
+    }
+
+    /// Returns the buffered (multi-)polygon of the given polygon with these parameters, as `GEOSBufferWithParams` does.
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn buffer_polygon(&self, input_polygon: &Polygon, distance: f64) -> MultiPolygon {
+        self.to_options()
+            .buffer(std::slice::from_ref(input_polygon), distance)
+    }
+
+    /// Returns the buffered (multi-)polygon of the given multi-polygon with these parameters, as `GEOSBufferWithParams` does.
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn buffer_multi_polygon(
+        &self,
+        input_multi_polygon: &MultiPolygon,
+        distance: f64,
+    ) -> MultiPolygon {
+        self.to_options().buffer(&input_multi_polygon.0, distance)
+    }
+
+    /// Returns the buffer of the given point with these parameters, as `GEOSBufferWithParams` does.
+    ///
+    /// The buffer is empty if `distance` is not positive or the end caps are flat.
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn buffer_point(&self, point: &Point, distance: f64) -> MultiPolygon {
+        if distance <= 0. {
+            return MultiPolygon::new(vec![]);
+        }
+        match self.end_cap {
+            EndCapStyle::Round if self.quad_segments > 0 => {
+                MultiPolygon::new(vec![buffer_point(point, distance, 4 * self.quad_segments)])
+            }
+            EndCapStyle::Round | EndCapStyle::Flat => MultiPolygon::new(vec![]),
+            EndCapStyle::Square => {
+                let reach = Coord {
+                    x: distance,
+                    y: distance,
+                };
+                MultiPolygon::new(vec![
+                    Rect::new(point.0 - reach, point.0 + reach).to_polygon()
+                ])
+            }
+        }
+    }
+
+    /// Returns the buffer of the given line string with these parameters, as `GEOSBufferWithParams` does.
+    ///
+    /// The buffer is empty if `distance` is not positive. A line string whose vertices all coincide is buffered as a point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{BufferParams, EndCapStyle, JoinStyle};
+    /// use geo::{Area, LineString};
+    ///
+    /// let ls = LineString::from(vec![(0., 0.), (10., 0.), (10., 10.)]);
+    /// let params = |end_cap| BufferParams::geos_like(8, end_cap, JoinStyle::Mitre, 5.);
+    ///
+    /// assert!((params(EndCapStyle::Flat).buffer_line_string(&ls, 1.).unsigned_area() - 40.).abs() < 1e-6);
+    /// assert!((params(EndCapStyle::Square).buffer_line_string(&ls, 1.).unsigned_area() - 44.).abs() < 1e-6);
+    /// ```
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn buffer_line_string(
+        &self,
+        input_line_string: &LineString,
+        distance: f64,
+    ) -> MultiPolygon {
+        if distance <= 0. {
+            return MultiPolygon::new(vec![]);
+        }
+        let mut coords: Vec<Coord> = input_line_string.0.clone();
+        coords.dedup();
+        let (Some(&first), Some(&last)) = (coords.first(), coords.last()) else {
+            return MultiPolygon::new(vec![]);
+        };
+        if coords.len() == 1 {
+            return self.buffer_point(&Point(first), distance);
+        }
+        let round = self.quad_segments > 0;
+        let disc = |c: Coord| buffer_point(&Point(c), distance, 4 * self.quad_segments);
+
+        let mut pieces = Vec::new();
+        let segments = coords.len() - 1;
+        for (i, w) in coords.windows(2).enumerate() {
+            let direction = (w[1] - w[0]) / (w[1] - w[0]).norm();
+            let normal = Coord {
+                x: -direction.y,
+                y: direction.x,
+            } * distance;
+            let (mut a, mut b) = (w[0], w[1]);
+            if self.end_cap == EndCapStyle::Square {
+                if i == 0 {
+                    a = a - direction * distance;
+                }
+                if i + 1 == segments {
+                    b = b + direction * distance;
+                }
+            }
+            pieces.push(vec![a - normal, b - normal, b + normal, a + normal]);
+        }
+        for w in coords.windows(3) {
+            pieces.extend(offset_union::join_piece(
+                w[0],
+                w[1],
+                w[2],
+                distance,
+                self.corners(),
+            ));
+        }
+        let mut res = MultiPolygon::new(
+            pieces
+                .into_iter()
+                .map(|ring| {
+                    let mut ls = LineString::from(ring);
+                    ls.close();
+                    ls.make_ccw_winding();
+                    Polygon::new(ls, vec![])
+                })
+                .collect(),
+        );
+        if self.end_cap == EndCapStyle::Round && round {
+            res.0.extend([disc(first), disc(last)]);
+        }
+        repair::repair(&mut res);
+        res
+    }
+}
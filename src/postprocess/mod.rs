@@ -0,0 +1,374 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use geo::winding_order::WindingOrder;
+use geo::{Area, BooleanOps, BoundingRect, Contains, Intersects, MapCoordsInPlace, Winding};
+use geo_types::{Coord, LineString, MultiPoint, MultiPolygon, Polygon, Rect};
+use rstar::{RTree, AABB};
+
+use crate::{BufferOptions, Warning};
+
+/// The distance, relative to the extent of the anchors, within which [`restore_anchors`] moves vertices onto them.
+///
+/// This covers the rounding of the coordinates by the boolean operations, which work on a grid of about `2^-29` of the extent.
+const ANCHOR_TOLERANCE: f64 = 1e-8;
+
+/// Brings `mp` into the canonical form of the output, see [`canonicalize_ring`].
+///
+/// The interiors of each polygon and the polygons are sorted lexicographically by their coordinates.
+pub(crate) fn canonicalize(mp: &mut MultiPolygon) {
+    for p in &mut mp.0 {
+        let mut exterior = p.exterior().clone();
+        canonicalize_ring(&mut exterior);
+        let mut interiors = p.interiors().to_vec();
+        interiors.iter_mut().for_each(canonicalize_ring);
+        interiors.sort_by(cmp_rings);
+        *p = Polygon::new(exterior, interiors);
+    }
+    mp.0.sort_by(|a, b| cmp_rings(a.exterior(), b.exterior()));
+}
+
+/// Rewinds the exterior of every polygon of `mp` to `exterior`, and the interiors to the opposite winding order.
+///
+/// Reversing a closed ring keeps its starting coordinate, so the canonical form is preserved.
+pub(crate) fn orient(mp: &mut MultiPolygon, exterior: WindingOrder) {
+    let wind = |ls: &mut LineString, winding: WindingOrder| match winding {
+        WindingOrder::Clockwise => ls.make_cw_winding(),
+        WindingOrder::CounterClockwise => ls.make_ccw_winding(),
+    };
+    let interior = match exterior {
+        WindingOrder::Clockwise => WindingOrder::CounterClockwise,
+        WindingOrder::CounterClockwise => WindingOrder::Clockwise,
+    };
+    for p in &mut mp.0 {
+        p.exterior_mut(|ls| wind(ls, exterior));
+        p.interiors_mut(|interiors| {
+            for ls in interiors {
+                wind(ls, interior);
+            }
+        });
+    }
+}
+
+/// Returns the polygons of `input` whose bounding rectangle, expanded by `reach`, intersects `rect`.
+pub(crate) fn polygons_near(input: &[Polygon], rect: Rect, reach: f64) -> Vec<Polygon> {
+    let reach = Coord { x: reach, y: reach };
+    input
+        .iter()
+        .filter(|p| {
+            p.bounding_rect()
+                .is_some_and(|r| Rect::new(r.min() - reach, r.max() + reach).intersects(&rect))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns, for each polygon of `mp` (the buffer of `input`), the indices of the polygons of `input` it intersects, in increasing order.
+///
+/// When inflating, the buffers of these polygons have grown into each other; when deflating, the polygon of `mp` is part of one of them.
+pub(crate) fn sources(mp: &MultiPolygon, input: &[Polygon]) -> Vec<Vec<usize>> {
+    let bounds: Vec<Option<Rect>> = input.iter().map(BoundingRect::bounding_rect).collect();
+    mp.iter()
+        .map(|p| {
+            let Some(rect) = p.bounding_rect() else {
+                return Vec::new();
+            };
+            input
+                .iter()
+                .zip(&bounds)
+                .enumerate()
+                .filter(|(_, (q, b))| b.is_some_and(|b| b.intersects(&rect)) && p.intersects(*q))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect()
+}
+
+/// Replaces `mp` by its intersection with `rect`. Nothing is done if `mp` is inside `rect`.
+pub(crate) fn clip(mp: &mut MultiPolygon, rect: Rect) {
+    let Some(bounds) = mp.bounding_rect() else {
+        return;
+    };
+    let inside = rect.min().x <= bounds.min().x
+        && rect.min().y <= bounds.min().y
+        && bounds.max().x <= rect.max().x
+        && bounds.max().y <= rect.max().y;
+    if inside {
+        return;
+    }
+    *mp = mp.intersection(&rect.to_polygon());
+    canonicalize(mp);
+}
+
+/// Removes the area covered by `mask` from `mp`.
+///
+/// Only the polygons of `mask` whose bounding rectangle intersects the one of `mp` take part in the difference.
+pub(crate) fn mask(mp: &mut MultiPolygon, mask: &MultiPolygon) {
+    let Some(bounds) = mp.bounding_rect() else {
+        return;
+    };
+    let barriers = MultiPolygon::new(
+        mask.0
+            .iter()
+            .filter(|p| p.bounding_rect().is_some_and(|r| r.intersects(&bounds)))
+            .cloned()
+            .collect(),
+    );
+    if barriers.0.is_empty() {
+        return;
+    }
+    *mp = mp.difference(&barriers);
+    canonicalize(mp);
+}
+
+/// Puts the rings `holes` back into `mp` as they are, each into the polygon whose exterior contains it.
+///
+/// Holes which cross the boundary of `mp` are cut out of it instead, and holes outside of `mp` are dropped.
+pub(crate) fn restore_holes(mp: &mut MultiPolygon, holes: &[LineString]) {
+    let mut crossing = Vec::new();
+    for hole in holes {
+        let around =
+            mp.0.iter_mut()
+                .find(|p| Polygon::new(p.exterior().clone(), vec![]).contains(hole));
+        match around {
+            Some(p) => p.interiors_push(hole.clone()),
+            None => crossing.push(Polygon::new(hole.clone(), vec![])),
+        }
+    }
+    mask(mp, &MultiPolygon::new(crossing));
+    canonicalize(mp);
+}
+
+/// Removes the polygons of `mp` whose exterior encloses less than `min_area`, and the interiors enclosing less than
+/// the larger of `min_area` and `min_hole_area`, adding a warning for each removed ring to `warnings`.
+pub(crate) fn remove_small_rings(
+    mp: &mut MultiPolygon,
+    min_area: Option<f64>,
+    min_hole_area: Option<f64>,
+    warnings: &mut Vec<Warning>,
+) {
+    let ring_area = |ls: &LineString| Polygon::new(ls.clone(), vec![]).unsigned_area();
+    let min_hole_area = f64::max(
+        min_area.unwrap_or(f64::NEG_INFINITY),
+        min_hole_area.unwrap_or(f64::NEG_INFINITY),
+    );
+    let mut next_polygon = 0;
+    mp.0.retain_mut(|p| {
+        let polygon = next_polygon;
+        next_polygon += 1;
+        let area = ring_area(p.exterior());
+        if min_area.is_some_and(|min_area| area < min_area) {
+            warnings.push(Warning::PolygonBelowMinArea { polygon, area });
+            return false;
+        }
+        let (exterior, mut interiors) =
+            std::mem::replace(p, Polygon::new(LineString::new(vec![]), vec![])).into_inner();
+        let mut next_hole = 0;
+        interiors.retain(|ls| {
+            let (hole, area) = (next_hole, ring_area(ls));
+            next_hole += 1;
+            if area < min_hole_area {
+                warnings.push(Warning::HoleBelowMinArea {
+                    polygon,
+                    hole,
+                    area,
+                });
+            }
+            area >= min_hole_area
+        });
+        *p = Polygon::new(exterior, interiors);
+        true
+    });
+}
+
+/// Removes the parts of `mp` narrower than `width` by a morphological opening: `mp` is deflated by `width / 2`, which removes
+/// the thin parts and cuts the thin necks, then the rest is inflated back by `width / 2` with rounded corners.
+///
+/// Every point of the result lies in a disk of diameter `width` within the result.
+pub(crate) fn remove_thin_parts(mp: &mut MultiPolygon, width: f64) {
+    let radius = width / 2.;
+    let eroded = BufferOptions::new().buffer(&mp.0, -radius);
+    *mp = BufferOptions::new().rounded(true).buffer(&eroded.0, radius);
+}
+
+/// Returns `c` with its coordinates rounded to multiples of `1 / scale`.
+pub(crate) fn snap(c: Coord, scale: f64) -> Coord {
+    Coord {
+        x: (c.x * scale).round() / scale,
+        y: (c.y * scale).round() / scale,
+    }
+}
+
+/// Rounds the coordinates of `mp` to multiples of `1 / scale`, then merges the vertices which have become coincident
+/// as [`remove_micro_segments`] does.
+pub(crate) fn snap_to_grid(mp: &mut MultiPolygon, scale: f64, warnings: &mut Vec<Warning>) {
+    mp.map_coords_in_place(|c| snap(c, scale));
+    // Distinct points of the grid are at least a cell apart.
+    remove_micro_segments(mp, 0.5 / scale, warnings);
+}
+
+/// Removes the vertices of every ring of `mp` closer than `tolerance` to the previously kept vertex of the ring.
+///
+/// Rings left with fewer than three distinct vertices are removed, adding a warning to `warnings`. If such a ring is
+/// the exterior of a polygon, the whole polygon is removed.
+pub(crate) fn remove_micro_segments(
+    mp: &mut MultiPolygon,
+    tolerance: f64,
+    warnings: &mut Vec<Warning>,
+) {
+    let mut next_polygon = 0;
+    mp.0.retain_mut(|p| {
+        let polygon = next_polygon;
+        next_polygon += 1;
+        let mut exterior = p.exterior().clone();
+        if !dedup_ring(&mut exterior, tolerance) {
+            warnings.push(Warning::RingCollapsed { polygon, ring: 0 });
+            return false;
+        }
+        let mut interiors = p.interiors().to_vec();
+        let mut next_ring = 1;
+        interiors.retain_mut(|ls| {
+            let ring = next_ring;
+            next_ring += 1;
+            let kept = dedup_ring(ls, tolerance);
+            if !kept {
+                warnings.push(Warning::RingCollapsed { polygon, ring });
+            }
+            kept
+        });
+        *p = Polygon::new(exterior, interiors);
+        true
+    });
+}
+
+/// Removes the vertices of the closed ring `ls` closer than `tolerance` to the previously kept vertex,
+/// and returns whether the ring still has at least three vertices.
+fn dedup_ring(ls: &mut LineString, tolerance: f64) -> bool {
+    let is_far = |a: &Coord, b: &Coord| f64::hypot(a.x - b.x, a.y - b.y) >= tolerance;
+    let len = if ls.is_closed() {
+        ls.0.len().saturating_sub(1)
+    } else {
+        ls.0.len()
+    };
+    let mut kept: Vec<Coord> = Vec::with_capacity(len + 1);
+    for c in &ls.0[..len] {
+        if kept.last().is_none_or(|last| is_far(last, c)) {
+            kept.push(*c);
+        }
+    }
+    while kept.len() > 1 && !is_far(&kept[kept.len() - 1], &kept[0]) {
+        kept.pop();
+    }
+    if kept.len() < 3 {
+        return false;
+    }
+    kept.push(kept[0]);
+    ls.0 = kept;
+    true
+}
+
+/// Moves the vertices of `mp` close to a vertex of `anchors` onto it, so that the anchors are restored bit-identically after the
+/// postprocessing has rounded them. If `keep_collinear` is `true`, the anchors close to a segment of `mp` (which have been dropped
+/// as collinear) are also inserted into it.
+///
+/// "Close" is within [`ANCHOR_TOLERANCE`] of the extent of the anchors.
+pub(crate) fn restore_anchors(mp: &mut MultiPolygon, anchors: &[Coord], keep_collinear: bool) {
+    let Some(bounds) = MultiPoint::from(anchors.to_vec()).bounding_rect() else {
+        return;
+    };
+    let tolerance = ANCHOR_TOLERANCE * (1. + f64::max(bounds.width(), bounds.height()));
+    let tree = RTree::bulk_load(anchors.iter().map(|c| [c.x, c.y]).collect());
+    let restore = |ls: &mut LineString| restore_ring(ls, &tree, tolerance, keep_collinear);
+    for p in &mut mp.0 {
+        p.exterior_mut(restore);
+        p.interiors_mut(|interiors| interiors.iter_mut().for_each(restore));
+    }
+    canonicalize(mp);
+}
+
+fn restore_ring(ls: &mut LineString, tree: &RTree<[f64; 2]>, tolerance: f64, keep_collinear: bool) {
+    for c in &mut ls.0 {
+        if let Some(&[x, y]) = tree.nearest_neighbor(&[c.x, c.y]) {
+            if f64::hypot(x - c.x, y - c.y) <= tolerance {
+                *c = Coord { x, y };
+            }
+        }
+    }
+    if !keep_collinear {
+        return;
+    }
+    let present: HashSet<CoordKey> = ls.0.iter().map(|&c| coord_key(c)).collect();
+    let mut res = Vec::with_capacity(ls.0.len());
+    for w in ls.0.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        res.push(a);
+        let d = b - a;
+        let len2 = d.x * d.x + d.y * d.y;
+        if len2 == 0. {
+            continue;
+        }
+        let envelope = AABB::from_corners(
+            [a.x.min(b.x) - tolerance, a.y.min(b.y) - tolerance],
+            [a.x.max(b.x) + tolerance, a.y.max(b.y) + tolerance],
+        );
+        let mut between: Vec<(f64, Coord)> = tree
+            .locate_in_envelope(&envelope)
+            .map(|&[x, y]| Coord { x, y })
+            .filter(|&c| !present.contains(&coord_key(c)))
+            .filter_map(|c| {
+                let t = ((c.x - a.x) * d.x + (c.y - a.y) * d.y) / len2;
+                let foot = a + d * t;
+                (0. < t && t < 1. && f64::hypot(c.x - foot.x, c.y - foot.y) <= tolerance)
+                    .then_some((t, c))
+            })
+            .collect();
+        between.sort_by(|u, v| u.0.total_cmp(&v.0));
+        res.extend(between.into_iter().map(|(_, c)| c));
+    }
+    res.extend(ls.0.last());
+    ls.0 = res;
+}
+
+/// Rotates the closed ring `ls` so that it starts at its lexicographically smallest coordinate.
+///
+/// If the smallest coordinate occurs more than once, the lexicographically smallest rotation among them is chosen.
+pub(crate) fn canonicalize_ring(ls: &mut LineString) {
+    if !ls.is_closed() || ls.0.len() < 3 {
+        return;
+    }
+    let len = ls.0.len() - 1;
+    let ring = &ls.0[..len];
+    let rotation = |i: usize| ring[i..].iter().chain(&ring[..i]);
+    let start = (0..len)
+        .min_by(|&i, &j| {
+            rotation(i)
+                .zip(rotation(j))
+                .map(|(a, b)| cmp_coords(a, b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+        .unwrap_or(0);
+    ls.0.truncate(len);
+    ls.0.rotate_left(start);
+    ls.close();
+}
+
+/// The bits of a coordinate, to find coordinates which are exactly equal by hashing.
+pub(crate) type CoordKey = (u64, u64);
+
+pub(crate) fn coord_key(c: Coord) -> CoordKey {
+    // Adding zero turns `-0.` into `0.`.
+    ((c.x + 0.).to_bits(), (c.y + 0.).to_bits())
+}
+
+pub(crate) fn cmp_coords(a: &Coord, b: &Coord) -> Ordering {
+    a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
+
+pub(crate) fn cmp_rings(a: &LineString, b: &LineString) -> Ordering {
+    a.0.iter()
+        .zip(&b.0)
+        .map(|(a, b)| cmp_coords(a, b))
+        .find(|o| o.is_ne())
+        .unwrap_or_else(|| a.0.len().cmp(&b.0.len()))
+}
@@ -1,80 +1,147 @@
+/// A handle to an item of a [`PriorityQueue`], returned by [`PriorityQueue::insert`].
+///
+/// Handles are never reused within a queue until it is initialized again, so a handle of an item
+/// which has already left the queue stays invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Handle(usize);
+
+/// Counts of the operations performed on a [`PriorityQueue`] since it was initialized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct QueueStats {
+    /// The number of inserted items.
+    pub(crate) inserted: usize,
+    /// The number of items taken out by [`PriorityQueue::pop`].
+    pub(crate) popped: usize,
+    /// The number of items taken out by [`PriorityQueue::remove`].
+    pub(crate) removed: usize,
+    /// The largest number of items in the queue at once.
+    pub(crate) peak_len: usize,
+}
+
+/// An indexed binary min-heap, whose items can be removed through the [`Handle`] returned on insertion.
 pub(crate) struct PriorityQueue<T: std::cmp::PartialOrd> {
-    size: usize,
-    content: Vec<T>,
+    /// The heap, holding each item together with its handle.
+    content: Vec<(T, Handle)>,
+    /// The position in `content` of the item of each handle, or `usize::MAX` if the item has left the queue.
+    positions: Vec<usize>,
+    stats: QueueStats,
 }
 
 impl<T: std::cmp::PartialOrd> PriorityQueue<T> {
     pub const fn new() -> Self {
         Self {
-            size: 0,
             content: Vec::new(),
+            positions: Vec::new(),
+            stats: QueueStats {
+                inserted: 0,
+                popped: 0,
+                removed: 0,
+                peak_len: 0,
+            },
         }
     }
 
-    /// Clears content and sets size to 0
-    #[allow(dead_code)]
+    /// Clears content, invalidates every handle and resets the statistics
     pub fn initialize(&mut self) {
-        self.size = 0;
         self.content.clear();
+        self.positions.clear();
+        self.stats = QueueStats::default();
     }
 
-    pub const fn is_empty(&self) -> bool {
-        self.size == 0
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
     }
 
-    pub fn insert(&mut self, item: T) {
-        self.content.push(item);
-        let mut cur = self.size;
-        let mut par;
-        while cur != 0 {
-            par = (cur - 1) / 2;
-            if self.content[cur] < self.content[par] {
-                self.content.swap(cur, par);
-                cur = par;
-            } else {
-                break;
-            }
-        }
-        self.size += 1;
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    pub const fn stats(&self) -> QueueStats {
+        self.stats
+    }
+
+    pub fn insert(&mut self, item: T) -> Handle {
+        let handle = Handle(self.positions.len());
+        self.positions.push(self.content.len());
+        self.content.push((item, handle));
+        self.sift_up(self.content.len() - 1);
+        self.stats.inserted += 1;
+        self.stats.peak_len = self.stats.peak_len.max(self.content.len());
+        handle
     }
 
-    #[allow(dead_code)]
     pub fn peek(&self) -> Option<&T> {
-        if self.is_empty() {
-            return None;
-        }
-        Some(&self.content[0])
+        self.content.first().map(|(item, _)| item)
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        if self.is_empty() {
+        let item = self.take(0)?;
+        self.stats.popped += 1;
+        Some(item)
+    }
+
+    /// Removes the item of `handle` from the queue and returns it, or returns `None` if it has already left the queue.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let pos = *self.positions.get(handle.0)?;
+        let item = self.take(pos)?;
+        self.stats.removed += 1;
+        Some(item)
+    }
+
+    /// Takes the item at position `pos` of the heap out, and restores the heap property.
+    fn take(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.content.len() {
             return None;
         }
-        let ret = self.content.swap_remove(0);
-        let mut cur = 0;
-        let mut nc;
-        self.size -= 1;
-        while cur < self.size {
+        let (item, handle) = self.content.swap_remove(pos);
+        self.positions[handle.0] = usize::MAX;
+        if pos < self.content.len() {
+            self.positions[self.content[pos].1 .0] = pos;
+            let pos = self.sift_up(pos);
+            self.sift_down(pos);
+        }
+        Some(item)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.content.swap(a, b);
+        self.positions[self.content[a].1 .0] = a;
+        self.positions[self.content[b].1 .0] = b;
+    }
+
+    /// Moves the item at `cur` up until its parent is not greater, and returns its new position.
+    fn sift_up(&mut self, mut cur: usize) -> usize {
+        while cur != 0 {
+            let par = (cur - 1) / 2;
+            if self.content[cur].0 < self.content[par].0 {
+                self.swap(cur, par);
+                cur = par;
+            } else {
+                break;
+            }
+        }
+        cur
+    }
+
+    /// Moves the item at `cur` down until none of its children is smaller.
+    fn sift_down(&mut self, mut cur: usize) {
+        let size = self.content.len();
+        loop {
             let lc = cur * 2 + 1;
             let rc = cur * 2 + 2;
-            if lc >= self.size {
+            let nc = if lc >= size {
                 break;
-            } else if rc >= self.size {
-                nc = lc;
+            } else if rc >= size || self.content[lc].0 < self.content[rc].0 {
+                lc
             } else {
-                nc = if self.content[lc] < self.content[rc] {
-                    lc
-                } else {
-                    rc
-                };
-            }
-            if self.content[nc] < self.content[cur] {
-                self.content.swap(cur, nc);
+                rc
+            };
+            if self.content[nc].0 < self.content[cur].0 {
+                self.swap(cur, nc);
                 cur = nc;
             } else {
                 break;
             }
         }
-        Some(ret)
     }
 }
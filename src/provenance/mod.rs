@@ -0,0 +1,113 @@
+//! Tracing the vertices of a buffer back to the input, see [`buffer_polygon_with_provenance`](crate::buffer_polygon_with_provenance).
+
+use std::collections::HashMap;
+
+use geo::CoordsIter;
+use geo_types::{Coord, MultiPolygon, Polygon};
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+
+use crate::skeleton::{Corners, Skeleton};
+use crate::util::Ray;
+
+/// Where a vertex of a buffer comes from, see [`buffer_polygon_with_provenance`](crate::buffer_polygon_with_provenance).
+///
+/// The vertices of the input are numbered ring by ring, the exterior of each polygon before its interiors, without the closing
+/// vertex of each ring. The `i`-th edge of the input runs from the `i`-th vertex to the next vertex of its ring.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VertexOrigin {
+    /// The offset of an input vertex, where the offsets of its two edges meet (a miter, or the inner corner of a reflex vertex).
+    Miter { vertex: usize },
+    /// A point of the rounded, bevelled or cut off corner drawn around an input vertex.
+    Arc { vertex: usize },
+    /// Where the offsets of two input edges meet at a vertex created by the straight skeleton when the wavefront split or
+    /// shrank, or a point of the corner drawn around such a vertex. `left` and `right` are the edges on either side.
+    Split { left: usize, right: usize },
+    /// A vertex created by the postprocessing (e.g. validation, unification or clipping), or which cannot be traced back.
+    Unknown,
+}
+
+/// Returns the origin of each vertex of `res`, the buffer of `input` by `offset_distance` along `skel` with the corners drawn as
+/// `corners` says, whose construction moved the input by `-shift`. The origins are grouped by polygon and by ring as the coordinates of `res`.
+///
+/// The vertices of the wavefront are traced back to the nodes of the skeleton, and each vertex of `res` takes the origin of the
+/// closest of them within `tolerance`.
+pub(crate) fn trace(
+    skel: &Skeleton,
+    offset_distance: f64,
+    corners: Corners,
+    shift: Coord,
+    input: &[Polygon],
+    res: &MultiPolygon,
+    tolerance: f64,
+) -> Vec<Vec<Vec<VertexOrigin>>> {
+    // The vertices and edges of the input, moved as the skeleton sees them.
+    let key = |c: Coord| ((c.x + 0.).to_bits(), (c.y + 0.).to_bits());
+    let mut vertices = HashMap::new();
+    let mut edges = HashMap::new();
+    let rings = input
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()));
+    let mut first = 0;
+    for ring in rings {
+        let coords = &ring.0[..ring.0.len().saturating_sub(1)];
+        let n = coords.len();
+        for (i, &c) in coords.iter().enumerate() {
+            let (a, b) = (c - shift, coords[(i + 1) % n] - shift);
+            vertices.entry(key(a)).or_insert(first + i);
+            for (origin, direction) in [(a, b - a), (b, a - b)] {
+                for direction in [direction, -direction] {
+                    edges
+                        .entry((key(origin), key(direction)))
+                        .or_insert(first + i);
+                }
+            }
+        }
+        first += n;
+    }
+    let edge = |ray: Ray| edges.get(&(key(ray.origin), key(ray.angle))).copied();
+
+    let mut traced = Vec::new();
+    let vertex_queue = skel.get_vertex_queue(offset_distance);
+    skel.for_each_corner_vertex(
+        &vertex_queue,
+        offset_distance,
+        corners,
+        |_, crd, node, corner| {
+            let (location, time) = skel.node(node).unwrap_or_default();
+            let origin = match vertices.get(&key(location)) {
+                Some(&vertex) if time == 0. && corner => VertexOrigin::Arc { vertex },
+                Some(&vertex) if time == 0. => VertexOrigin::Miter { vertex },
+                _ => {
+                    let (left, right) = skel.base_rays(node);
+                    match (edge(left), edge(right)) {
+                        (Some(left), Some(right)) => VertexOrigin::Split { left, right },
+                        _ => VertexOrigin::Unknown,
+                    }
+                }
+            };
+            let crd = crd + shift;
+            traced.push(GeomWithData::new([crd.x, crd.y], origin));
+        },
+    );
+    let tree = RTree::bulk_load(traced);
+    res.iter()
+        .map(|p| {
+            std::iter::once(p.exterior())
+                .chain(p.interiors())
+                .map(|ring| {
+                    ring.coords_iter()
+                        .map(|c| {
+                            tree.nearest_neighbor(&[c.x, c.y])
+                                .filter(|t| {
+                                    let [x, y] = *t.geom();
+                                    (x - c.x).hypot(y - c.y) <= tolerance
+                                })
+                                .map_or(VertexOrigin::Unknown, |t| t.data)
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,97 @@
+//! Buffering of rectilinear polygons without a straight skeleton, see [`Algorithm::Rectilinear`](crate::Algorithm::Rectilinear).
+
+use geo::winding_order::WindingOrder;
+use geo::{MapCoordsInPlace, Winding};
+use geo_types::{Coord, MultiPolygon, Polygon, Rect};
+use i_overlay::core::overlay_rule::OverlayRule;
+
+use crate::{postprocess, repair};
+
+/// Returns the rings of `input` without their closing vertex and repeated consecutive vertices.
+fn rings(input: &[Polygon]) -> impl Iterator<Item = Vec<Coord>> + '_ {
+    input
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .map(|ring| {
+            let mut coords = ring.0.clone();
+            coords.dedup();
+            if coords.len() > 1 && coords.first() == coords.last() {
+                coords.pop();
+            }
+            coords
+        })
+}
+
+/// Checks whether every edge of `input` is parallel to an axis.
+pub(crate) fn is_rectilinear(input: &[Polygon]) -> bool {
+    rings(input).all(|coords| {
+        let n = coords.len();
+        (0..n).all(|i| {
+            let (a, b) = (coords[i], coords[(i + 1) % n]);
+            a.x == b.x || a.y == b.y
+        })
+    })
+}
+
+/// Returns the rectilinear polygons `input` buffered by `distance` in the L∞ norm, i.e. the union (or the difference when
+/// deflating) of the input with the square of side `2 * |distance|` swept along its boundary.
+///
+/// The square swept along an axis-parallel edge is a rectangle, so this is a single boolean operation on rectangles,
+/// which runs in O(n log n) for n edges.
+pub(crate) fn buffer(input: &[Polygon], distance: f64) -> MultiPolygon {
+    let mut subject = MultiPolygon::new(input.to_vec());
+    postprocess::orient(&mut subject, WindingOrder::CounterClockwise);
+    let offset = distance.abs();
+    let reach = Coord {
+        x: offset,
+        y: offset,
+    };
+    let mut band = MultiPolygon::new(Vec::new());
+    if offset > 0. {
+        for coords in rings(&subject.0) {
+            let n = coords.len();
+            for i in 0..n {
+                let edge = Rect::new(coords[i], coords[(i + 1) % n]);
+                let mut rect = Rect::new(edge.min() - reach, edge.max() + reach).to_polygon();
+                rect.exterior_mut(|ls| ls.make_ccw_winding());
+                band.0.push(rect);
+            }
+        }
+    }
+    repair::repair(&mut band);
+    let rule = if distance > 0. {
+        OverlayRule::Union
+    } else {
+        OverlayRule::Difference
+    };
+    let mut res = repair::overlay(&subject, &band, rule);
+    // The boolean operation rounds to its own grid, but every coordinate of the result is a coordinate of the input,
+    // possibly moved by `offset`.
+    let candidates = |axis: fn(Coord) -> f64| {
+        let mut values: Vec<f64> = rings(&subject.0)
+            .flatten()
+            .flat_map(|c| [axis(c) - offset, axis(c), axis(c) + offset])
+            .collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    };
+    let (xs, ys) = (candidates(|c| c.x), candidates(|c| c.y));
+    res.map_coords_in_place(|c| Coord {
+        x: nearest(&xs, c.x),
+        y: nearest(&ys, c.y),
+    });
+    res
+}
+
+/// Returns the value of the sorted `values` closest to `v`.
+fn nearest(values: &[f64], v: f64) -> f64 {
+    let i = values.partition_point(|&x| x < v);
+    [i.checked_sub(1), Some(i)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| values.get(i))
+        .copied()
+        .min_by(|a, b| (a - v).abs().total_cmp(&(b - v).abs()))
+        .unwrap_or(v)
+}
@@ -0,0 +1,506 @@
+use std::collections::{HashMap, HashSet};
+
+use geo::line_intersection::{line_intersection, LineIntersection};
+use geo::{Area, BoundingRect, Contains, InteriorPoint, Intersects, MapCoordsInPlace, Winding};
+use geo_types::{Coord, Line, LineString, MultiPolygon, Point, Polygon, Rect};
+use i_overlay::core::fill_rule::FillRule;
+use i_overlay::core::overlay_rule::OverlayRule;
+use i_overlay::float::single::SingleFloatOverlay;
+use rstar::{RTree, RTreeObject, AABB};
+
+use crate::postprocess::{self, coord_key};
+
+/// How many times its extent the input has to be away from the origin to be moved near it, see [`normalizing_shift`].
+///
+/// Closer inputs lose less than `2^-42` of their extent to the magnitude of their coordinates.
+const FAR_FROM_ORIGIN: f64 = 1024.;
+
+/// Diagnostics of the validation stage of the output, see [`BufferOptions::repair`](crate::BufferOptions::repair).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// The number of pairs of segments of the output which cross or overlap each other.
+    pub self_intersections: usize,
+    /// The number of interiors which are not inside their exterior, plus the number of polygons inside other polygons.
+    pub nesting_errors: usize,
+    /// Whether the output has been replaced by the union of its rings to fix the errors above.
+    pub repaired: bool,
+}
+
+impl RepairReport {
+    /// Returns `true` if no error has been found in the output.
+    ///
+    /// Note that the errors have been fixed if [`repaired`](RepairReport::repaired) is `true`.
+    pub fn is_valid(&self) -> bool {
+        self.self_intersections == 0 && self.nesting_errors == 0
+    }
+}
+
+/// A segment of the output, identified by its ring and its position in the ring.
+struct Segment {
+    line: Line,
+    ring: usize,
+    index: usize,
+    ring_len: usize,
+}
+
+impl RTreeObject for Segment {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_corners(self.line.start.into(), self.line.end.into())
+    }
+}
+
+impl Segment {
+    fn is_adjacent(&self, other: &Segment) -> bool {
+        self.ring == other.ring
+            && ((self.index + 1) % self.ring_len == other.index
+                || (other.index + 1) % other.ring_len == self.index)
+    }
+}
+
+/// Checks `mp` for crossing or overlapping segments and for misplaced rings.
+pub(crate) fn validate(mp: &MultiPolygon) -> RepairReport {
+    RepairReport {
+        self_intersections: count_self_intersections(mp),
+        nesting_errors: count_nesting_errors(mp),
+        repaired: false,
+    }
+}
+
+fn count_self_intersections(mp: &MultiPolygon) -> usize {
+    let rings =
+        mp.0.iter()
+            .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()));
+    let mut segments = Vec::new();
+    for (ring, ls) in rings.enumerate() {
+        let ring_len = ls.0.len().saturating_sub(1);
+        for (index, line) in ls.lines().enumerate() {
+            segments.push(Segment {
+                line,
+                ring,
+                index,
+                ring_len,
+            });
+        }
+    }
+    let tree = RTree::bulk_load(segments);
+    let mut count = 0;
+    for (a, b) in tree.intersection_candidates_with_other_tree(&tree) {
+        // Each pair is visited in both orders, so only one of them is counted.
+        if (a.ring, a.index) >= (b.ring, b.index) {
+            continue;
+        }
+        let crosses = match line_intersection(a.line, b.line) {
+            Some(LineIntersection::SinglePoint { is_proper, .. }) => is_proper && !a.is_adjacent(b),
+            Some(LineIntersection::Collinear { intersection }) => {
+                intersection.start != intersection.end
+            }
+            None => false,
+        };
+        if crosses {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn count_nesting_errors(mp: &MultiPolygon) -> usize {
+    let mut count = 0;
+    for p in &mp.0 {
+        let exterior = Polygon::new(p.exterior().clone(), vec![]);
+        count += p
+            .interiors()
+            .iter()
+            .filter(|ls| !first_point(ls).is_some_and(|pt| exterior.contains(&pt)))
+            .count();
+    }
+    for (i, p) in mp.0.iter().enumerate() {
+        let Some(pt) = first_point(p.exterior()) else {
+            continue;
+        };
+        let inside_other = mp.0.iter().enumerate().any(|(j, q)| {
+            i != j && q.bounding_rect().is_some_and(|r| r.intersects(&pt)) && q.contains(&pt)
+        });
+        if inside_other {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn first_point(ls: &LineString) -> Option<Point> {
+    ls.0.first().map(|&c| c.into())
+}
+
+/// Replaces `mp` by the union of the regions enclosed by its rings, counted by winding number.
+///
+/// Regions wound positively (counter-clockwise) more often than negatively are kept,
+/// which resolves crossings and overlaps the way the offset curve of the input intends.
+pub(crate) fn repair(mp: &mut MultiPolygon) {
+    let empty: Vec<Vec<[f64; 2]>> = Vec::new();
+    let shapes = to_paths(mp).overlay(&empty, OverlayRule::Subject, FillRule::Negative);
+    *mp = from_shapes(shapes);
+    postprocess::canonicalize(mp);
+}
+
+/// Returns the result of the boolean operation `rule` on `subject` and `clip`, whose exteriors must be counter-clockwise.
+pub(crate) fn overlay(
+    subject: &MultiPolygon,
+    clip: &MultiPolygon,
+    rule: OverlayRule,
+) -> MultiPolygon {
+    let shapes = to_paths(subject).overlay(&to_paths(clip), rule, FillRule::Negative);
+    let mut res = from_shapes(shapes);
+    postprocess::canonicalize(&mut res);
+    res
+}
+
+/// Returns `input` without repeated consecutive vertices and without vertices on the line through their neighbours,
+/// including the tips of zero-width spikes, see [`BufferOptions::merge_degenerate_vertices`](crate::BufferOptions::merge_degenerate_vertices).
+///
+/// Interiors left with fewer than three vertices are removed, and so are polygons whose exterior is.
+pub(crate) fn merge_degenerate_vertices(input: &[Polygon]) -> Vec<Polygon> {
+    input
+        .iter()
+        .filter_map(|p| {
+            let exterior = merge_ring(p.exterior())?;
+            let interiors = p.interiors().iter().filter_map(merge_ring).collect();
+            Some(Polygon::new(exterior, interiors))
+        })
+        .collect()
+}
+
+/// Returns the translation moving `input` near the origin, where the straight skeleton does not lose the precision that the magnitude
+/// of coordinates far from the origin (e.g. in Web-Mercator meters) would take, or zero if `input` is within [`FAR_FROM_ORIGIN`]
+/// times its extent from the origin already, since translating the result back rounds it.
+///
+/// The translation is a multiple of a power of two at least as large as the extent of `input`, so that translating `input`
+/// by its opposite is exact: each difference is either a multiple of the unit in the last place of the coordinate, or it is
+/// exact by Sterbenz's lemma.
+pub(crate) fn normalizing_shift(input: &[Polygon]) -> Coord {
+    let Some(rect) = input
+        .iter()
+        .filter_map(|p| p.exterior().bounding_rect())
+        .reduce(|a, b| {
+            Rect::new(
+                Coord {
+                    x: a.min().x.min(b.min().x),
+                    y: a.min().y.min(b.min().y),
+                },
+                Coord {
+                    x: a.max().x.max(b.max().x),
+                    y: a.max().y.max(b.max().y),
+                },
+            )
+        })
+    else {
+        return Coord::zero();
+    };
+    let extent = rect.width().max(rect.height());
+    if !(extent > 0. && extent.is_finite()) {
+        return Coord::zero();
+    }
+    let center = rect.center();
+    if center.x.abs().max(center.y.abs()) < FAR_FROM_ORIGIN * extent {
+        return Coord::zero();
+    }
+    let grid = 2f64.powi(extent.log2().ceil() as i32);
+    Coord {
+        x: (center.x / grid).round() * grid,
+        y: (center.y / grid).round() * grid,
+    }
+}
+
+/// Returns `input` with its coordinates rounded to multiples of `1 / scale`, without the degenerate vertices this creates,
+/// see [`BufferOptions::snap_input`](crate::BufferOptions::snap_input).
+pub(crate) fn snap_input(input: &[Polygon], scale: f64) -> Vec<Polygon> {
+    let mut snapped = MultiPolygon::new(input.to_vec());
+    snapped.map_coords_in_place(|c| postprocess::snap(c, scale));
+    merge_degenerate_vertices(&snapped.0)
+}
+
+fn merge_ring(ls: &LineString) -> Option<LineString> {
+    // The sine of the angle below which a vertex is considered to lie on the line through its neighbours.
+    const MAX_SINE: f64 = 1e-9;
+    let degenerate = |a: Coord, b: Coord, c: Coord| {
+        let (u, v) = (b - a, c - b);
+        a == b
+            || b == c
+            || (u.x * v.y - u.y * v.x).abs() <= MAX_SINE * u.x.hypot(u.y) * v.x.hypot(v.y)
+    };
+    let len = if ls.is_closed() {
+        ls.0.len().saturating_sub(1)
+    } else {
+        ls.0.len()
+    };
+    let mut kept: Vec<Coord> = Vec::with_capacity(len + 1);
+    for &c in &ls.0[..len] {
+        while let [.., a, b] = kept[..] {
+            if !degenerate(a, b, c) {
+                break;
+            }
+            kept.pop();
+        }
+        if kept.last() != Some(&c) {
+            kept.push(c);
+        }
+    }
+    // Removing the vertices around the first vertex can make the vertices around it degenerate in turn.
+    loop {
+        let n = kept.len();
+        if n < 3 {
+            return None;
+        }
+        if degenerate(kept[n - 2], kept[n - 1], kept[0]) {
+            kept.pop();
+        } else if degenerate(kept[n - 1], kept[0], kept[1]) {
+            kept.remove(0);
+        } else {
+            break;
+        }
+    }
+    kept.push(kept[0]);
+    Some(LineString::new(kept))
+}
+
+/// Removes the rings of `input` with fewer than 4 coordinates, i.e. fewer than 3 vertices, which enclose nothing, together with
+/// the polygons whose exterior is such a ring, or returns `None` if no ring has fewer than 4 coordinates.
+pub(crate) fn drop_degenerate_rings(input: &[Polygon]) -> Option<Vec<Polygon>> {
+    let is_degenerate = |ls: &LineString| ls.0.len() < 4;
+    if !input
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .any(is_degenerate)
+    {
+        return None;
+    }
+    Some(
+        input
+            .iter()
+            .filter(|p| !is_degenerate(p.exterior()))
+            .map(|p| {
+                let interiors = p.interiors().iter().filter(|ls| !is_degenerate(ls));
+                Polygon::new(p.exterior().clone(), interiors.cloned().collect())
+            })
+            .collect(),
+    )
+}
+
+/// Splits the polygons of `input` whose rings touch themselves (e.g. figure-eights) into valid polygons,
+/// or returns `None` if no ring does.
+///
+/// Each ring is cut into simple loops at its repeated vertices. The loops of an exterior winding as its largest loop
+/// are exteriors and the others (loops turned inside out) are holes, while the loops of an interior are holes.
+/// The resulting polygons have counter-clockwise exteriors and clockwise interiors.
+pub(crate) fn split_pinches(input: &[Polygon]) -> Option<Vec<Polygon>> {
+    if !input
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .any(is_pinched)
+    {
+        return None;
+    }
+    let mut res = Vec::with_capacity(input.len());
+    for p in input {
+        let mut loops = split_ring(p.exterior());
+        let Some(largest) = loops
+            .iter()
+            .max_by(|a, b| signed_area(a).abs().total_cmp(&signed_area(b).abs()))
+        else {
+            continue;
+        };
+        let ccw = signed_area(largest) > 0.;
+        for ls in &mut loops {
+            if (signed_area(ls) > 0.) == ccw {
+                ls.make_ccw_winding();
+            } else {
+                ls.make_cw_winding();
+            }
+        }
+        for ls in p.interiors() {
+            loops.extend(split_ring(ls).into_iter().map(|mut ls| {
+                ls.make_cw_winding();
+                ls
+            }));
+        }
+        res.extend(polygons_of_rings(loops));
+    }
+    Some(res)
+}
+
+/// Moves the vertices of the interiors of `input` which touch the exterior slightly into their interiors,
+/// or returns `None` if no interior touches the exterior of its polygon at a vertex.
+///
+/// When inflating, the exterior and the interior move apart at such a vertex, but the straight skeleton would join them there.
+pub(crate) fn separate_touching_holes(input: &[Polygon]) -> Option<Vec<Polygon>> {
+    let touches = |p: &Polygon| {
+        let exterior: HashSet<_> = p.exterior().coords().map(|&c| coord_key(c)).collect();
+        p.interiors()
+            .iter()
+            .any(|ls| ls.coords().any(|&c| exterior.contains(&coord_key(c))))
+    };
+    if !input.iter().any(touches) {
+        return None;
+    }
+    let res = input
+        .iter()
+        .map(|p| {
+            let exterior: HashSet<_> = p.exterior().coords().map(|&c| coord_key(c)).collect();
+            let mut p = p.clone();
+            p.interiors_mut(|interiors| {
+                for ls in interiors {
+                    let len = ls.0.len().saturating_sub(1);
+                    let area = signed_area(ls);
+                    let moved: Vec<Coord> = (0..len)
+                        .map(|i| {
+                            let (prv, cur, nxt) = (ls.0[(i + len - 1) % len], ls.0[i], ls.0[i + 1]);
+                            if !exterior.contains(&coord_key(cur)) {
+                                return cur;
+                            }
+                            cur + inward_nudge(prv, cur, nxt, area)
+                        })
+                        .collect();
+                    *ls = LineString::new(moved);
+                    ls.close();
+                }
+            });
+            p
+        })
+        .collect();
+    Some(res)
+}
+
+/// Returns a short vector from the vertex `cur` of a ring with signed area `area` into the region enclosed by the ring,
+/// where `prv` and `nxt` are the neighbours of `cur`.
+///
+/// Its length is a hundred-millionth of the shorter edge at `cur`, but no less than `1e-8` to exceed the tolerance of the skeleton.
+fn inward_nudge(prv: Coord, cur: Coord, nxt: Coord, area: f64) -> Coord {
+    let (a, b) = (prv - cur, nxt - cur);
+    let (la, lb) = (a.x.hypot(a.y), b.x.hypot(b.y));
+    if la == 0. || lb == 0. {
+        return Coord::zero();
+    }
+    let length = f64::max(1e-8 * la.min(lb), 1e-8);
+    // The region is on the left of a counter-clockwise ring, and a vertex is convex if the ring turns towards it.
+    let sign = area.signum();
+    let turn = (cur.x - prv.x) * (nxt.y - cur.y) - (cur.y - prv.y) * (nxt.x - cur.x);
+    let bisector = a / la + b / lb;
+    let norm = bisector.x.hypot(bisector.y);
+    let direction = if norm < 1e-12 {
+        Coord { x: -b.y, y: b.x } / lb * sign
+    } else if turn * sign > 0. {
+        bisector / norm
+    } else {
+        bisector / -norm
+    };
+    direction * length
+}
+
+fn signed_area(ls: &LineString) -> f64 {
+    ls.lines().map(|l| l.determinant()).sum::<f64>() / 2.
+}
+
+fn is_pinched(ls: &LineString) -> bool {
+    let len = ls.0.len().saturating_sub(1);
+    let mut seen = HashSet::with_capacity(len);
+    !ls.0[..len].iter().all(|&c| seen.insert(coord_key(c)))
+}
+
+/// Cuts the closed ring `ls` into closed loops without repeated vertices, dropping the loops of fewer than three vertices.
+fn split_ring(ls: &LineString) -> Vec<LineString> {
+    let len = ls.0.len().saturating_sub(1);
+    let mut loops = Vec::new();
+    let mut stack: Vec<Coord> = Vec::with_capacity(len);
+    let mut position = HashMap::with_capacity(len);
+    for &c in &ls.0[..len] {
+        match position.get(&coord_key(c)) {
+            Some(&i) => {
+                let mut ring: Vec<Coord> = stack.drain(i..).collect();
+                for v in &ring[1..] {
+                    position.remove(&coord_key(*v));
+                }
+                stack.push(ring[0]);
+                if ring.len() >= 3 {
+                    ring.push(ring[0]);
+                    loops.push(LineString::new(ring));
+                }
+            }
+            None => {
+                position.insert(coord_key(c), stack.len());
+                stack.push(c);
+            }
+        }
+    }
+    if stack.len() >= 3 {
+        stack.push(stack[0]);
+        loops.push(LineString::new(stack));
+    }
+    loops
+}
+
+/// Assembles counter-clockwise rings and clockwise rings into polygons, putting each clockwise ring into the smallest
+/// counter-clockwise ring around it.
+pub(crate) fn polygons_of_rings(rings: Vec<LineString>) -> Vec<Polygon> {
+    let (exteriors, interiors): (Vec<_>, Vec<_>) = rings.into_iter().partition(|ls| ls.is_ccw());
+    let mut polygons: Vec<Polygon> = exteriors
+        .into_iter()
+        .map(|ls| Polygon::new(ls, vec![]))
+        .collect();
+    let areas: Vec<f64> = polygons.iter().map(|p| p.unsigned_area()).collect();
+    for ls in interiors {
+        let Some(point) = ls.interior_point() else {
+            continue;
+        };
+        let around = (0..polygons.len())
+            .filter(|&i| contains(&polygons[i], point))
+            .min_by(|&i, &j| areas[i].total_cmp(&areas[j]));
+        if let Some(i) = around {
+            polygons[i].interiors_push(ls);
+        }
+    }
+    polygons
+}
+
+/// Returns whether `p` contains `point`, testing the bounding rectangle of `p` first.
+pub(crate) fn contains(p: &Polygon, point: Point) -> bool {
+    p.bounding_rect().is_some_and(|r| r.intersects(&point)) && p.contains(&point)
+}
+
+/// Returns the rings of `mp` as paths of `i_overlay`, without their closing coordinates.
+///
+/// `i_overlay` counts clockwise windings as positive, so the (counter-clockwise) exteriors of the output count as negative.
+pub(crate) fn to_paths(mp: &MultiPolygon) -> Vec<Vec<[f64; 2]>> {
+    mp.0.iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .map(|ls| {
+            let len = ls.0.len().saturating_sub(1);
+            ls.0[..len].iter().map(|c| [c.x, c.y]).collect()
+        })
+        .collect()
+}
+
+/// Converts the shapes emitted by `i_overlay`, which have clockwise exteriors and counter-clockwise holes, into polygons.
+pub(crate) fn from_shapes(shapes: Vec<Vec<Vec<[f64; 2]>>>) -> MultiPolygon {
+    let to_ring = |path: Vec<[f64; 2]>| {
+        let mut ls = LineString::from(
+            path.into_iter()
+                .rev()
+                .map(|[x, y]| (x, y))
+                .collect::<Vec<_>>(),
+        );
+        ls.close();
+        ls
+    };
+    MultiPolygon::new(
+        shapes
+            .into_iter()
+            .filter(|shape| !shape.is_empty())
+            .map(|shape| {
+                let mut rings = shape.into_iter().map(to_ring);
+                let exterior = rings.next().unwrap();
+                Polygon::new(exterior, rings.collect())
+            })
+            .collect(),
+    )
+}
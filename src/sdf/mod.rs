@@ -0,0 +1,155 @@
+//! Rasterized signed distance fields of polygons, sampled from their straight skeletons.
+//!
+//! A signed distance field stores, for each cell of a grid, the distance from the center of the cell to the boundary of a shape,
+//! positive inside and negative outside. The fields here measure distances by the wavefront of the straight skeleton, as
+//! [`Skeleton::signed_distance`] does, so the level set of each distance `d` is the boundary of the shape buffered by `d`
+//! (with the miter-joint-like corners of [`buffer_polygon`](crate::buffer_polygon)).
+
+use geo_types::{Coord, MultiPolygon, Polygon, Rect};
+
+use crate::skeleton::{Side, Skeleton};
+
+/// A regular grid of `width` by `height` cells covering `bounds`, to sample a signed distance field on.
+///
+/// The cells are stored row by row, starting from the row at the top (largest y) and from the left (smallest x) within each row,
+/// as the pixels of an image are. The cell in column `col` and row `row` is at index `row * width + col`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid {
+    /// The rectangle covered by the grid.
+    pub bounds: Rect,
+    /// The number of columns.
+    pub width: usize,
+    /// The number of rows.
+    pub height: usize,
+}
+
+impl Grid {
+    /// Creates a grid of `width` by `height` cells covering `bounds`.
+    #[must_use]
+    pub const fn new(bounds: Rect, width: usize, height: usize) -> Self {
+        Self {
+            bounds,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a grid of square cells of side `cell_size` whose bottom left corner is the bottom left corner of `bounds`.
+    ///
+    /// The grid has enough cells to cover `bounds`, so it may reach beyond the top and the right of `bounds`.
+    /// The grid has no cells if `cell_size` is not positive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::sdf::Grid;
+    /// use geo::Rect;
+    ///
+    /// let grid = Grid::with_cell_size(Rect::new((0., 0.), (10., 5.)), 2.);
+    /// assert_eq!((grid.width, grid.height), (5, 3));
+    /// assert_eq!(grid.bounds, Rect::new((0., 0.), (10., 6.)));
+    /// ```
+    #[must_use]
+    pub fn with_cell_size(bounds: Rect, cell_size: f64) -> Self {
+        if cell_size <= 0. || !cell_size.is_finite() {
+            return Self::new(bounds, 0, 0);
+        }
+        let cells = |len: f64| (len / cell_size).ceil().max(1.) as usize;
+        let (width, height) = (cells(bounds.width()), cells(bounds.height()));
+        let max = bounds.min()
+            + Coord {
+                x: width as f64 * cell_size,
+                y: height as f64 * cell_size,
+            };
+        Self::new(Rect::new(bounds.min(), max), width, height)
+    }
+
+    /// Returns the center of the cell in column `col` and row `row`.
+    #[must_use]
+    pub fn cell_center(&self, col: usize, row: usize) -> Coord {
+        let (min, max) = (self.bounds.min(), self.bounds.max());
+        Coord {
+            x: min.x + (col as f64 + 0.5) * self.bounds.width() / self.width as f64,
+            y: max.y - (row as f64 + 0.5) * self.bounds.height() / self.height as f64,
+        }
+    }
+}
+
+/// This function returns the signed distance field of the given polygon, sampled at the center of each cell of `grid`.
+///
+/// The value of each cell is the distance from its center to the boundary of the polygon, positive inside and negative outside,
+/// measured by the straight skeletons of both sides of the boundary. The cells are in the order described at [`Grid`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::sdf::{signed_distance_field, Grid};
+/// use geo::{Polygon, LineString, Rect};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]), vec![],
+/// );
+/// let grid = Grid::new(Rect::new((-2., -2.), (6., 6.)), 4, 4);
+/// let field = signed_distance_field(&p1, &grid);
+///
+/// // The cells are centered at -1, 1, 3 and 5 on both axes.
+/// assert_eq!(field.len(), 16);
+/// assert_eq!(field[0], -1.);
+/// assert_eq!(field[5], 1.);
+/// assert_eq!(field[6], 1.);
+/// assert_eq!(field[1 * 4 + 3], -1.);
+/// ```
+#[must_use]
+pub fn signed_distance_field(input_polygon: &Polygon, grid: &Grid) -> Vec<f32> {
+    sample(std::slice::from_ref(input_polygon), grid)
+}
+
+/// This function returns the signed distance field of the given multi-polygon, sampled at the center of each cell of `grid`.
+///
+/// See [`signed_distance_field`] for the details.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::sdf::{signed_distance_field_of_multi_polygon, Grid};
+/// use geo::{MultiPolygon, Polygon, LineString, Rect};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let p2 = Polygon::new(
+///     LineString::from(vec![(4., 0.), (6., 0.), (6., 2.), (4., 2.)]), vec![],
+/// );
+/// let grid = Grid::new(Rect::new((0., 0.), (6., 2.)), 3, 1);
+/// let field = signed_distance_field_of_multi_polygon(&MultiPolygon::new(vec![p1, p2]), &grid);
+///
+/// assert_eq!(field, vec![1., -1., 1.]);
+/// ```
+#[must_use]
+pub fn signed_distance_field_of_multi_polygon(
+    input_multi_polygon: &MultiPolygon,
+    grid: &Grid,
+) -> Vec<f32> {
+    sample(&input_multi_polygon.0, grid)
+}
+
+fn sample(input_polygon_vector: &[Polygon], grid: &Grid) -> Vec<f32> {
+    let cells = grid.width * grid.height;
+    if input_polygon_vector.is_empty() {
+        return vec![f32::NEG_INFINITY; cells];
+    }
+    // Each skeleton measures its own side of the boundary by the wavefront, the other side only by the Euclidean distance.
+    let inward = Skeleton::skeleton_of_polygon_vector(input_polygon_vector, Side::Interior);
+    let outward = Skeleton::skeleton_of_polygon_vector(input_polygon_vector, Side::Exterior);
+    (0..grid.height)
+        .flat_map(|row| (0..grid.width).map(move |col| grid.cell_center(col, row)))
+        .map(|c| {
+            let d = inward.signed_distance(c);
+            if d < 0. {
+                outward.signed_distance(c) as f32
+            } else {
+                d as f32
+            }
+        })
+        .collect()
+}
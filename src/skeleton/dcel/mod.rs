@@ -0,0 +1,272 @@
+//! Export of a [`Skeleton`] as a doubly connected edge list (DCEL), i.e. a half-edge structure of the planar subdivision
+//! it induces.
+
+use std::collections::HashSet;
+use std::f64::consts::TAU;
+
+use super::*;
+
+/// The type of an edge of a [`Dcel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DcelEdgeKind {
+    /// An edge of the input.
+    Boundary,
+    /// An arc of the skeleton, traced by a vertex of the wavefront.
+    Arc,
+    /// An edge of the wavefront where the arcs to infinity of an outward skeleton are cut, see [`Skeleton::to_dcel`].
+    Wavefront,
+}
+
+/// A vertex of a [`Dcel`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DcelVertex {
+    /// The location of the vertex.
+    pub location: Coord,
+    /// The time at which the wavefront passes the vertex.
+    pub time: f64,
+    /// A half-edge starting at the vertex.
+    pub half_edge: usize,
+}
+
+/// A half-edge of a [`Dcel`], i.e. an edge directed so that its face lies to its left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    /// The vertex the half-edge starts at.
+    pub origin: usize,
+    /// The half-edge of the same edge in the opposite direction.
+    pub twin: usize,
+    /// The half-edge following this one around its face, counter-clockwise.
+    pub next: usize,
+    /// The half-edge preceding this one around its face.
+    pub prev: usize,
+    /// The face to the left of the half-edge.
+    pub face: usize,
+    /// The type of the edge.
+    pub kind: DcelEdgeKind,
+}
+
+/// A face of a [`Dcel`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Face {
+    /// A half-edge on the boundary of the face.
+    pub half_edge: usize,
+    /// The half-edge of the edge of the input whose sweep by the wavefront makes up this face,
+    /// or `None` for the faces outside of the swept region.
+    pub input_edge: Option<usize>,
+}
+
+/// A doubly connected edge list (DCEL) of a [`Skeleton`], see [`Skeleton::to_dcel`].
+///
+/// The half-edges `2 * i` and `2 * i + 1` are twins. Each cycle of `next` pointers bounds a face of its own, so a region
+/// enclosing a separate component of the subdivision (e.g. the skeleton of a hole inside an outward skeleton) is split into
+/// a face for its outer boundary and a face for the boundary of each component inside.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dcel {
+    /// The vertices, indexed by [`HalfEdge::origin`].
+    pub vertices: Vec<DcelVertex>,
+    /// The half-edges, indexed by [`HalfEdge::twin`], [`HalfEdge::next`] and [`HalfEdge::prev`].
+    pub half_edges: Vec<HalfEdge>,
+    /// The faces, indexed by [`HalfEdge::face`].
+    pub faces: Vec<Face>,
+}
+
+impl Skeleton {
+    /// Returns the subdivision of the plane by the input and the arcs of this skeleton as a doubly connected edge list.
+    ///
+    /// Each face swept by an edge of the input is a face of the result, bounded by the edge and by the arcs traced by the
+    /// vertices of the wavefront at its ends. The arcs to infinity of an outward skeleton are cut where the wavefront is at
+    /// time `1` past its latest node, and the cut ends are joined by the edges of the wavefront at that time,
+    /// so that every face is bounded. The other faces are those outside of the swept region.
+    ///
+    /// Nodes at the same location (up to `1e-9` in each coordinate) are merged into one vertex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::skeleton::DcelEdgeKind;
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let dcel = skeleton_of_polygon(&p1, Side::Interior).to_dcel();
+    ///
+    /// // The four corners and both ends of the ridge; four edges of the input and five arcs.
+    /// assert_eq!(dcel.vertices.len(), 6);
+    /// assert_eq!(dcel.half_edges.len(), 2 * 9);
+    /// // A face for each edge of the input, and the outside.
+    /// assert_eq!(dcel.faces.len(), 5);
+    /// assert_eq!(dcel.faces.iter().filter(|f| f.input_edge.is_some()).count(), 4);
+    /// for (i, h) in dcel.half_edges.iter().enumerate() {
+    ///     assert_eq!(dcel.half_edges[h.twin].twin, i);
+    ///     assert_eq!(dcel.half_edges[h.next].prev, i);
+    ///     assert_eq!(dcel.half_edges[h.next].face, h.face);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn to_dcel(&self) -> Dcel {
+        let mut dcel = Dcel::default();
+        let node_vertex = self.merge_nodes(&mut dcel);
+        let cut_time = self
+            .ray_vector
+            .iter()
+            .map(VertexType::time_elapsed)
+            .fold(0., f64::max)
+            + 1.;
+        let mut cut_vertex = vec![usize::MAX; self.ray_vector.len()];
+        let mut cut = |dcel: &mut Dcel, v: usize| {
+            if cut_vertex[v] == usize::MAX {
+                cut_vertex[v] = dcel.vertices.len();
+                dcel.vertices.push(DcelVertex {
+                    location: self.ray_vector[v].location_at(cut_time),
+                    time: cut_time,
+                    half_edge: usize::MAX,
+                });
+            }
+            cut_vertex[v]
+        };
+
+        // The edges, each with the flag whether the swept side is to the left of its first half-edge.
+        let mut edges: Vec<(usize, usize, DcelEdgeKind, bool)> = Vec::new();
+        let inward = self.side.is_interior();
+        let vertex_queue = &self.initial_vertex_queue;
+        for n in vertex_queue.live_nodes() {
+            let (u, w) = (
+                vertex_queue.get_real_index(n),
+                vertex_queue.get_real_index(vertex_queue.rv(n)),
+            );
+            edges.push((
+                node_vertex[u],
+                node_vertex[w],
+                DcelEdgeKind::Boundary,
+                inward,
+            ));
+        }
+        for (v, node) in self.ray_vector.iter().enumerate() {
+            let VertexType::Tree { parent, .. } = *node else {
+                continue;
+            };
+            let end = match self.ray_vector.get(parent) {
+                Some(_) => node_vertex[parent],
+                None => cut(&mut dcel, v),
+            };
+            edges.push((node_vertex[v], end, DcelEdgeKind::Arc, false));
+        }
+        let wavefront = self.get_vertex_queue(cut_time);
+        for n in wavefront.live_nodes() {
+            let (u, w) = (
+                wavefront.get_real_index(n),
+                wavefront.get_real_index(wavefront.rv(n)),
+            );
+            edges.push((
+                cut(&mut dcel, u),
+                cut(&mut dcel, w),
+                DcelEdgeKind::Wavefront,
+                false,
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        let mut swept = Vec::new();
+        for (a, b, kind, left_swept) in edges {
+            if a == b || !seen.insert((a.min(b), a.max(b))) {
+                continue;
+            }
+            let h = dcel.half_edges.len();
+            for (origin, twin) in [(a, h + 1), (b, h)] {
+                dcel.half_edges.push(HalfEdge {
+                    origin,
+                    twin,
+                    next: usize::MAX,
+                    prev: usize::MAX,
+                    face: usize::MAX,
+                    kind,
+                });
+            }
+            if kind == DcelEdgeKind::Boundary {
+                swept.push(if left_swept { h } else { h + 1 });
+            }
+        }
+        dcel.link(&swept);
+        dcel
+    }
+
+    /// Adds a vertex to `dcel` for each group of nodes at the same location (up to `1e-9` in each coordinate), and returns the vertex of each node.
+    fn merge_nodes(&self, dcel: &mut Dcel) -> Vec<usize> {
+        let nodes: Vec<(Coord, f64)> = self
+            .ray_vector
+            .iter()
+            .map(|v| (v.inner_location(), v.time_elapsed()))
+            .collect();
+        let mut order: Vec<usize> = (0..nodes.len()).collect();
+        order.sort_by(|&i, &j| nodes[i].0.x.total_cmp(&nodes[j].0.x));
+        let mut node_vertex = vec![usize::MAX; nodes.len()];
+        for (k, &i) in order.iter().enumerate() {
+            let location = nodes[i].0;
+            let earlier = order[..k]
+                .iter()
+                .rev()
+                .take_while(|&&j| feq(location.x, nodes[j].0.x))
+                .find(|&&j| nodes[j].0.approx_eq(&location));
+            node_vertex[i] = match earlier {
+                Some(&j) => node_vertex[j],
+                None => {
+                    dcel.vertices.push(DcelVertex {
+                        location,
+                        time: nodes[i].1,
+                        half_edge: usize::MAX,
+                    });
+                    dcel.vertices.len() - 1
+                }
+            };
+        }
+        node_vertex
+    }
+}
+
+impl Dcel {
+    /// Sets the `next` and `prev` pointers of the half-edges by their order around each vertex, then finds the faces.
+    ///
+    /// `swept` holds the half-edges of the input which have the swept side to their left.
+    fn link(&mut self, swept: &[usize]) {
+        let mut outgoing: Vec<Vec<(f64, usize)>> = vec![Vec::new(); self.vertices.len()];
+        for (h, e) in self.half_edges.iter().enumerate() {
+            let d = self.vertices[self.half_edges[e.twin].origin].location
+                - self.vertices[e.origin].location;
+            outgoing[e.origin].push((d.angle().rem_euclid(TAU), h));
+        }
+        for (v, out) in outgoing.iter_mut().enumerate() {
+            out.sort_by(|a, b| a.0.total_cmp(&b.0));
+            if let Some(&(_, h)) = out.first() {
+                self.vertices[v].half_edge = h;
+            }
+            // The half-edge arriving along the twin of `out[i]` continues along the next outgoing one clockwise.
+            for i in 0..out.len() {
+                let incoming = self.half_edges[out[i].1].twin;
+                let next = out[(i + out.len() - 1) % out.len()].1;
+                self.half_edges[incoming].next = next;
+                self.half_edges[next].prev = incoming;
+            }
+        }
+        for start in 0..self.half_edges.len() {
+            if self.half_edges[start].face != usize::MAX {
+                continue;
+            }
+            let face = self.faces.len();
+            self.faces.push(Face {
+                half_edge: start,
+                input_edge: None,
+            });
+            let mut h = start;
+            while self.half_edges[h].face == usize::MAX {
+                self.half_edges[h].face = face;
+                h = self.half_edges[h].next;
+            }
+        }
+        for &h in swept {
+            let face = self.half_edges[h].face;
+            self.faces[face].input_edge = Some(h);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+//! Distances measured by the wavefront of a [`Skeleton`], i.e. by the time the wavefront takes to reach a point.
+
+use geo::{Contains, Distance, Euclidean};
+use geo_types::{Line, Point};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+
+use super::*;
+
+/// The region swept by the edge of the wavefront between two vertices, while both of them exist.
+#[derive(Clone, Copy, Debug)]
+struct Piece {
+    /// The vertex at the left end of the edge.
+    left: usize,
+    /// The vertex at the right end of the edge.
+    right: usize,
+    start: f64,
+    /// The time at which one of the vertices vanishes, or infinity if none does.
+    end: f64,
+}
+
+/// The projection of a point onto the boundary of the input of a [`Skeleton`], see [`Skeleton::project_onto_boundary`].
+///
+/// The face of the skeleton the point projects onto is the region swept by `edge` as the wavefront moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundaryProjection {
+    /// The edge of the boundary the point projects onto, directed as the wavefront ring it starts: the wavefront moves to the
+    /// left of the edge for an inward skeleton, and to the right for an outward one.
+    pub edge: Line,
+    /// The position of the foot of the perpendicular from the point along `edge`, from 0 at its start to 1 at its end.
+    pub parameter: f64,
+    /// The distance from the point to `edge`.
+    pub distance: f64,
+    /// The vertex of the skeleton at the start of `edge`.
+    vertex: usize,
+}
+
+/// The regions swept by the edges of the wavefront of a skeleton, indexed for point queries.
+pub(crate) struct DistanceField {
+    pieces: Vec<Piece>,
+    /// The bounding rectangles of the pieces which end at a finite time.
+    bounded: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+    /// The pieces which sweep to infinity, which remain after the last event of an outward skeleton.
+    unbounded: Vec<usize>,
+    /// The input of the skeleton, to measure points on the side the wavefront does not reach.
+    input: MultiPolygon,
+    inward: bool,
+}
+
+/// The tolerance of the tests whether a point lies in a piece, growing with the time to allow for rounding errors.
+fn tolerance(time: f64) -> f64 {
+    1e-9 * (1. + time.abs())
+}
+
+impl DistanceField {
+    pub(crate) fn new(skel: &Skeleton) -> Self {
+        let pieces = sweep_pieces(skel);
+        let mut bounded = Vec::new();
+        let mut unbounded = Vec::new();
+        for (i, piece) in pieces.iter().enumerate() {
+            if !piece.end.is_finite() {
+                unbounded.push(i);
+                continue;
+            }
+            let corners = [
+                skel.ray_vector[piece.left].location_at(piece.start),
+                skel.ray_vector[piece.right].location_at(piece.start),
+                skel.ray_vector[piece.left].location_at(piece.end),
+                skel.ray_vector[piece.right].location_at(piece.end),
+            ];
+            let tol = tolerance(piece.end);
+            let lo = corners.iter().fold([f64::INFINITY; 2], |lo, c| {
+                [lo[0].min(c.x - tol), lo[1].min(c.y - tol)]
+            });
+            let hi = corners.iter().fold([f64::NEG_INFINITY; 2], |hi, c| {
+                [hi[0].max(c.x + tol), hi[1].max(c.y + tol)]
+            });
+            bounded.push(GeomWithData::new(Rectangle::from_corners(lo, hi), i));
+        }
+        Self {
+            pieces,
+            bounded: RTree::bulk_load(bounded),
+            unbounded,
+            input: skel.input_polygons(),
+            inward: skel.side.is_interior(),
+        }
+    }
+
+    /// Returns the pieces whose bounding rectangles contain `point`, and those without one.
+    fn candidates(&self, point: Coord) -> impl Iterator<Item = &Piece> + '_ {
+        self.bounded
+            .locate_all_at_point(&[point.x, point.y])
+            .map(|r| r.data)
+            .chain(self.unbounded.iter().copied())
+            .map(|i| &self.pieces[i])
+    }
+
+    pub(crate) fn signed_distance(&self, skel: &Skeleton, point: Coord) -> f64 {
+        let time = self
+            .candidates(point)
+            .filter_map(|piece| time_at(skel, piece, point))
+            .min_by(f64::total_cmp);
+        if let Some(time) = time {
+            return if self.inward { time } else { -time };
+        }
+        // The point lies on the side of the boundary which the wavefront does not sweep.
+        let distance = self
+            .input
+            .iter()
+            .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+            .map(|ls| Euclidean::distance(&Point::from(point), ls))
+            .fold(f64::INFINITY, f64::min);
+        if self.input.contains(&Point::from(point)) {
+            distance
+        } else {
+            -distance
+        }
+    }
+
+    /// Returns whether the edge of the wavefront on the line of `edge` passes `point` at time `time`.
+    fn covers(&self, skel: &Skeleton, edge: &Ray, point: Coord, time: f64) -> bool {
+        self.candidates(point).any(|piece| {
+            let ray = skel.ray_vector[piece.left].unwrap_base_ray().1;
+            ray.origin == edge.origin
+                && ray.angle == edge.angle
+                && time_at(skel, piece, point).is_some_and(|t| (t - time).abs() <= tolerance(time))
+        })
+    }
+}
+
+impl Skeleton {
+    /// Returns the signed distance from the input of this skeleton to `point`, positive inside and negative outside.
+    ///
+    /// On the side of the boundary swept by the wavefront, the distance is the time the wavefront takes to reach `point`,
+    /// i.e. the smallest distance by which the input has to be buffered for its boundary to pass `point`. This differs from
+    /// the Euclidean distance near reflex corners, where the wavefront is mitered. On the other side of the boundary, the
+    /// Euclidean distance to the boundary is returned; build the skeleton of the other side to measure those points by the
+    /// wavefront as well.
+    ///
+    /// The first call indexes the wavefront, which takes about as long as building the skeleton. Later calls only look up
+    /// the index, so a single skeleton can classify many points cheaply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{coord, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+    /// );
+    /// let inward = skeleton_of_polygon(&p1, Side::Interior);
+    /// assert!((inward.signed_distance(coord! { x: 5., y: 2. }) - 2.).abs() < 1e-9);
+    /// assert!((inward.signed_distance(coord! { x: 5., y: -3. }) + 3.).abs() < 1e-9);
+    ///
+    /// let outward = skeleton_of_polygon(&p1, Side::Exterior);
+    /// // The wavefront of the square is mitered at its corners.
+    /// assert!((outward.signed_distance(coord! { x: 12., y: 12. }) + 2.).abs() < 1e-9);
+    /// assert!((outward.signed_distance(coord! { x: 5., y: 2. }) - 2.).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn signed_distance(&self, point: Coord) -> f64 {
+        self.distance_field().signed_distance(self, point)
+    }
+
+    /// Returns the projection of `point` onto the closest edge of the input of this skeleton, or `None` if the input has no edges.
+    ///
+    /// Together with [`Skeleton::offset_point`], this carries positions along the boundary (e.g. addresses along a frontage)
+    /// over to the buffered boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{coord, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let projection = skel.project_onto_boundary(coord! { x: 3., y: 0.5 }).unwrap();
+    ///
+    /// assert_eq!(projection.edge.start, coord! { x: 0., y: 0. });
+    /// assert_eq!(projection.edge.end, coord! { x: 10., y: 0. });
+    /// assert!((projection.parameter - 0.3).abs() < 1e-9);
+    /// assert!((projection.distance - 0.5).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn project_onto_boundary(&self, point: Coord) -> Option<BoundaryProjection> {
+        let vertex_queue = &self.initial_vertex_queue;
+        vertex_queue
+            .live_nodes()
+            .filter(|&n| vertex_queue.lv(n) != vertex_queue.rv(n))
+            .map(|n| {
+                let vertex = vertex_queue.get_real_index(n);
+                let next = vertex_queue.get_real_index(vertex_queue.rv(n));
+                let edge = Line::new(
+                    self.ray_vector[vertex].location_at(0.),
+                    self.ray_vector[next].location_at(0.),
+                );
+                let d = edge.delta();
+                let len2 = d.inner_product(&d);
+                let parameter = if len2 == 0. {
+                    0.
+                } else {
+                    ((point - edge.start).inner_product(&d) / len2).clamp(0., 1.)
+                };
+                BoundaryProjection {
+                    edge,
+                    parameter,
+                    distance: point.dist_coord(&(edge.start + d * parameter)),
+                    vertex,
+                }
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Returns where the foot of `projection` is after the boundary has been buffered by `offset_distance`, i.e. the point of the
+    /// wavefront at time `offset_distance` reached by moving the foot perpendicularly to its edge.
+    ///
+    /// Returns `None` if `offset_distance` is negative, or if that part of the edge has vanished from the wavefront by then
+    /// (because the face of the edge ends before, or another part of the wavefront has cut it off).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{coord, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let projection = skel.project_onto_boundary(coord! { x: 3., y: 0.5 }).unwrap();
+    ///
+    /// let q = skel.offset_point(&projection, 2.).unwrap();
+    /// assert!((q.x - 3.).abs() < 1e-9 && (q.y - 2.).abs() < 1e-9);
+    /// // The corner of the wavefront passes x = 3 at time 3, after which the point is in the face of the left edge.
+    /// assert!(skel.offset_point(&projection, 3.5).is_none());
+    /// ```
+    #[must_use]
+    pub fn offset_point(
+        &self,
+        projection: &BoundaryProjection,
+        offset_distance: f64,
+    ) -> Option<Coord> {
+        if offset_distance < 0. {
+            return None;
+        }
+        let vertex = &self.ray_vector[projection.vertex];
+        let edge = vertex.unwrap_base_ray().1;
+        let d = projection.edge.delta();
+        let foot = projection.edge.start + d * projection.parameter;
+        // The vertex moves away from the edge at unit speed, so the part of its velocity normal to the edge is the unit normal.
+        let velocity = vertex.location_at(1.) - vertex.location_at(0.);
+        let len2 = d.inner_product(&d);
+        let normal = if len2 == 0. {
+            velocity
+        } else {
+            velocity - d * (velocity.inner_product(&d) / len2)
+        };
+        let point = foot + normal * offset_distance;
+        self.distance_field()
+            .covers(self, &edge, point, offset_distance)
+            .then_some(point)
+    }
+
+    /// Returns the location and the time of the node of this skeleton closest to `point`, or `None` if the skeleton has no nodes.
+    ///
+    /// The nodes are the vertices of the input and the points where the wavefront changes its shape (by an event).
+    /// The time of a node is the distance by which the input has to be buffered for the wavefront to pass it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{coord, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let (location, time) = skel.nearest_node(coord! { x: 8., y: 1.2 }).unwrap();
+    ///
+    /// assert!(location.x == 9. && location.y == 1.);
+    /// assert_eq!(time, 1.);
+    /// ```
+    #[must_use]
+    pub fn nearest_node(&self, point: Coord) -> Option<(Coord, f64)> {
+        self.ray_vector
+            .iter()
+            .map(|v| (v.inner_location(), v.time_elapsed()))
+            .min_by(|(a, _), (b, _)| point.dist_coord(a).total_cmp(&point.dist_coord(b)))
+    }
+
+    /// Returns the local width of the input of this skeleton at `point`, i.e. twice the time of the point of the skeleton
+    /// closest to `point`, or `None` if the skeleton has no arcs.
+    ///
+    /// For an inward skeleton of an elongated polygon, such as a river or a road, this is the width of the polygon across `point`.
+    /// Near a convex corner, the closest point of the skeleton lies on the bisector of the corner, so the width drops to 0
+    /// towards the corner.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{coord, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 2.), (6., 2.), (6., 4.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    ///
+    /// assert!((skel.width_at(coord! { x: 7.5, y: 0.5 }).unwrap() - 2.).abs() < 1e-9);
+    /// assert!((skel.width_at(coord! { x: 3., y: 3.5 }).unwrap() - 4.).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn width_at(&self, point: Coord) -> Option<f64> {
+        let mut nearest: Option<(f64, f64)> = None;
+        for v in &self.ray_vector {
+            let VertexType::Tree {
+                axis,
+                parent,
+                time_elapsed,
+                ..
+            } = *v
+            else {
+                continue;
+            };
+            // The time grows linearly along each arc, by the distance along the axis for an arc to infinity.
+            let (end, end_time, ratio_max) = match self.ray_vector.get(parent) {
+                Some(p) => (p.inner_location(), p.time_elapsed(), 1.),
+                None => (axis.point_by_ratio(1.), time_elapsed + 1., f64::INFINITY),
+            };
+            let d = end - axis.origin;
+            let len2 = d.inner_product(&d);
+            let ratio = if len2 == 0. {
+                0.
+            } else {
+                ((point - axis.origin).inner_product(&d) / len2).clamp(0., ratio_max)
+            };
+            let dist = point.dist_coord(&(axis.origin + d * ratio));
+            if nearest.is_none_or(|(best, _)| dist < best) {
+                nearest = Some((dist, time_elapsed + (end_time - time_elapsed) * ratio));
+            }
+        }
+        nearest.map(|(_, time)| 2. * time)
+    }
+
+    fn distance_field(&self) -> &DistanceField {
+        self.distance_field.get_or_init(|| DistanceField::new(self))
+    }
+}
+
+/// Returns the time at which the edge of `piece` passes `point`, or `None` if it does not.
+fn time_at(skel: &Skeleton, piece: &Piece, point: Coord) -> Option<f64> {
+    let left = &skel.ray_vector[piece.left];
+    let time = point.dist_ray(&left.unwrap_base_ray().1);
+    let tol = tolerance(time);
+    if time < piece.start - tol || time > piece.end + tol {
+        return None;
+    }
+    let a = left.location_at(time);
+    let b = skel.ray_vector[piece.right].location_at(time);
+    let d = b - a;
+    let len = d.norm();
+    if len <= tol {
+        return (point.dist_coord(&a) <= tol).then_some(time);
+    }
+    let along = (point - a).inner_product(&d) / len;
+    let across = (point - a).outer_product(&d).abs() / len;
+    (across <= tol && along >= -tol && along <= len + tol).then_some(time)
+}
+
+/// Replays the events of `skel` and returns the pieces swept by the edges of its wavefront.
+fn sweep_pieces(skel: &Skeleton) -> Vec<Piece> {
+    let mut vertex_queue = skel.initial_vertex_queue.clone();
+    // The edge to the right of each live node: its left and right vertices, and the time since it has had them.
+    let edge = |vertex_queue: &VertexQueue, n: usize| {
+        let node = &vertex_queue.content[n];
+        (!node.done && node.left != node.right).then(|| {
+            let right = vertex_queue.content[node.right.get_index()].index;
+            (node.index.get_real_index(), right.get_real_index())
+        })
+    };
+    let mut open: Vec<Option<(usize, usize, f64)>> = (0..vertex_queue.content.len())
+        .map(|n| edge(&vertex_queue, n).map(|(l, r)| (l, r, 0.)))
+        .collect();
+    let mut pieces = Vec::new();
+    let mut close = |(left, right, start): (usize, usize, f64), end: f64| {
+        if start < end {
+            pieces.push(Piece {
+                left,
+                right,
+                start,
+                end,
+            });
+        }
+    };
+    let mut touched = Vec::new();
+    for event in &skel.event_queue {
+        let (time, nodes) = match *event {
+            Event::VertexEvent {
+                time, merge_from, ..
+            } => (time, [Some(merge_from), None]),
+            Event::EdgeEvent {
+                time,
+                split_from,
+                split_into,
+                ..
+            } => (time, [Some(split_from), Some(split_into)]),
+        };
+        let neighbours = |vertex_queue: &VertexQueue, n: usize, touched: &mut Vec<usize>| {
+            let n = IndexType::PointerIndex(n);
+            touched.extend([n, vertex_queue.lv(n), vertex_queue.rv(n)].map(|i| i.get_index()));
+        };
+        touched.clear();
+        for &n in nodes.iter().flatten() {
+            neighbours(&vertex_queue, n, &mut touched);
+        }
+        let len = vertex_queue.content.len();
+        Skeleton::apply_event(&mut vertex_queue, event);
+        open.resize(vertex_queue.content.len(), None);
+        let after: Vec<usize> = touched
+            .iter()
+            .copied()
+            .chain(len..vertex_queue.content.len())
+            .collect();
+        for n in after {
+            neighbours(&vertex_queue, n, &mut touched);
+        }
+        touched.sort_unstable();
+        touched.dedup();
+        for &n in &touched {
+            let current = edge(&vertex_queue, n);
+            if open[n].map(|(l, r, _)| (l, r)) == current {
+                continue;
+            }
+            if let Some(piece) = open[n] {
+                close(piece, time);
+            }
+            open[n] = current.map(|(l, r)| (l, r, time));
+        }
+    }
+    for piece in open.into_iter().flatten() {
+        close(piece, f64::INFINITY);
+    }
+    pieces
+}
@@ -1,15 +1,43 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "rayon")]
+use std::sync::Arc;
+use std::sync::{Mutex, OnceLock, PoisonError, TryLockError};
 
 use geo::winding_order::WindingOrder;
 use geo::{Contains, Winding};
-use geo_types::{LineString, MultiPolygon, Polygon};
+use geo_types::{Coord, Geometry, LineString, MultiLineString, MultiPolygon, Polygon};
 
-use crate::priority_queue::PriorityQueue;
+use crate::curve::{self, CircularArc, Corner, CurvedRing};
+use crate::edge_index::{EdgeIndex, SplitBound};
+use crate::monitor::{Monitor, Progress};
+use crate::postprocess;
+use crate::priority_queue::{Handle, PriorityQueue, QueueStats};
+use crate::repair;
 use crate::util::*;
 use crate::vertex_queue::*;
+use crate::{BufferOptions, Error};
+use splits::SplitSchedule;
 
-#[derive(Debug)]
+mod dcel;
+mod distance;
+mod replay;
+mod snapshot;
+mod splits;
+mod stats;
+mod transform;
+
+pub use dcel::{Dcel, DcelEdgeKind, DcelVertex, Face, HalfEdge};
+pub use distance::BoundaryProjection;
+pub use replay::{EventFrame, EventKind, SkeletonEvent};
+pub use stats::SkeletonStats;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 pub(crate) enum VertexType {
     Tree {
@@ -21,23 +49,23 @@ pub(crate) enum VertexType {
     },
     Split {
         anchor: usize,
-        location: Coordinate,
+        location: Coord,
         split_left: usize,
         split_right: usize,
         time_elapsed: f64,
     },
     Root {
-        location: Coordinate,
+        location: Coord,
         time_elapsed: f64,
     },
 }
 
 impl VertexType {
-    fn init_tree_vertex(lv: Coordinate, cv: Coordinate, rv: Coordinate, orient: bool) -> Self {
+    fn init_tree_vertex(lv: Coord, cv: Coord, rv: Coord, orient: bool) -> Self {
         let r1 = Ray::new(cv, lv);
         let r2 = Ray::new(cv, rv);
         let mut r3 = r1.bisector(&r2, cv, orient);
-        r3.angle = r3.angle / (r3.point_by_ratio(1.).dist_ray(&r2));
+        r3.angle = div_or_keep(r3.angle, r3.point_by_ratio(1.).dist_ray(&r2));
         VertexType::Tree {
             axis: r3,
             left_ray: r1,
@@ -47,13 +75,15 @@ impl VertexType {
         }
     }
 
-    fn new_tree_vertex(location: Coordinate, left_ray: Ray, right_ray: Ray, orient: bool) -> Self {
+    fn new_tree_vertex(location: Coord, left_ray: Ray, right_ray: Ray, orient: bool) -> Self {
         let mut axis = left_ray.bisector(&right_ray, location, orient);
-        axis.angle = axis.angle
-            / f64::abs(
+        axis.angle = div_or_keep(
+            axis.angle,
+            f64::abs(
                 axis.point_by_ratio(1.).dist_ray(&left_ray)
                     - axis.point_by_ratio(0.).dist_ray(&left_ray),
-            );
+            ),
+        );
         let time_elapsed = axis.origin.dist_ray(&left_ray);
         VertexType::Tree {
             axis,
@@ -64,78 +94,36 @@ impl VertexType {
         }
     }
 
-    fn initialize_from_polygon(input_polygon: &Polygon, orient: bool) -> Vec<Self> {
-        let len = input_polygon.exterior().0.len() - 1;
+    fn initialize_from_polygon_vector(input_polygon_vector: &[Polygon], orient: bool) -> Vec<Self> {
         let mut ret = Vec::with_capacity(
-            len + 1
-            + (input_polygon.interiors().iter().map(|ls| ls.0.len() + 1).sum::<usize>())
+            input_polygon_vector
+                .iter()
+                .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+                .map(|ls| ls.0.len() + 1)
+                .sum(),
         );
-
-        for cur in 0..len {
-            let prv = (cur + len - 1) % len;
-            let nxt = (cur + 1) % len;
-            let new_vertex = VertexType::init_tree_vertex(
-                input_polygon.exterior().0[prv].into(),
-                input_polygon.exterior().0[cur].into(),
-                input_polygon.exterior().0[nxt].into(),
-                orient,
-            );
-            ret.push(new_vertex);
-        }
-        for i in 0..input_polygon.interiors().len() {
-            let len = input_polygon.interiors()[i].0.len() - 1;
-            for cur in 0..len {
-                let prv = (cur + len - 1) % len;
-                let nxt = (cur + 1) % len;
-                let new_node = VertexType::init_tree_vertex(
-                    input_polygon.interiors()[i].0[prv].into(),
-                    input_polygon.interiors()[i].0[cur].into(),
-                    input_polygon.interiors()[i].0[nxt].into(),
-                    orient,
-                );
-                ret.push(new_node);
+        for p in input_polygon_vector {
+            Self::push_ring(&mut ret, p.exterior(), orient);
+            for ls in p.interiors() {
+                Self::push_ring(&mut ret, ls, orient);
             }
         }
         ret
     }
 
-    fn initialize_from_polygon_vector(
-        input_polygon_vector: &Vec<Polygon>,
-        orient: bool,
-    ) -> Vec<Self> {
-        let mut ret = Vec::new();
-        for p in input_polygon_vector {
-            let len = p.exterior().0.len() - 1;
-            for cur in 0..len {
-                let prv = (cur + len - 1) % len;
-                let nxt = (cur + 1) % len;
-                let new_vertex = VertexType::init_tree_vertex(
-                    p.exterior().0[prv].into(),
-                    p.exterior().0[cur].into(),
-                    p.exterior().0[nxt].into(),
-                    orient,
-                );
-                ret.push(new_vertex);
-            }
-            for i in 0..p.interiors().len() {
-                let len = p.interiors()[i].0.len() - 1;
-                for cur in 0..len {
-                    let prv = (cur + len - 1) % len;
-                    let nxt = (cur + 1) % len;
-                    let new_node = VertexType::init_tree_vertex(
-                        p.interiors()[i].0[prv].into(),
-                        p.interiors()[i].0[cur].into(),
-                        p.interiors()[i].0[nxt].into(),
-                        orient,
-                    );
-                    ret.push(new_node);
-                }
-            }
+    /// Pushes the initial tree vertices of the closed ring `ls` to `ret`.
+    fn push_ring(ret: &mut Vec<Self>, ls: &LineString, orient: bool) {
+        let len = ls.0.len() - 1;
+        for cur in 0..len {
+            let prv = (cur + len - 1) % len;
+            let nxt = (cur + 1) % len;
+            ret.push(VertexType::init_tree_vertex(
+                ls.0[prv], ls.0[cur], ls.0[nxt], orient,
+            ));
         }
-        ret
     }
 
-    const fn inner_location(&self) -> Coordinate {
+    const fn inner_location(&self) -> Coord {
         match self {
             VertexType::Tree { axis, .. } => axis.origin,
             VertexType::Split { location, .. } => *location,
@@ -151,6 +139,11 @@ impl VertexType {
         }
     }
 
+    /// Returns the location of this tree vertex at time `time`, extrapolating its axis if it has not been created yet.
+    pub(crate) fn location_at(&self, time: f64) -> Coord {
+        self.unwrap_ray().point_by_ratio(time - self.time_elapsed())
+    }
+
     fn unwrap_ray(&self) -> Ray {
         if let VertexType::Tree { axis, .. } = self {
             return *axis;
@@ -179,7 +172,8 @@ impl VertexType {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Event {
     VertexEvent {
         time: f64,
@@ -254,18 +248,27 @@ impl Event {
 enum Timeline {
     ShrinkEvent {
         time: f64,
-        location: Coordinate,
+        location: Coord,
         left_vertex: IndexType,
         right_vertex: IndexType,
         left_real: usize,
         right_real: usize,
+        left_generation: u32,
+        right_generation: u32,
         tie_break: f64,
+        origins: [Coord; 2],
+        rule: TieBreak,
     },
     SplitEvent {
         time: f64,
-        location: Coordinate,
+        location: Coord,
         anchor_vertex: IndexType,
         anchor_real: usize,
+        anchor_generation: u32,
+        /// The nodes at the ends of the wavefront edge to split, with their generations when the split was found.
+        split_edge: [(IndexType, u32); 2],
+        origin: Coord,
+        rule: TieBreak,
     },
 }
 
@@ -282,19 +285,58 @@ impl fmt::Display for Timeline {
     }
 }
 
+impl Timeline {
+    const fn time(&self) -> f64 {
+        match self {
+            Timeline::ShrinkEvent { time, .. } | Timeline::SplitEvent { time, .. } => *time,
+        }
+    }
+
+    const fn rule(&self) -> TieBreak {
+        match self {
+            Timeline::ShrinkEvent { rule, .. } | Timeline::SplitEvent { rule, .. } => *rule,
+        }
+    }
+}
+
 impl PartialOrd for Timeline {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let t1 = match self {
-            Timeline::ShrinkEvent { time, .. } => *time,
-            Timeline::SplitEvent { time, .. } => *time,
-        };
-        let t2 = match other {
-            Timeline::ShrinkEvent { time, .. } => *time,
-            Timeline::SplitEvent { time, .. } => *time,
-        };
+        let (t1, t2) = (self.time(), other.time());
         if fneq(t1, t2) {
             return Some(t1.partial_cmp(&t2).unwrap());
         }
+        if self.rule() == TieBreak::Location {
+            let key = |event: &Timeline| match *event {
+                Timeline::ShrinkEvent {
+                    location,
+                    origins: [left, right],
+                    left_real,
+                    right_real,
+                    ..
+                } => (
+                    1,
+                    location.x_y(),
+                    left.x_y(),
+                    right.x_y(),
+                    left_real,
+                    right_real,
+                ),
+                Timeline::SplitEvent {
+                    location,
+                    origin,
+                    anchor_real,
+                    ..
+                } => (
+                    0,
+                    location.x_y(),
+                    origin.x_y(),
+                    origin.x_y(),
+                    anchor_real,
+                    anchor_real,
+                ),
+            };
+            return key(self).partial_cmp(&key(other));
+        }
         let x1 = match self {
             Timeline::ShrinkEvent {
                 location,
@@ -302,12 +344,12 @@ impl PartialOrd for Timeline {
                 right_real,
                 tie_break,
                 ..
-            } => (1, tie_break, location, left_real, right_real),
+            } => (1, tie_break, location.x_y(), left_real, right_real),
             Timeline::SplitEvent {
                 location,
                 anchor_real,
                 ..
-            } => (0, &0., location, anchor_real, anchor_real),
+            } => (0, &0., location.x_y(), anchor_real, anchor_real),
         };
         let x2 = match other {
             Timeline::ShrinkEvent {
@@ -316,32 +358,359 @@ impl PartialOrd for Timeline {
                 right_real,
                 tie_break,
                 ..
-            } => (1, tie_break, location, left_real, right_real),
+            } => (1, tie_break, location.x_y(), left_real, right_real),
             Timeline::SplitEvent {
                 location,
                 anchor_real,
                 ..
-            } => (0, &0., location, anchor_real, anchor_real),
+            } => (0, &0., location.x_y(), anchor_real, anchor_real),
         };
         Some(x1.partial_cmp(&x2).unwrap())
     }
 }
 
+/// The queue of pending events, which drops the events of a vertex of the wavefront as soon as the vertex changes.
+struct EventQueue {
+    pq: PriorityQueue<Timeline>,
+    /// How the events inserted from now on break ties.
+    tie_break: TieBreak,
+    /// The events referring to each node of the vertex queue, with the generation of the node they refer to.
+    by_node: Vec<Vec<(Handle, u32)>>,
+}
+
+impl EventQueue {
+    const fn new() -> Self {
+        Self {
+            pq: PriorityQueue::new(),
+            tie_break: TieBreak::OriginDistance,
+            by_node: Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self) {
+        self.pq.initialize();
+        self.by_node.iter_mut().for_each(Vec::clear);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    const fn stats(&self) -> QueueStats {
+        self.pq.stats()
+    }
+
+    fn insert(&mut self, event: Timeline) -> Handle {
+        let nodes = match event {
+            Timeline::ShrinkEvent {
+                left_vertex,
+                right_vertex,
+                left_generation,
+                right_generation,
+                ..
+            } => [
+                Some((left_vertex, left_generation)),
+                Some((right_vertex, right_generation)),
+            ],
+            Timeline::SplitEvent {
+                anchor_vertex,
+                anchor_generation,
+                ..
+            } => [Some((anchor_vertex, anchor_generation)), None],
+        };
+        let handle = self.pq.insert(event);
+        for (node, generation) in nodes.into_iter().flatten() {
+            let node = node.get_index();
+            if self.by_node.len() <= node {
+                self.by_node.resize_with(node + 1, Vec::new);
+            }
+            self.by_node[node].push((handle, generation));
+        }
+        handle
+    }
+
+    fn pop(&mut self) -> Option<Timeline> {
+        self.pq.pop()
+    }
+
+    /// Returns the time of the next event, or `None` if the queue is empty.
+    fn next_time(&self) -> Option<f64> {
+        self.pq.peek().map(Timeline::time)
+    }
+
+    /// Removes the event of `handle` if it is still queued.
+    fn remove(&mut self, handle: Handle) {
+        self.pq.remove(handle);
+    }
+
+    /// Removes the events referring to an older generation of the node `cv` than its current one, or to `cv` if it is done.
+    fn retire(&mut self, cv: IndexType, vertex_queue: &VertexQueue) {
+        let Some(events) = self.by_node.get_mut(cv.get_index()) else {
+            return;
+        };
+        let pq = &mut self.pq;
+        events.retain(|&(handle, generation)| {
+            if vertex_queue.is_current(cv, generation) {
+                return true;
+            }
+            pq.remove(handle);
+            false
+        });
+    }
+}
+
+/// A split event candidate: the time and location of the split, and the node and vertex of the split edge.
+type SplitCandidate = (f64, Coord, IndexType, usize);
+
+/// Returns the key ordering split event candidates, earliest first.
+fn split_key(c: &SplitCandidate) -> (f64, (f64, f64), IndexType, usize) {
+    (c.0, c.1.x_y(), c.2, c.3)
+}
+
+/// The least number of events between two cached wavefront snapshots.
+const MIN_SNAPSHOT_INTERVAL: usize = 32;
+/// The most wavefront snapshots cached by a skeleton, bounding their memory to a constant factor of the input.
+const MAX_SNAPSHOTS: usize = 16;
+/// The angle (in radian) between consecutive vertices of the arcs of rounded corners.
+pub(crate) const ARC_STEP: f64 = 0.1;
+
+/// How the corners of a buffer around the vertices of the wavefront which bend away from the swept region are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum Corners {
+    /// The vertices of the wavefront themselves, i.e. miter-joint-like corners.
+    #[default]
+    Miter,
+    /// Miter-joint-like corners, cut off perpendicular to the bisector where they reach farther from the vertex of the input
+    /// than the given multiple of the distance.
+    MiterLimit(f64),
+    /// Arcs whose consecutive vertices are the given angle (in radian) apart.
+    Round(f64),
+    /// Straight segments between the ends of the offset edges.
+    Bevel,
+}
+
+/// A straight skeleton of a (multi-)polygon, from which the polygon can be buffered by any distance.
+///
 /// This module implements a core logic of the polygon buffering algorithm. In the normal cases, you don't need to know how this
-/// module works, nor need to use this module.
-pub(crate) struct Skeleton {
+/// module works, nor need to use this module. Building the skeleton is the expensive part of buffering, so a skeleton built by
+/// [`skeleton_of_polygon`](crate::skeleton_of_polygon) or [`skeleton_of_multi_polygon`](crate::skeleton_of_multi_polygon) can be kept
+/// (and, with the `serde` feature, serialized) to buffer the same polygon by several distances.
+///
+/// A skeleton is `Send + Sync`: its cached wavefronts are guarded internally, so one skeleton behind an `Arc` can serve
+/// buffers to many threads or async tasks at once.
+///
+/// ```
+/// use std::sync::Arc;
+/// use geo_buf::{skeleton_of_polygon, BufferOptions, Side};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let skel = Arc::new(skeleton_of_polygon(&p1, Side::Interior));
+/// let handles: Vec<_> = [0.1, 0.2, 0.45]
+///     .into_iter()
+///     .map(|distance| {
+///         let skel = Arc::clone(&skel);
+///         std::thread::spawn(move || skel.buffer(distance, &BufferOptions::new()))
+///     })
+///     .collect();
+/// for handle in handles {
+///     assert!(!handle.join().unwrap().0.is_empty());
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{buffer_polygon, skeleton_of_polygon, BufferOptions, Side};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+/// );
+/// let skel = skeleton_of_polygon(&p1, Side::Interior);
+///
+/// # #[cfg(feature = "serde")]
+/// let skel: geo_buf::skeleton::Skeleton = serde_json::from_str(&serde_json::to_string(&skel).unwrap()).unwrap();
+/// for distance in [0.1, 0.2, 0.45] {
+///     assert_eq!(skel.buffer(distance, &BufferOptions::new()), buffer_polygon(&p1, -distance));
+/// }
+/// ```
+///
+/// With the `serde` feature, a deserialized skeleton is checked as [`Skeleton::from_bytes`] checks the binary form,
+/// and the fields derived from its nodes and events are rebuilt rather than read.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use geo_buf::{skeleton_of_polygon, Side};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let mut json = serde_json::to_value(skeleton_of_polygon(&p1, Side::Interior)).unwrap();
+/// json["ray_vector"][0]["Tree"]["parent"] = 1000.into();
+///
+/// let err = serde_json::from_value::<geo_buf::skeleton::Skeleton>(json).unwrap_err();
+/// assert!(err.to_string().contains("vertex index out of range"));
+/// # }
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "snapshot::SkeletonParts"))]
+pub struct Skeleton {
     ray_vector: Vec<VertexType>,
     event_queue: Vec<Event>,
+    /// `replay_until[i]` is the latest time among the first `i + 1` events, so that the events to replay
+    /// until some time can be found by binary search even where the event times are out of order within `EPS`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    replay_until: Vec<f64>,
     initial_vertex_queue: VertexQueue,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    snapshot_interval: usize,
+    /// The side of the input the wavefront moves to.
+    side: Side,
+    /// Whether the exterior of the first input polygon is clockwise, for [`OutputWinding::MatchInput`](crate::OutputWinding::MatchInput).
+    pub(crate) clockwise_input: bool,
+    /// Wavefronts after every multiple of `snapshot_interval` events, cached as they are replayed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    snapshots: Mutex<BTreeMap<usize, VertexQueue>>,
+    /// The wavefront of the last query, see [`Skeleton::get_vertex_queue`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cursor: Mutex<Option<WavefrontCursor>>,
+    /// The index for [`Skeleton::signed_distance`], built on the first query.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    distance_field: OnceLock<distance::DistanceField>,
+}
+
+/// The side of a ring (or of the boundary of a polygon) to work on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Side {
+    /// The region enclosed by the ring, where deflation takes place.
+    Interior,
+    /// The region outside of the ring, where inflation takes place.
+    Exterior,
+}
+
+impl Side {
+    /// Returns the side a buffer by `distance` moves to: [`Side::Interior`] for negative distances, [`Side::Exterior`] otherwise.
+    pub(crate) fn of_distance(distance: f64) -> Self {
+        if distance < 0. {
+            Self::Interior
+        } else {
+            Self::Exterior
+        }
+    }
+
+    /// Returns `true` for [`Side::Interior`], the former `orientation` flag of the skeleton construction.
+    pub(crate) const fn is_interior(self) -> bool {
+        matches!(self, Self::Interior)
+    }
+}
+
+/// How the construction of a straight skeleton orders events happening at the same time (up to rounding), see
+/// [`BufferOptions::tie_break`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TieBreak {
+    /// Split events before shrink events, and shrink events by the distance between the origins of the two vertices of the
+    /// wavefront which collide, then by location. Events which are still tied are taken in the order of the vertices of the input,
+    /// so the result may depend on which vertex each ring starts at.
+    #[default]
+    OriginDistance,
+    /// Split events before shrink events, then lexicographically by location (`x` first), then by the origins of the vertices
+    /// of the wavefront involved. This only depends on the geometry of the input, so events tied exactly are processed in the
+    /// same order whichever vertex each ring starts at.
+    Location,
+}
+
+/// Converts the `orientation` flag the skeleton functions used to take: `true` is [`Side::Interior`] and `false` is [`Side::Exterior`].
+impl From<bool> for Side {
+    fn from(interior: bool) -> Self {
+        if interior {
+            Self::Interior
+        } else {
+            Self::Exterior
+        }
+    }
+}
+
+/// The wavefront of a [`Skeleton`] at some time, which can be moved to other times cheaply.
+///
+/// Moving forward replays only the events in between, starting from the latest cached snapshot if that is closer.
+/// Moving backward rolls the vertex queue back to the checkpoint taken before the first event to undo,
+/// or restarts from a snapshot if the cursor itself started after that event.
+pub(crate) struct WavefrontCursor {
+    vertex_queue: VertexQueue,
+    /// The number of events applied to the snapshot this cursor started from.
+    base: usize,
+    /// `checkpoints[i]` is the state of `vertex_queue` before the `base + i`-th event was applied.
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl WavefrontCursor {
+    /// Moves the cursor to the time `time_elapsed` of the skeleton `skel`, and returns the wavefront at that time.
+    ///
+    /// `skel` has to be the skeleton this cursor was created from.
+    pub(crate) fn seek(&mut self, skel: &Skeleton, time_elapsed: f64) -> &VertexQueue {
+        let target = skel
+            .replay_until
+            .partition_point(|&time| time <= time_elapsed);
+        let applied = self.base + self.checkpoints.len();
+        let restart = if target < self.base {
+            Some(skel.latest_snapshot(target))
+        } else if target > applied {
+            Some(skel.latest_snapshot(target)).filter(|(base, _)| *base > applied)
+        } else {
+            None
+        };
+        if let Some((base, vertex_queue)) = restart {
+            self.vertex_queue = vertex_queue;
+            self.base = base;
+            self.checkpoints.clear();
+        } else if target < applied {
+            self.vertex_queue
+                .rollback(self.checkpoints[target - self.base]);
+            self.checkpoints.truncate(target - self.base);
+        }
+        for i in self.base + self.checkpoints.len()..target {
+            self.checkpoints.push(self.vertex_queue.checkpoint());
+            Skeleton::apply_event(&mut self.vertex_queue, &skel.event_queue[i]);
+            if (i + 1) % skel.snapshot_interval == 0 {
+                skel.store_snapshot(i + 1, &self.vertex_queue);
+            }
+        }
+        &self.vertex_queue
+    }
 }
 
 impl Skeleton {
+    /// Returns the wavefront `vertex_queue` at the time `offset_distance` as a multi-polygon.
+    ///
+    /// The coordinates of the rings are stored in the (empty) vectors of `spare` as long as there are any left.
     pub(crate) fn apply_vertex_queue(
         &self,
         vertex_queue: &VertexQueue,
         offset_distance: f64,
+        spare: &mut Vec<Vec<Coord>>,
     ) -> MultiPolygon {
-        let mut res = Vec::new();
+        Self::assemble_multi_polygon(self.wavefront_rings(vertex_queue, offset_distance, spare))
+    }
+
+    /// Returns the closed rings of the wavefront `vertex_queue` at the time `offset_distance`, in the order of the queue.
+    ///
+    /// The coordinates of the rings are stored in the (empty) vectors of `spare` as long as there are any left.
+    fn wavefront_rings(
+        &self,
+        vertex_queue: &VertexQueue,
+        offset_distance: f64,
+        spare: &mut Vec<Vec<Coord>>,
+    ) -> Vec<LineString> {
         let mut lsv = Vec::new();
         let mut crdv = Vec::new();
         let mut cur_vidx = usize::MAX;
@@ -353,7 +722,7 @@ impl Skeleton {
                     lsv.push(ls);
                 }
                 cur_vidx = vidx;
-                crdv = Vec::new();
+                crdv = spare.pop().unwrap_or_default();
             }
             let crd = self.ray_vector[idx]
                 .unwrap_ray()
@@ -365,156 +734,373 @@ impl Skeleton {
             ls.close();
             lsv.push(ls);
         }
-        for ls in &lsv {
-            if ls.winding_order() == Some(WindingOrder::CounterClockwise) {
-                let p1: Polygon = Polygon::new(ls.clone(), vec![]);
-                res.push(p1);
-            }
-        }
-        for ls in &lsv {
-            if ls.winding_order() == Some(WindingOrder::Clockwise) {
-                for e in &mut res {
-                    if e.contains(ls) {
-                        e.interiors_push(ls.clone());
-                        break;
-                    }
-                }
-            }
-        }
-        MultiPolygon::new(res)
+        lsv
+    }
+
+    /// Returns the input of this skeleton, i.e. its wavefront at time `0`.
+    pub(crate) fn input_polygons(&self) -> MultiPolygon {
+        self.apply_vertex_queue(&self.initial_vertex_queue, 0., &mut Vec::new())
+    }
+
+    /// Returns the locations at the time `offset_distance` of the vertices of the wavefront `vertex_queue` which are offsets
+    /// of vertices of the input (rather than created by events), computed exactly as [`Skeleton::apply_vertex_queue`] does.
+    pub(crate) fn input_vertex_offsets(
+        &self,
+        vertex_queue: &VertexQueue,
+        offset_distance: f64,
+    ) -> Vec<Coord> {
+        vertex_queue
+            .iter()
+            .map(|(_, _, idx)| &self.ray_vector[idx])
+            .filter(|v| v.time_elapsed() == 0.)
+            .map(|v| {
+                v.unwrap_ray()
+                    .point_by_ratio(offset_distance - v.time_elapsed())
+            })
+            .collect()
+    }
+
+    /// Returns the origins of the vertices of the wavefront `vertex_queue` whose corners [`Corners::MiterLimit`] with `limit` cuts off
+    /// in [`Skeleton::apply_vertex_queue_with_corners`].
+    pub(crate) fn limited_miters(&self, vertex_queue: &VertexQueue, limit: f64) -> Vec<Coord> {
+        vertex_queue
+            .iter()
+            .map(|(_, _, idx)| &self.ray_vector[idx])
+            .filter_map(|v| {
+                let (lray, _) = v.unwrap_base_ray();
+                let cray = v.unwrap_ray();
+                let corner = (lray.angle + cray.angle).norm() <= (lray.angle - cray.angle).norm();
+                (corner && cray.angle.norm() > limit).then_some(cray.origin)
+            })
+            .collect()
     }
 
-    pub(crate) fn apply_vertex_queue_rounded(
+    /// Returns the lines of the edges of the input on the left and on the right of the node `index`, as rays from one of their ends.
+    pub(crate) fn base_rays(&self, index: usize) -> (Ray, Ray) {
+        self.ray_vector[index].unwrap_base_ray()
+    }
+
+    /// Returns the wavefront `vertex_queue` at the time `offset_distance` as a multi-polygon, with the corners drawn as `corners` says.
+    ///
+    /// The coordinates of the rings are stored in the (empty) vectors of `spare` as in [`Skeleton::apply_vertex_queue`].
+    pub(crate) fn apply_vertex_queue_with_corners(
         &self,
         vertex_queue: &VertexQueue,
         offset_distance: f64,
+        corners: Corners,
+        spare: &mut Vec<Vec<Coord>>,
     ) -> MultiPolygon {
-        let orient = self.get_orientation();
-        let mut res = Vec::new();
         let mut lsv = Vec::new();
         let mut crdv = Vec::new();
         let mut cur_vidx = usize::MAX;
-        for (vidx, _, idx) in vertex_queue.iter() {
+        self.for_each_corner_vertex(vertex_queue, offset_distance, corners, |vidx, crd, _, _| {
             if vidx != cur_vidx {
                 if cur_vidx < usize::MAX {
-                    let mut ls = LineString::from(crdv);
+                    let mut ls = LineString::from(std::mem::take(&mut crdv));
                     ls.close();
                     lsv.push(ls);
                 }
                 cur_vidx = vidx;
-                crdv = Vec::new();
+                crdv = spare.pop().unwrap_or_default();
             }
+            crdv.push(crd);
+        });
+        if cur_vidx < usize::MAX {
+            let mut ls = LineString::from(crdv);
+            ls.close();
+            lsv.push(ls);
+        }
+        Self::assemble_multi_polygon(lsv)
+    }
+
+    /// Calls `emit` with the ring, the location, the node and whether it is a point of a corner drawn as `corners` says (rather than
+    /// the wavefront vertex itself), for each vertex of the wavefront `vertex_queue` at the time `offset_distance` with its corners,
+    /// ring by ring in the order of [`Skeleton::apply_vertex_queue_with_corners`].
+    pub(crate) fn for_each_corner_vertex(
+        &self,
+        vertex_queue: &VertexQueue,
+        offset_distance: f64,
+        corners: Corners,
+        mut emit: impl FnMut(usize, Coord, usize, bool),
+    ) {
+        let orient = self.side == Side::Exterior;
+        for (vidx, _, idx) in vertex_queue.iter() {
+            let mut push = |crd: Coord, corner: bool| emit(vidx, crd, idx, corner);
             let time_left = offset_distance - self.ray_vector[idx].time_elapsed();
             let (lray, rray) = self.ray_vector[idx].unwrap_base_ray();
             let cray = self.ray_vector[idx].unwrap_ray();
             if (lray.angle + cray.angle).norm() > (lray.angle - cray.angle).norm() {
-                let crd = cray.point_by_ratio(time_left);
-                crdv.push(crd);
+                push(cray.point_by_ratio(time_left), false);
             } else {
-                let mut left_normal;
-                let mut right_normal;
-                if orient {
-                    left_normal = Ray {
-                        origin: cray.origin,
-                        angle: (-lray.angle.1, lray.angle.0).into(),
-                    };
-                    right_normal = Ray {
-                        origin: cray.origin,
-                        angle: (rray.angle.1, -rray.angle.0).into(),
-                    };
-                } else {
-                    left_normal = Ray {
-                        origin: cray.origin,
-                        angle: (lray.angle.1, -lray.angle.0).into(),
-                    };
-                    right_normal = Ray {
-                        origin: cray.origin,
-                        angle: (-rray.angle.1, rray.angle.0).into(),
-                    };
-                }
-                left_normal.normalize();
-                right_normal.normalize();
-                loop {
-                    let lcrd = left_normal.point_by_ratio(time_left);
-                    crdv.push(lcrd);
-                    left_normal = left_normal.rotate_by(if orient { 0.1 } else { -0.1 });
-                    if orient && left_normal.orientation(&right_normal.point_by_ratio(1.)) == -1 {
-                        break;
+                let (mut left_normal, right_normal) =
+                    Self::corner_normals(lray, rray, cray, orient);
+                match corners {
+                    Corners::Miter => push(cray.point_by_ratio(time_left), false),
+                    Corners::MiterLimit(limit) if cray.angle.norm() <= limit => {
+                        push(cray.point_by_ratio(time_left), false);
+                    }
+                    Corners::MiterLimit(limit) => {
+                        // Each offset edge is cut where it reaches `limit * time_left` along the bisector.
+                        let bisector = cray.angle / cray.angle.norm();
+                        let reach = limit * time_left;
+                        let cut = |normal: &Ray, direction: Coord| {
+                            let start = normal.point_by_ratio(time_left);
+                            let along = direction.inner_product(&bisector);
+                            if feq(along, 0.) {
+                                return start;
+                            }
+                            start
+                                + direction
+                                    * ((reach - (start - cray.origin).inner_product(&bisector))
+                                        / along)
+                        };
+                        push(cut(&left_normal, lray.angle), true);
+                        push(cut(&right_normal, rray.angle), true);
                     }
-                    if !orient && left_normal.orientation(&right_normal.point_by_ratio(1.)) == 1 {
-                        break;
+                    Corners::Round(step) => {
+                        loop {
+                            let lcrd = left_normal.point_by_ratio(time_left);
+                            push(lcrd, true);
+                            left_normal = left_normal.rotate_by(if orient { step } else { -step });
+                            if orient
+                                && left_normal.orientation(&right_normal.point_by_ratio(1.)) == -1
+                            {
+                                break;
+                            }
+                            if !orient
+                                && left_normal.orientation(&right_normal.point_by_ratio(1.)) == 1
+                            {
+                                break;
+                            }
+                        }
+                        push(right_normal.point_by_ratio(time_left), true);
+                    }
+                    Corners::Bevel => {
+                        push(left_normal.point_by_ratio(time_left), true);
+                        push(right_normal.point_by_ratio(time_left), true);
                     }
                 }
-                crdv.push(right_normal.point_by_ratio(time_left));
             }
         }
+    }
+
+    /// Returns the unit normals of the edges on the left and on the right of a vertex of the wavefront, whose edges lie along
+    /// `lray` and `rray` and which moves along `cray`, pointing to where the wavefront moves, as rays from the origin of `cray`.
+    fn corner_normals(lray: Ray, rray: Ray, cray: Ray, orient: bool) -> (Ray, Ray) {
+        let (left, right): (Coord, Coord) = if orient {
+            (
+                (-lray.angle.y, lray.angle.x).into(),
+                (rray.angle.y, -rray.angle.x).into(),
+            )
+        } else {
+            (
+                (lray.angle.y, -lray.angle.x).into(),
+                (-rray.angle.y, rray.angle.x).into(),
+            )
+        };
+        let mut left_normal = Ray::from_direction(cray.origin, left);
+        let mut right_normal = Ray::from_direction(cray.origin, right);
+        left_normal.normalize();
+        right_normal.normalize();
+        (left_normal, right_normal)
+    }
+
+    /// Returns the rings of the wavefront `vertex_queue` at the time `offset_distance` with a circular arc around each convex vertex,
+    /// i.e. the rings [`Skeleton::apply_vertex_queue_with_corners`] approximates with [`Corners::Round`], in the order of the queue.
+    pub(crate) fn curved_wavefront(
+        &self,
+        vertex_queue: &VertexQueue,
+        offset_distance: f64,
+    ) -> Vec<CurvedRing> {
+        let orient = self.side == Side::Exterior;
+        let mut rings = Vec::new();
+        let mut corners = Vec::new();
+        let mut cur_vidx = usize::MAX;
+        for (vidx, _, idx) in vertex_queue.iter() {
+            if vidx != cur_vidx {
+                if cur_vidx < usize::MAX {
+                    rings.push(curve::ring(&corners));
+                    corners.clear();
+                }
+                cur_vidx = vidx;
+            }
+            let time_left = offset_distance - self.ray_vector[idx].time_elapsed();
+            let (lray, rray) = self.ray_vector[idx].unwrap_base_ray();
+            let cray = self.ray_vector[idx].unwrap_ray();
+            if (lray.angle + cray.angle).norm() > (lray.angle - cray.angle).norm() {
+                corners.push(Corner::Point(cray.point_by_ratio(time_left)));
+                continue;
+            }
+            let (left_normal, right_normal) = Self::corner_normals(lray, rray, cray, orient);
+            let start_angle = left_normal.angle();
+            // The arc turns counter-clockwise from the left normal to the right normal when inflating, and clockwise when deflating.
+            let sweep = if orient {
+                (right_normal.angle() - start_angle).rem_euclid(TAU)
+            } else {
+                -(start_angle - right_normal.angle()).rem_euclid(TAU)
+            };
+            corners.push(Corner::Arc(CircularArc {
+                center: cray.origin,
+                radius: time_left,
+                start_angle,
+                end_angle: start_angle + sweep,
+            }));
+        }
         if cur_vidx < usize::MAX {
-            let mut ls = LineString::from(crdv);
-            ls.close();
-            lsv.push(ls);
+            rings.push(curve::ring(&corners));
+        }
+        rings
+    }
+
+    /// Builds a multi-polygon from the wavefront rings `lsv` in canonical form.
+    ///
+    /// Every ring starts at its lexicographically smallest coordinate, and the polygons and the interiors
+    /// of each polygon are sorted lexicographically by their coordinates, so that the output does not
+    /// depend on the order in which the events were processed.
+    fn assemble_multi_polygon(mut lsv: Vec<LineString>) -> MultiPolygon {
+        for ls in &mut lsv {
+            postprocess::canonicalize_ring(ls);
         }
-        for ls in &lsv {
-            if ls.winding_order() == Some(WindingOrder::CounterClockwise) {
-                let p1: Polygon = Polygon::new(ls.clone(), vec![]);
-                res.push(p1);
+        lsv.sort_by(postprocess::cmp_rings);
+        let mut res = Vec::new();
+        let mut holes = Vec::new();
+        for ls in lsv {
+            match ls.winding_order() {
+                Some(WindingOrder::CounterClockwise) => res.push(Polygon::new(ls, vec![])),
+                Some(WindingOrder::Clockwise) => holes.push(ls),
+                None => {}
             }
         }
-        for ls in &lsv {
-            if ls.winding_order() == Some(WindingOrder::Clockwise) {
-                for e in &mut res {
-                    if e.contains(ls) {
-                        e.interiors_push(ls.clone());
-                        break;
-                    }
-                }
+        for ls in holes {
+            if let Some(e) = res.iter_mut().find(|e| e.contains(&ls)) {
+                e.interiors_push(ls);
             }
         }
         MultiPolygon::new(res)
     }
 
+    /// Returns the wavefront at the time `time_elapsed`.
+    ///
+    /// The wavefront of the previous query is kept and moved to `time_elapsed`, by rolling back or replaying only the events
+    /// in between, so that queries at nearby times (e.g. the frames of an animation) are cheap. A query made while another
+    /// thread holds that wavefront starts from the latest cached snapshot instead.
     pub(crate) fn get_vertex_queue(&self, time_elapsed: f64) -> VertexQueue {
-        let mut ret = self.initial_vertex_queue.clone();
-        for e in &self.event_queue {
-            if e.unwrap_time() <= time_elapsed {
-                Self::apply_event(&mut ret, e);
-                ret.cleanup();
-            } else {
-                break;
+        let mut cursor = match self.cursor.try_lock() {
+            Ok(cursor) => cursor,
+            // A query panicked while moving the wavefront, which may have been left halfway: it is started over.
+            Err(TryLockError::Poisoned(poisoned)) => {
+                let mut cursor = poisoned.into_inner();
+                *cursor = None;
+                cursor
+            }
+            Err(TryLockError::WouldBlock) => {
+                let mut cursor = self.new_cursor();
+                cursor.seek(self, time_elapsed);
+                let mut ret = cursor.vertex_queue;
+                ret.clear_journal();
+                return ret;
             }
+        };
+        cursor
+            .get_or_insert_with(|| self.new_cursor())
+            .seek(self, time_elapsed)
+            .snapshot()
+    }
+
+    /// Returns a cursor positioned before the first event, for querying the wavefront at several times.
+    fn new_cursor(&self) -> WavefrontCursor {
+        WavefrontCursor {
+            vertex_queue: self.initial_vertex_queue.clone(),
+            base: 0,
+            checkpoints: Vec::new(),
         }
-        ret
     }
 
-    fn get_orientation(&self) -> bool {
-        let iz_ray = self.ray_vector[0].unwrap_ray();
-        let iz_left = self.ray_vector[0].unwrap_base_ray().0;
-        iz_left.orientation(&iz_ray.point_by_ratio(1.)) == 1
+    /// Returns the cached wavefront with the most events applied, at most `max_events`, and the number of its events.
+    fn latest_snapshot(&self, max_events: usize) -> (usize, VertexQueue) {
+        let snapshots = self
+            .snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match snapshots.range(..=max_events).next_back() {
+            Some((&events, vertex_queue)) => (events, vertex_queue.snapshot()),
+            None => (0, self.initial_vertex_queue.clone()),
+        }
     }
 
+    fn store_snapshot(&self, events: usize, vertex_queue: &VertexQueue) {
+        let mut snapshots = self
+            .snapshots
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        snapshots
+            .entry(events)
+            .or_insert_with(|| vertex_queue.snapshot());
+    }
+
+    /// Returns the side of the vertices of the input that the bisectors in `ray_vector` point to.
+    ///
+    /// This recovers the side of a skeleton which has been stored without it, e.g. by [`Skeleton::to_bytes`].
+    fn traced_side(ray_vector: &[VertexType]) -> Side {
+        let Some(VertexType::Tree { .. }) = ray_vector.first() else {
+            return Side::Exterior;
+        };
+        let iz_ray = ray_vector[0].unwrap_ray();
+        let iz_left = ray_vector[0].unwrap_base_ray().0;
+        if iz_left.orientation(&iz_ray.point_by_ratio(1.)) == 1 {
+            Side::Exterior
+        } else {
+            Side::Interior
+        }
+    }
+
+    /// Checks whether the tree vertex `vertex` bends away from the region swept by the wavefront, i.e. whether it can split an edge.
+    fn is_reflex(vertex: &VertexType, orient: bool) -> bool {
+        let (left_ray, right_ray) = vertex.unwrap_base_ray();
+        let op = left_ray.angle.outer_product(&right_ray.angle);
+        if orient {
+            !fleq(op, 0.)
+        } else {
+            !fgeq(op, 0.)
+        }
+    }
+
+    /// Finds the earliest split of `cv` among the right edges of the vertices in `candidates` from time `time` on,
+    /// and stores it in `ret`.
+    ///
+    /// Splits after `time_bound` may be culled.
+    #[allow(clippy::too_many_arguments)]
     fn find_split_vertex(
         cv: IndexType,
+        candidates: impl Iterator<Item = IndexType>,
         vertex_queue: &VertexQueue,
         vertex_vector: &[VertexType],
-        is_init: bool,
         orient: bool,
-    ) -> Vec<(f64, Coordinate, IndexType, usize)> {
-        let mut ret = Vec::new();
+        time: f64,
+        time_bound: f64,
+        ret: &mut Vec<SplitCandidate>,
+    ) {
+        ret.clear();
         let cv_real = vertex_queue.get_real_index(cv);
-        let left_ray = vertex_vector[cv_real].unwrap_base_ray().0;
-        let right_ray = vertex_vector[cv_real].unwrap_base_ray().1;
-        if orient && fleq(left_ray.angle.outer_product(&right_ray.angle), 0.) {
-            return ret;
-        } // check if ver_vec[i] is a reflex vertex
-        if !orient && fgeq(left_ray.angle.outer_product(&right_ray.angle), 0.) {
-            return ret;
+        if !Self::is_reflex(&vertex_vector[cv_real], orient) {
+            return;
         }
+        let (left_ray, right_ray) = vertex_vector[cv_real].unwrap_base_ray();
 
-        for (_, sv, sv_real) in vertex_queue.iter() {
+        // Only the earliest split is kept, so later candidates can be culled.
+        let mut bound = SplitBound::new(&vertex_vector[cv_real], time_bound);
+        for sv in candidates {
+            let sv_real = vertex_queue.get_real_index(sv);
             let srv = vertex_queue.rv(sv);
             let srv_real = vertex_queue.get_real_index(srv);
             if sv == cv || sv == vertex_queue.rv(cv) || srv == cv || srv == vertex_queue.lv(cv) {
                 continue;
             }
+            if let Some(bound) = &bound {
+                if !bound.may_split(&vertex_vector[sv_real], &vertex_vector[srv_real]) {
+                    continue;
+                }
+            }
             let base_ray = vertex_vector[sv_real].unwrap_base_ray().1;
             let left_intersection = if left_ray.is_parallel(&base_ray) {
                 Default::default()
@@ -539,14 +1125,7 @@ impl Skeleton {
                 }
                 li_ray.intersect(&vertex_vector[cv_real].unwrap_ray())
             };
-            if is_init {
-                if orient && base_ray.orientation(&real_intersection) < 0 {
-                    continue;
-                }
-                if !orient && base_ray.orientation(&real_intersection) > 0 {
-                    continue;
-                }
-            } else if orient {
+            if orient {
                 if vertex_vector[sv_real]
                     .unwrap_ray()
                     .orientation(&real_intersection)
@@ -584,38 +1163,76 @@ impl Skeleton {
                 }
             }
             let dist = real_intersection.dist_ray(&right_ray);
+            // The edge did not reach the intersection if it lies before the current time, e.g. if the edge has grown since.
+            if flt(dist, time) {
+                continue;
+            }
+            // An edge cannot be split before both its vertices exist, since their axes do not bound it before.
+            let born = f64::max(
+                vertex_vector[sv_real].time_elapsed(),
+                vertex_vector[srv_real].time_elapsed(),
+            );
+            if flt(dist, born) {
+                continue;
+            }
+            // The split has to lie on the edge at that time, which the orientations above miss when a vertex of the edge has
+            // a degenerate axis, e.g. between two opposite edges which meet. Such a vertex moves arbitrarily fast,
+            // so it is taken where it was created at the time it was created.
+            let location_at = |vertex: &VertexType| {
+                if fleq(dist, vertex.time_elapsed()) {
+                    vertex.unwrap_ray().origin
+                } else {
+                    vertex.location_at(dist)
+                }
+            };
+            let edge = base_ray.angle / base_ray.angle.norm();
+            let from_left =
+                (real_intersection - location_at(&vertex_vector[sv_real])).inner_product(&edge);
+            let from_right =
+                (real_intersection - location_at(&vertex_vector[srv_real])).inner_product(&edge);
+            if flt(from_left, 0.) || fgt(from_right, 0.) {
+                continue;
+            }
+            if let Some(bound) = &mut bound {
+                bound.tighten(&vertex_vector[cv_real], dist);
+            }
             ret.push((dist, real_intersection, sv, sv_real));
         }
-        ret.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        if !is_init && !ret.is_empty() {
-            ret = vec![ret[0]];
-        }
-        ret
+        ret.sort_by(|a, b| split_key(a).partial_cmp(&split_key(b)).unwrap());
+        ret.truncate(1);
     }
 
+    /// Queues the split `candidate` of the reflex vertex `cv`, and returns the handle of its event.
     fn make_split_event(
         cv: IndexType,
+        candidate: &SplitCandidate,
         vertex_queue: &VertexQueue,
-        event_pq: &mut PriorityQueue<Timeline>,
+        event_pq: &mut EventQueue,
         vertex_vector: &[VertexType],
-        orient: bool,
-    ) {
-        let resv = Self::find_split_vertex(cv, vertex_queue, vertex_vector, true, orient);
+    ) -> Handle {
         let cv_real = vertex_queue.get_real_index(cv);
-        for (time, location, _, _) in resv {
-            event_pq.insert(Timeline::SplitEvent {
-                time,
-                location,
-                anchor_vertex: cv,
-                anchor_real: cv_real,
-            });
-        }
+        let origin = vertex_vector[cv_real].unwrap_ray().origin;
+        let &(time, location, sv, _) = candidate;
+        let srv = vertex_queue.rv(sv);
+        event_pq.insert(Timeline::SplitEvent {
+            time,
+            location,
+            anchor_vertex: cv,
+            anchor_real: cv_real,
+            anchor_generation: vertex_queue.generation(cv),
+            split_edge: [
+                (sv, vertex_queue.generation(sv)),
+                (srv, vertex_queue.generation(srv)),
+            ],
+            origin,
+            rule: event_pq.tie_break,
+        })
     }
 
     fn make_shrink_event(
         cv: IndexType,
         vertex_queue: &VertexQueue,
-        event_pq: &mut PriorityQueue<Timeline>,
+        event_pq: &mut EventQueue,
         vertex_vector: &[VertexType],
         is_init: bool,
     ) {
@@ -640,7 +1257,11 @@ impl Skeleton {
                     right_vertex: rv,
                     left_real: lv_real,
                     right_real: rv_real,
+                    left_generation: vertex_queue.generation(lv),
+                    right_generation: vertex_queue.generation(rv),
                     tie_break,
+                    origins: [lv_ray.origin, rv_ray.origin],
+                    rule: event_pq.tie_break,
                 });
             }
             if is_init {
@@ -665,8 +1286,8 @@ impl Skeleton {
             let cv = vertex_queue.remove_and_set(merge_from, merge_to);
             if vertex_queue.lv(cv) == vertex_queue.rv(cv) {
                 let lv = vertex_queue.lv(cv);
-                vertex_queue.content[lv.get_index()].done = true;
-                vertex_queue.content[cv.get_index()].done = true;
+                vertex_queue.mark_done(lv);
+                vertex_queue.mark_done(cv);
                 return (
                     Some(vertex_queue.content[vertex_queue.lv(cv).get_index()].index),
                     None,
@@ -688,47 +1309,166 @@ impl Skeleton {
             let split_to_right = IndexType::RealIndex(*split_to_right);
             let ret =
                 vertex_queue.split_and_set(split_from, split_into, split_to_left, split_to_right);
-            vertex_queue.cleanup();
             return (Some(ret.0), Some(ret.1));
         }
 
         (None, None)
     }
 
-    pub(crate) fn skeleton_of_polygon(input_polygon: &Polygon, orient: bool) -> Self {
-        let mut vertex_vector =
-            VertexType::initialize_from_polygon(input_polygon, orient);
-        let mut vertex_queue = VertexQueue::new();
-        vertex_queue.initialize_from_polygon(input_polygon);
-        let (event_queue, initial_vertex_queue) = init_pq(orient, &mut vertex_vector, &mut vertex_queue);
-        Self {
-            ray_vector: vertex_vector,
-            event_queue,
-            initial_vertex_queue,
+    /// Buffers the input of this skeleton by `offset_distance` with the given [`BufferOptions`].
+    ///
+    /// The skeleton determines whether the input is inflated or deflated, so `offset_distance` has to be non-negative.
+    /// Other than that, the result is the same as buffering the input with
+    /// [`buffer_polygon_with`](crate::buffer_polygon_with) or [`buffer_multi_polygon_with`](crate::buffer_multi_polygon_with).
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn buffer(&self, offset_distance: f64, options: &BufferOptions) -> MultiPolygon {
+        options.buffer_skeleton(self, offset_distance)
+    }
+
+    /// Returns the wavefront at the fraction `t` of the way from the input of this skeleton to its buffer by `offset_distance`,
+    /// i.e. the buffer by `t * offset_distance`, where `t` is clamped to `[0, 1]`.
+    ///
+    /// Calling this with increasing `t` animates the growth (or shrinkage) of the input while building the skeleton only once.
+    /// As for [`Skeleton::buffer`], `offset_distance` has to be non-negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Exterior);
+    /// let frames: Vec<f64> = (0..=4)
+    ///     .map(|i| skel.interpolate(1., i as f64 / 4.).unsigned_area())
+    ///     .collect();
+    ///
+    /// assert!((frames[0] - 4.).abs() < 1e-9);
+    /// assert!((frames[2] - 9.).abs() < 1e-9);
+    /// assert!((frames[4] - 16.).abs() < 1e-9);
+    /// ```
+    #[must_use = "Use the newly buffered MultiPolygon"]
+    pub fn interpolate(&self, offset_distance: f64, t: f64) -> MultiPolygon {
+        self.buffer(offset_distance * t.clamp(0., 1.), &BufferOptions::new())
+    }
+
+    pub(crate) fn skeleton_of_polygon(input_polygon: &Polygon, side: Side) -> Self {
+        SkeletonBuilder::new().skeleton_of_polygon(input_polygon, side)
+    }
+
+    pub(crate) fn skeleton_of_polygon_vector(input_polygon_vector: &[Polygon], side: Side) -> Self {
+        SkeletonBuilder::new().skeleton_of_polygon_vector(input_polygon_vector, side)
+    }
+
+    /// Returns the side of the input this skeleton has been built on, which is the side its buffers move to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// assert_eq!(skeleton_of_polygon(&p1, Side::Interior).side(), Side::Interior);
+    /// assert_eq!(skeleton_of_polygon(&p1, Side::Exterior).side(), Side::Exterior);
+    /// ```
+    #[must_use]
+    pub const fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Returns the segments of this skeleton as two-point `LineString`s, as
+    /// [`skeleton_of_polygon_to_linestring`](crate::skeleton_of_polygon_to_linestring) does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::buffer_polygon_with_skeleton;
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let (_, skel) = buffer_polygon_with_skeleton(&p1, -0.5);
+    ///
+    /// // The four corners meet at the center.
+    /// assert!(skel.to_linestring().iter().all(|ls| ls.0.contains(&(1., 1.).into())));
+    /// ```
+    #[must_use]
+    pub fn to_linestring(&self) -> Vec<LineString> {
+        let mut ret = Vec::new();
+        self.for_each_segment(|a, b| ret.push(LineString(vec![a, b])));
+        ret
+    }
+
+    /// Returns the time and the location of the last vertex of this skeleton, where the wavefront of a deflated input vanishes.
+    pub(crate) fn last_event(&self) -> Option<(f64, Coord)> {
+        self.ray_vector
+            .iter()
+            .max_by(|a, b| a.time_elapsed().total_cmp(&b.time_elapsed()))
+            .map(|v| (v.time_elapsed(), v.inner_location()))
+    }
+
+    /// Returns the arcs of this skeleton between two of its nodes, leaving out the arcs from the vertices of the input and the arcs
+    /// to infinity. For an inward skeleton, this is where the input collapses to when it is deflated until it vanishes.
+    pub(crate) fn spine(&self) -> Vec<LineString> {
+        let mut ret = Vec::new();
+        for v in &self.ray_vector {
+            let VertexType::Tree {
+                axis,
+                parent,
+                time_elapsed,
+                ..
+            } = *v
+            else {
+                continue;
+            };
+            if time_elapsed <= 0. {
+                continue;
+            }
+            if let Some(p) = self.ray_vector.get(parent) {
+                if axis.origin != p.inner_location() {
+                    ret.push(LineString(vec![axis.origin, p.inner_location()]));
+                }
+            }
         }
+        ret
     }
 
-    pub(crate) fn skeleton_of_polygon_vector(
-        input_polygon_vector: &Vec<Polygon>,
-        orient: bool,
-    ) -> Self {
-        let mut vertex_vector =
-            VertexType::initialize_from_polygon_vector(input_polygon_vector, orient);
-        let mut vertex_queue = VertexQueue::new();
-        vertex_queue.initialize_from_polygon_vector(input_polygon_vector);
-        let (event_queue, initial_vertex_queue) = init_pq(orient, &mut vertex_vector, &mut vertex_queue);
-        Self {
-            ray_vector: vertex_vector,
-            event_queue,
-            initial_vertex_queue,
+    /// Calls `f` with the endpoints of each arc of this skeleton, cut where the wavefront reaches at time `time`.
+    ///
+    /// Unlike [`Skeleton::for_each_segment`], the arcs are visited in the order of their vertices, and arcs to infinity are cut as well.
+    pub(crate) fn for_each_arc_until(&self, time: f64, mut f: impl FnMut(Coord, Coord)) {
+        for v in &self.ray_vector {
+            let VertexType::Tree {
+                axis,
+                parent,
+                time_elapsed,
+                ..
+            } = *v
+            else {
+                continue;
+            };
+            if time_elapsed >= time {
+                continue;
+            }
+            match self.ray_vector.get(parent) {
+                Some(p) if p.time_elapsed() <= time => f(axis.origin, p.inner_location()),
+                _ => f(axis.origin, v.location_at(time)),
+            }
         }
     }
 
-    pub(crate) fn to_linestring(&self) -> Vec<LineString> {
+    /// Calls `f` with the endpoints of each segment of this skeleton, in the order of [`Skeleton::to_linestring`].
+    pub(crate) fn for_each_segment(&self, mut f: impl FnMut(Coord, Coord)) {
         fn dfs_helper(
             cur: usize,
             visit: &mut Vec<bool>,
-            ret: &mut Vec<LineString>,
+            f: &mut impl FnMut(Coord, Coord),
             ray_vector: &Vec<VertexType>,
         ) {
             if visit[cur] {
@@ -739,51 +1479,439 @@ impl Skeleton {
                 VertexType::Root { .. } => {}
                 VertexType::Tree { parent, .. } => {
                     if parent == usize::MAX {
-                        let ls = LineString(vec![
-                            ray_vector[cur].inner_location().into(),
-                            ray_vector[cur].unwrap_ray().point_by_ratio(5.).into(),
-                        ]);
-                        ret.push(ls);
+                        f(
+                            ray_vector[cur].inner_location(),
+                            ray_vector[cur].unwrap_ray().point_by_ratio(5.),
+                        );
                         return;
                     }
-                    let ls = LineString(vec![
-                        ray_vector[cur].inner_location().into(),
-                        ray_vector[parent].inner_location().into(),
-                    ]);
-                    ret.push(ls);
-                    dfs_helper(parent, visit, ret, ray_vector);
+                    f(
+                        ray_vector[cur].inner_location(),
+                        ray_vector[parent].inner_location(),
+                    );
+                    dfs_helper(parent, visit, f, ray_vector);
                 }
                 VertexType::Split {
                     split_left,
                     split_right,
                     ..
                 } => {
-                    dfs_helper(split_left, visit, ret, ray_vector);
-                    dfs_helper(split_right, visit, ret, ray_vector);
+                    dfs_helper(split_left, visit, f, ray_vector);
+                    dfs_helper(split_right, visit, f, ray_vector);
                 }
             }
         }
         let mut visit = vec![false; self.ray_vector.len()];
-        let mut ret = Vec::new();
         for (_, _, e) in self.initial_vertex_queue.iter() {
-            dfs_helper(e, &mut visit, &mut ret, &self.ray_vector);
+            dfs_helper(e, &mut visit, &mut f, &self.ray_vector);
         }
-        ret
+    }
+}
+
+/// Builds straight skeletons, keeping the scratch buffers of the construction (the event queue and the split schedule) from one skeleton
+/// to the next.
+///
+/// Building many skeletons with one builder, e.g. one per member of a multi-polygon, avoids reallocating these buffers for every polygon.
+/// The skeletons are the same as the ones of [`skeleton_of_polygon`](crate::skeleton_of_polygon) and
+/// [`skeleton_of_multi_polygon`](crate::skeleton_of_multi_polygon). See [`BufferOptions::skeleton_builder`] for a builder set up as
+/// some buffer options.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_polygon, Side, SkeletonBuilder};
+/// use geo::{Polygon, LineString};
+///
+/// let polygons: Vec<Polygon> = (0..4)
+///     .map(|i| {
+///         let x = i as f64 * 10.;
+///         Polygon::new(LineString::from(vec![(x, 0.), (x + 4., 0.), (x + 4., 4.), (x + 2., 1.), (x, 4.)]), vec![])
+///     })
+///     .collect();
+/// let mut builder = SkeletonBuilder::new();
+/// for p in &polygons {
+///     let skel = builder.skeleton_of_polygon(p, Side::Interior);
+///     assert_eq!(skel.to_linestring(), skeleton_of_polygon(p, Side::Interior).to_linestring());
+/// }
+/// ```
+pub struct SkeletonBuilder {
+    tie_break: TieBreak,
+    event_pq: EventQueue,
+    splits: SplitSchedule,
+}
+
+impl SkeletonBuilder {
+    /// Creates a builder with empty scratch buffers, ordering simultaneous events by [`TieBreak::OriginDistance`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tie_break: TieBreak::default(),
+            event_pq: EventQueue::new(),
+            splits: SplitSchedule::new(),
+        }
+    }
+
+    /// Sets how the skeletons built from now on order simultaneous events, see [`BufferOptions::tie_break`].
+    #[must_use]
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Sets the thread pool running the parallel parts of the construction, instead of the global pool of rayon, see
+    /// [`BufferOptions::thread_pool`]. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn thread_pool(mut self, thread_pool: Arc<rayon::ThreadPool>) -> Self {
+        self.splits.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// This function returns the straight skeleton of the given polygon as [`skeleton_of_polygon`](crate::skeleton_of_polygon) does.
+    ///
+    /// # Arguments
+    ///
+    /// + `input_polygon`: `Polygon` to get the straight skeleton.
+    /// + `side`: determines the region where the straight skeleton created, see [`skeleton_of_polygon`](crate::skeleton_of_polygon).
+    #[must_use]
+    pub fn skeleton_of_polygon(
+        &mut self,
+        input_polygon: &Polygon,
+        side: impl Into<Side>,
+    ) -> Skeleton {
+        self.skeleton_of_polygon_vector(std::slice::from_ref(input_polygon), side.into())
+    }
+
+    /// This function returns the straight skeleton of the given multi-polygon as
+    /// [`skeleton_of_multi_polygon`](crate::skeleton_of_multi_polygon) does.
+    ///
+    /// # Arguments
+    ///
+    /// + `input_multi_polygon`: `MultiPolygon` to get the straight skeleton.
+    /// + `side`: determines the region where the straight skeleton created, see [`skeleton_of_polygon`](crate::skeleton_of_polygon).
+    #[must_use]
+    pub fn skeleton_of_multi_polygon(
+        &mut self,
+        input_multi_polygon: &MultiPolygon,
+        side: impl Into<Side>,
+    ) -> Skeleton {
+        self.skeleton_of_polygon_vector(&input_multi_polygon.0, side.into())
+    }
+
+    /// This function returns the straight skeleton of the given multi-polygon as
+    /// [`try_skeleton_of_multi_polygon`](crate::try_skeleton_of_multi_polygon) does, or an error instead of panicking if the
+    /// construction breaks down on a pathological input.
+    ///
+    /// The builder stays usable after an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NumericFailure`] if the construction of the straight skeleton fails.
+    pub fn try_skeleton_of_multi_polygon(
+        &mut self,
+        input_multi_polygon: &MultiPolygon,
+        side: impl Into<Side>,
+    ) -> Result<Skeleton, Error> {
+        self.try_skeleton_of_polygon_vector(&input_multi_polygon.0, side.into(), Monitor::default())
+    }
+
+    pub(crate) fn skeleton_of_polygon_vector(
+        &mut self,
+        input_polygon_vector: &[Polygon],
+        side: Side,
+    ) -> Skeleton {
+        self.build(input_polygon_vector, side, Monitor::default())
+            .expect("the construction is never interrupted")
+    }
+
+    /// Builds the skeleton as [`SkeletonBuilder::skeleton_of_polygon_vector`] does, unless `monitor` aborts the construction.
+    ///
+    /// A panic of the construction is caught and returned as [`Error::NumericFailure`].
+    pub(crate) fn try_skeleton_of_polygon_vector(
+        &mut self,
+        input_polygon_vector: &[Polygon],
+        side: Side,
+        monitor: Monitor,
+    ) -> Result<Skeleton, Error> {
+        // The next construction resets the scratch buffers, so no broken state outlives the panic.
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            self.build(input_polygon_vector, side, monitor)
+        }))
+        .unwrap_or(Err(Error::NumericFailure))
+    }
+
+    fn build(
+        &mut self,
+        input_polygon_vector: &[Polygon],
+        side: Side,
+        monitor: Monitor,
+    ) -> Result<Skeleton, Error> {
+        let orient = side.is_interior();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("skeleton", polygons = input_polygon_vector.len(), orient)
+            .entered();
+        // There is no clock on `wasm32-unknown-unknown`, where `Instant::now` panics.
+        #[cfg(feature = "tracing")]
+        let start = (!cfg!(target_family = "wasm")).then(std::time::Instant::now);
+        let valid = repair::drop_degenerate_rings(input_polygon_vector);
+        let input_polygon_vector = valid.as_deref().unwrap_or(input_polygon_vector);
+        let split = repair::split_pinches(input_polygon_vector);
+        let mut polygons = split.as_deref().unwrap_or(input_polygon_vector);
+        let separated = (!orient)
+            .then(|| repair::separate_touching_holes(polygons))
+            .flatten();
+        if let Some(separated) = &separated {
+            polygons = separated;
+        }
+        let mut vertex_vector = VertexType::initialize_from_polygon_vector(polygons, orient);
+        let mut vertex_queue = VertexQueue::new();
+        vertex_queue.initialize_from_polygon_vector(polygons);
+        let (event_queue, initial_vertex_queue) =
+            init_pq(self, orient, &mut vertex_vector, &mut vertex_queue, monitor)?;
+        let clockwise_input = input_polygon_vector
+            .first()
+            .and_then(|p| p.exterior().winding_order())
+            == Some(WindingOrder::Clockwise);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            events = event_queue.len(),
+            vertices = vertex_vector.len(),
+            elapsed_us = start.map(|start| start.elapsed().as_micros() as u64),
+            "skeleton built"
+        );
+        Ok(Skeleton::from_parts(
+            vertex_vector,
+            event_queue,
+            initial_vertex_queue,
+            side,
+            clockwise_input,
+        ))
+    }
+}
+
+impl Default for SkeletonBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The scratch buffers are left out, as they only hold the state of the last construction.
+impl fmt::Debug for SkeletonBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SkeletonBuilder")
+            .field("tie_break", &self.tie_break)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Skeleton {
+    /// Assembles a skeleton from its vertices, its events and its initial wavefront.
+    fn from_parts(
+        ray_vector: Vec<VertexType>,
+        event_queue: Vec<Event>,
+        initial_vertex_queue: VertexQueue,
+        side: Side,
+        clockwise_input: bool,
+    ) -> Self {
+        let replay_until = event_queue
+            .iter()
+            .scan(f64::NEG_INFINITY, |latest, e| {
+                *latest = f64::max(*latest, e.unwrap_time());
+                Some(*latest)
+            })
+            .collect();
+        let snapshot_interval = usize::max(
+            MIN_SNAPSHOT_INTERVAL,
+            event_queue.len().div_ceil(MAX_SNAPSHOTS),
+        );
+        Skeleton {
+            ray_vector,
+            event_queue,
+            replay_until,
+            initial_vertex_queue,
+            snapshot_interval,
+            side,
+            clockwise_input,
+            snapshots: Mutex::new(BTreeMap::new()),
+            cursor: Mutex::new(None),
+            distance_field: OnceLock::new(),
+        }
+    }
+}
+
+/// The cached wavefronts, the wavefront of the last query and the distance index are not cloned, but rebuilt on demand.
+impl Clone for Skeleton {
+    fn clone(&self) -> Self {
+        Skeleton::from_parts(
+            self.ray_vector.clone(),
+            self.event_queue.clone(),
+            self.initial_vertex_queue.clone(),
+            self.side,
+            self.clockwise_input,
+        )
+    }
+}
+
+/// Two skeletons are equal if their nodes, events and initial wavefronts are the same bit for bit, i.e. if they have the same
+/// [binary form](Skeleton::to_bytes).
+impl PartialEq for Skeleton {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Skeleton {}
+
+/// Hashes the [binary form](Skeleton::to_bytes) of the skeleton, consistently with its equality.
+impl Hash for Skeleton {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
+/// Shows a summary of the skeleton rather than its nodes, see [`Skeleton::stats`] for more.
+impl fmt::Debug for Skeleton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Skeleton")
+            .field("side", &self.side)
+            .field("nodes", &self.ray_vector.len())
+            .field("events", &self.event_queue.len())
+            .field("max_time", &self.last_event().map_or(0., |(time, _)| time))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Writes the segments of the skeleton (see [`Skeleton::to_linestring`]) as a WKT `MULTILINESTRING`.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_polygon, Side};
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let skel = skeleton_of_polygon(&p1, Side::Interior);
+///
+/// // The corners of the square run to its center.
+/// assert!(skel.to_string().starts_with("MULTILINESTRING((0 0,1 1),"));
+/// assert_eq!(format!("{skel:?}"), "Skeleton { side: Interior, nodes: 6, events: 2, max_time: 1.0, .. }");
+/// assert_eq!(skel.clone(), skel);
+/// ```
+impl fmt::Display for Skeleton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let segments = self.to_linestring();
+        if segments.is_empty() {
+            return write!(f, "MULTILINESTRING EMPTY");
+        }
+        write!(f, "MULTILINESTRING(")?;
+        for (i, ls) in segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "(")?;
+            for (j, c) in ls.0.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{} {}", c.x, c.y)?;
+            }
+            write!(f, ")")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Collects the segments of the skeleton, see [`Skeleton::to_linestring`].
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::{skeleton_of_polygon, Side};
+/// use geo::{Geometry, GeometryCollection, LineString, MultiLineString, Polygon};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 2.)]), vec![],
+/// );
+/// let skel = skeleton_of_polygon(&p1, Side::Interior);
+///
+/// let mls = MultiLineString::from(&skel);
+/// assert_eq!(mls.0, skel.to_linestring());
+/// assert_eq!(Geometry::from(&skel), Geometry::MultiLineString(mls));
+/// // Through the conversion into a `Geometry`.
+/// assert_eq!(GeometryCollection::from(&skel).len(), 1);
+/// ```
+impl From<&Skeleton> for MultiLineString {
+    fn from(skel: &Skeleton) -> Self {
+        MultiLineString::new(skel.to_linestring())
+    }
+}
+
+impl From<Skeleton> for MultiLineString {
+    fn from(skel: Skeleton) -> Self {
+        MultiLineString::from(&skel)
+    }
+}
+
+/// The segments of the skeleton as a [`Geometry::MultiLineString`], which also converts the skeleton into a one-member `GeometryCollection`.
+impl From<&Skeleton> for Geometry {
+    fn from(skel: &Skeleton) -> Self {
+        Geometry::MultiLineString(skel.into())
+    }
+}
+
+impl From<Skeleton> for Geometry {
+    fn from(skel: Skeleton) -> Self {
+        Geometry::from(&skel)
     }
 }
 
 /// Returns an event_queue and an initial_vertex_queue
-fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut VertexQueue) -> (Vec<Event>, VertexQueue) {
-    let mut event_pq = PriorityQueue::new();
+fn init_pq(
+    builder: &mut SkeletonBuilder,
+    orient: bool,
+    vertex_vector: &mut Vec<VertexType>,
+    vertex_queue: &mut VertexQueue,
+    mut monitor: Monitor,
+) -> Result<(Vec<Event>, VertexQueue), Error> {
+    let SkeletonBuilder {
+        tie_break,
+        event_pq,
+        splits,
+    } = builder;
+    monitor.check()?;
+    event_pq.initialize();
+    event_pq.tie_break = *tie_break;
     let mut event_queue = Vec::new();
     let initial_vertex_queue = vertex_queue.clone();
-    // make initial PQ
-    for (_, cv, _) in vertex_queue.iter() {
-        Skeleton::make_shrink_event(cv, vertex_queue, &mut event_pq, vertex_vector, true);
-        Skeleton::make_split_event(cv, vertex_queue, &mut event_pq, vertex_vector, orient);
+    // make initial PQ; the split events are found by the first advance of the split schedule
+    for cv in vertex_queue.live_nodes() {
+        Skeleton::make_shrink_event(cv, vertex_queue, event_pq, vertex_vector, true);
     }
+    splits.initialize(orient, vertex_queue, vertex_vector);
 
-    while !event_pq.is_empty() {
+    // Counts of the events of the priority queue, reported when the queue is exhausted.
+    #[cfg(feature = "tracing")]
+    let (mut shrink_events, mut split_events, mut rejected_splits) = (0usize, 0usize, 0usize);
+    let mut processed_events = 0;
+    loop {
+        splits.advance(
+            vertex_queue,
+            vertex_vector,
+            event_pq,
+            &mut monitor,
+            processed_events,
+        )?;
+        if event_pq.is_empty() {
+            break;
+        }
+        monitor.poll(Progress {
+            processed_events,
+            queued_events: event_pq.len(),
+            retired_events: event_pq.stats().removed,
+        })?;
+        processed_events += 1;
         let x = event_pq.pop().unwrap();
         if let Timeline::ShrinkEvent {
             time,
@@ -792,15 +1920,19 @@ fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut
             right_vertex,
             left_real,
             right_real,
+            left_generation,
+            right_generation,
             ..
         } = x
         {
-            if vertex_queue.content[left_vertex.get_index()].done
-                || vertex_queue.content[right_vertex.get_index()].done
-                || vertex_queue.get_real_index(left_vertex) != left_real
-                || vertex_queue.get_real_index(right_vertex) != right_real
+            debug_assert!(
+                vertex_queue.is_current(left_vertex, left_generation)
+                    && vertex_queue.is_current(right_vertex, right_generation),
+                "stale shrink event left in the queue"
+            );
+            #[cfg(feature = "tracing")]
             {
-                continue;
+                shrink_events += 1;
             }
             let new_index = vertex_vector.len();
             let left_ray = vertex_vector[left_real].unwrap_base_ray().0;
@@ -814,7 +1946,14 @@ fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut
             };
             let new_vertex = VertexType::new_tree_vertex(location, left_ray, right_ray, orient);
             vertex_vector.push(new_vertex);
-            match Skeleton::apply_event(vertex_queue, &new_event) {
+            let applied = Skeleton::apply_event(vertex_queue, &new_event);
+            // Merging replaces the right neighbour of the merged vertex, and removes its left neighbour too if the ring collapses.
+            let merged_into = vertex_queue.rv(left_vertex);
+            let changed = [left_vertex, merged_into, vertex_queue.lv(merged_into)];
+            for cv in changed {
+                event_pq.retire(cv, vertex_queue);
+            }
+            match applied {
                 (Some(IndexType::RealIndex(rv)), None) => {
                     vertex_vector[rv].set_parent(new_index);
                     vertex_vector[new_index] = VertexType::Root {
@@ -823,38 +1962,43 @@ fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut
                     };
                 }
                 (Some(cv), None) => {
-                    Skeleton::make_shrink_event(
-                        cv,
-                        vertex_queue,
-                        &mut event_pq,
-                        vertex_vector,
-                        false,
-                    );
+                    splits.update(cv, time, vertex_queue, vertex_vector, event_pq);
+                    Skeleton::make_shrink_event(cv, vertex_queue, event_pq, vertex_vector, false);
                 }
                 _ => panic!("Expected Vertex Event"),
             }
+            for cv in changed {
+                splits.touch(cv, time, vertex_queue, vertex_vector, event_pq);
+            }
             event_queue.push(new_event);
         } else if let Timeline::SplitEvent {
             time,
             location,
             anchor_vertex,
             anchor_real,
+            anchor_generation,
+            split_edge,
+            ..
         } = x
         {
-            if vertex_queue.content[anchor_vertex.get_index()].done
-                || vertex_queue.get_real_index(anchor_vertex) != anchor_real
-            {
-                continue;
-            }
-            vertex_queue.cleanup();
-            let rv = Skeleton::find_split_vertex(
+            debug_assert!(
+                vertex_queue.is_current(anchor_vertex, anchor_generation),
+                "stale split event left in the queue"
+            );
+            let split_vertex = splits.take(
                 anchor_vertex,
+                time,
+                location,
+                split_edge,
                 vertex_queue,
                 vertex_vector,
-                false,
-                orient,
+                event_pq,
             );
-            if rv.len() == 1 && feq(rv[0].0, time) && rv[0].1.eq(&location) {
+            if let Some((split_into, split_real)) = split_vertex {
+                #[cfg(feature = "tracing")]
+                {
+                    split_events += 1;
+                }
                 let new_index1 = vertex_vector.len();
                 let new_index2 = new_index1 + 1;
                 let new_split_vertex = VertexType::Split {
@@ -867,12 +2011,12 @@ fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut
                 let new_tree_vertex1 = VertexType::new_tree_vertex(
                     location,
                     vertex_vector[anchor_real].unwrap_base_ray().0,
-                    vertex_vector[rv[0].3].unwrap_base_ray().1,
+                    vertex_vector[split_real].unwrap_base_ray().1,
                     orient,
                 );
                 let new_tree_vertex2 = VertexType::new_tree_vertex(
                     location,
-                    vertex_vector[rv[0].3].unwrap_base_ray().1.reverse(),
+                    vertex_vector[split_real].unwrap_base_ray().1.reverse(),
                     vertex_vector[anchor_real].unwrap_base_ray().1,
                     orient,
                 );
@@ -882,34 +2026,59 @@ fn init_pq(orient: bool, vertex_vector: &mut Vec<VertexType>, vertex_queue: &mut
                 let new_event = Event::EdgeEvent {
                     time,
                     split_from: anchor_vertex.get_index(),
-                    split_into: rv[0].2.get_index(),
+                    split_into: split_into.get_index(),
                     split_to_left: new_index1,
                     split_to_right: new_index2,
                 };
-                match Skeleton::apply_event(vertex_queue, &new_event) {
+                let applied = Skeleton::apply_event(vertex_queue, &new_event);
+                event_pq.retire(anchor_vertex, vertex_queue);
+                match applied {
                     (Some(cv1), Some(cv2)) => {
                         vertex_vector[anchor_real].set_parent(new_index2 + 1);
+                        splits.update(cv1, time, vertex_queue, vertex_vector, event_pq);
+                        splits.update(cv2, time, vertex_queue, vertex_vector, event_pq);
                         Skeleton::make_shrink_event(
                             cv1,
                             vertex_queue,
-                            &mut event_pq,
+                            event_pq,
                             vertex_vector,
                             false,
                         );
                         Skeleton::make_shrink_event(
                             cv2,
                             vertex_queue,
-                            &mut event_pq,
+                            event_pq,
                             vertex_vector,
                             false,
                         );
                     }
                     _ => panic!("Expected Edge Event"),
                 }
+                for cv in [anchor_vertex, split_into] {
+                    splits.touch(cv, time, vertex_queue, vertex_vector, event_pq);
+                }
                 event_queue.push(new_event);
+            } else {
+                #[cfg(feature = "tracing")]
+                {
+                    rejected_splits += 1;
+                    tracing::trace!(time, "split event rejected");
+                }
             }
         }
-        vertex_queue.cleanup();
     }
-    (event_queue, initial_vertex_queue)
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        shrink_events,
+        split_events,
+        rejected_splits,
+        peak_queued_events = event_pq.stats().peak_len,
+        "event queue exhausted"
+    );
+    monitor.report(Progress {
+        processed_events,
+        queued_events: 0,
+        retired_events: event_pq.stats().removed,
+    });
+    Ok((event_queue, initial_vertex_queue))
 }
@@ -0,0 +1,220 @@
+//! Step-by-step replay of the construction of a [`Skeleton`], for debugging and for learning how the algorithm works.
+
+use super::*;
+
+/// The type of an event of the construction of a straight skeleton.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// Two adjacent vertices of the wavefront met, removing the edge between them.
+    Shrink,
+    /// A vertex of the wavefront hit an edge of the wavefront, splitting a ring into two.
+    Split,
+}
+
+/// The state of the wavefront right after an event of the construction of a straight skeleton, see [`Skeleton::frames`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventFrame {
+    /// The position of the event in the order of processing.
+    pub index: usize,
+    /// The type of the event.
+    pub kind: EventKind,
+    /// The time of the event, i.e. the distance the wavefront has travelled from the input.
+    pub time: f64,
+    /// The location where the event happened.
+    pub location: Coord,
+    /// The closed rings of the wavefront right after the event.
+    pub wavefront: Vec<LineString>,
+}
+
+/// An event of the construction of a straight skeleton, see [`Skeleton::events`].
+///
+/// The nodes of the skeleton are numbered in the order they were created, starting from the vertices of the input,
+/// and can be looked up by [`Skeleton::node`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkeletonEvent {
+    /// The position of the event in the order of processing.
+    pub index: usize,
+    /// The type of the event.
+    pub kind: EventKind,
+    /// The time of the event, i.e. the distance the wavefront has travelled from the input.
+    pub time: f64,
+    /// The location where the event happened.
+    pub location: Coord,
+    /// The nodes of the vertices of the wavefront taking part in the event, whose arcs end here: the two adjacent vertices
+    /// which met for a shrink event, and the vertex which hit an edge for a split event.
+    pub ending: Vec<usize>,
+    /// The nodes of the vertices of the wavefront created by the event, whose arcs start here: one for a shrink event
+    /// (unless the ring vanished), and one on each side of the split for a split event.
+    pub starting: Vec<usize>,
+}
+
+impl Skeleton {
+    /// Returns the processed events of the construction of this skeleton in order, without the wavefronts of [`Skeleton::frames`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::skeleton::EventKind;
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let split = skel.events().find(|e| e.kind == EventKind::Split).unwrap();
+    ///
+    /// // The reflex vertex (2, 1) hits the bottom edge, and a vertex starts on each side.
+    /// assert_eq!(skel.node(split.ending[0]).unwrap().0, (2., 1.).into());
+    /// assert_eq!(split.starting.len(), 2);
+    /// assert!(split.starting.iter().all(|&n| (skel.node(n).unwrap().1 - split.time).abs() < 1e-9));
+    /// ```
+    pub fn events(&self) -> impl Iterator<Item = SkeletonEvent> + '_ {
+        let mut vertex_queue = self.initial_vertex_queue.clone();
+        self.event_queue
+            .iter()
+            .enumerate()
+            .map(move |(index, event)| {
+                let (kind, time, ending, starting) = match *event {
+                    Event::VertexEvent {
+                        time,
+                        merge_from,
+                        merge_to,
+                    } => {
+                        let from = IndexType::PointerIndex(merge_from);
+                        let ending = vec![
+                            vertex_queue.get_real_index(from),
+                            vertex_queue.get_real_index(vertex_queue.rv(from)),
+                        ];
+                        (EventKind::Shrink, time, ending, vec![merge_to])
+                    }
+                    Event::EdgeEvent {
+                        time,
+                        split_from,
+                        split_to_left,
+                        split_to_right,
+                        ..
+                    } => {
+                        let ending =
+                            vec![vertex_queue.get_real_index(IndexType::PointerIndex(split_from))];
+                        (
+                            EventKind::Split,
+                            time,
+                            ending,
+                            vec![split_to_left, split_to_right],
+                        )
+                    }
+                };
+                Self::apply_event(&mut vertex_queue, event);
+                SkeletonEvent {
+                    index,
+                    kind,
+                    time,
+                    location: self.ray_vector[starting[0]].inner_location(),
+                    ending,
+                    starting,
+                }
+            })
+    }
+
+    /// Returns the location and the time of the node `index` of this skeleton, or `None` if there is no such node.
+    ///
+    /// The time of a node is the distance by which the input has to be buffered for the wavefront to pass it.
+    /// See [`SkeletonEvent`] for the numbering of the nodes.
+    #[must_use]
+    pub fn node(&self, index: usize) -> Option<(Coord, f64)> {
+        self.ray_vector
+            .get(index)
+            .map(|v| (v.inner_location(), v.time_elapsed()))
+    }
+
+    /// Replays the construction of this skeleton, and returns a frame for each processed event in order.
+    ///
+    /// Events which turned out to be stale during the construction are not recorded in the skeleton, so they are not replayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::skeleton::EventKind;
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let frames = skeleton_of_polygon(&p1, Side::Interior).frames();
+    ///
+    /// assert!(frames.iter().any(|f| f.kind == EventKind::Split));
+    /// assert!(frames.windows(2).all(|w| w[0].time <= w[1].time + 1e-9));
+    /// assert!(frames.last().unwrap().wavefront.is_empty());
+    /// ```
+    pub fn frames(&self) -> Vec<EventFrame> {
+        let mut vertex_queue = self.initial_vertex_queue.clone();
+        self.event_queue
+            .iter()
+            .enumerate()
+            .map(|(index, event)| {
+                Self::apply_event(&mut vertex_queue, event);
+                let (kind, time, vertex) = match *event {
+                    Event::VertexEvent { time, merge_to, .. } => {
+                        (EventKind::Shrink, time, merge_to)
+                    }
+                    Event::EdgeEvent {
+                        time,
+                        split_to_left,
+                        ..
+                    } => (EventKind::Split, time, split_to_left),
+                };
+                EventFrame {
+                    index,
+                    kind,
+                    time,
+                    location: self.ray_vector[vertex].inner_location(),
+                    wavefront: self.wavefront_rings(&vertex_queue, time, &mut Vec::new()),
+                }
+            })
+            .collect()
+    }
+
+    /// Replays the construction of this skeleton, and returns the wavefront after each time at which its rings changed,
+    /// together with that time and the locations of the events happening at it.
+    ///
+    /// Events closer in time than the tolerance of the crate are taken together. The wavefront is taken halfway to the next
+    /// time at which events happen, where it is a proper multi-polygon.
+    pub(crate) fn topology_changes(&self) -> Vec<(f64, Vec<Coord>, MultiPolygon)> {
+        let mut vertex_queue = self.initial_vertex_queue.clone();
+        let mut rings = vertex_queue.ring_starts().len();
+        let mut events = self.event_queue.iter().peekable();
+        let mut res = Vec::new();
+        while let Some(first) = events.next() {
+            let time = first.unwrap_time();
+            let mut locations = Vec::new();
+            let mut split = false;
+            let mut event = first;
+            loop {
+                Self::apply_event(&mut vertex_queue, event);
+                let vertex = match *event {
+                    Event::VertexEvent { merge_to, .. } => merge_to,
+                    Event::EdgeEvent { split_to_left, .. } => {
+                        split = true;
+                        split_to_left
+                    }
+                };
+                locations.push(self.ray_vector[vertex].inner_location());
+                match events.next_if(|e| feq(e.unwrap_time(), time)) {
+                    Some(e) => event = e,
+                    None => break,
+                }
+            }
+            let count = vertex_queue.ring_starts().len();
+            if split || count != rings {
+                let next = events.peek().map_or(time + 1., |e| e.unwrap_time());
+                let wavefront =
+                    self.apply_vertex_queue(&vertex_queue, (time + next) / 2., &mut Vec::new());
+                res.push((time, locations, wavefront));
+            }
+            rings = count;
+        }
+        res
+    }
+}
@@ -0,0 +1,475 @@
+//! A versioned binary format of [`Skeleton`], see [`Skeleton::to_bytes`].
+//!
+//! The format is a 24-byte header followed by the payload, all little-endian:
+//!
+//! | bytes  | content                                   |
+//! |--------|-------------------------------------------|
+//! | 0..4   | magic `GBSK`                              |
+//! | 4..6   | format version                            |
+//! | 6..8   | reserved, zero                            |
+//! | 8..16  | payload length                            |
+//! | 16..24 | FNV-1a hash of the payload                |
+//!
+//! The payload holds the vertices, the events, the initial wavefront and the winding of the input.
+//! The other fields of a skeleton are derived from these when it is loaded.
+
+use super::*;
+use crate::Error;
+
+const MAGIC: &[u8; 4] = b"GBSK";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 24;
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, x: u8) {
+        self.0.push(x);
+    }
+
+    fn u32(&mut self, x: u32) {
+        self.0.extend_from_slice(&x.to_le_bytes());
+    }
+
+    fn usize(&mut self, x: usize) {
+        // `usize::MAX` is written as `u64::MAX` so that skeletons move between 32 and 64 bit targets.
+        let x = if x == usize::MAX { u64::MAX } else { x as u64 };
+        self.0.extend_from_slice(&x.to_le_bytes());
+    }
+
+    fn f64(&mut self, x: f64) {
+        self.0.extend_from_slice(&x.to_le_bytes());
+    }
+
+    fn coordinate(&mut self, c: Coord) {
+        self.f64(c.x);
+        self.f64(c.y);
+    }
+
+    fn ray(&mut self, r: &Ray) {
+        self.coordinate(r.origin);
+        self.coordinate(r.angle);
+    }
+
+    fn index(&mut self, i: IndexType) {
+        match i {
+            IndexType::PointerIndex(x) => {
+                self.u8(0);
+                self.usize(x);
+            }
+            IndexType::RealIndex(x) => {
+                self.u8(1);
+                self.usize(x);
+            }
+        }
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (head, rest) = self.0.split_first_chunk().ok_or(Error::Truncated)?;
+        self.0 = rest;
+        Ok(*head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn usize(&mut self) -> Result<usize, Error> {
+        match u64::from_le_bytes(self.take()?) {
+            // `usize::MAX` marks missing parents, whatever the width of `usize` is.
+            u64::MAX => Ok(usize::MAX),
+            x => usize::try_from(x).map_err(|_| Error::MalformedSkeleton("index out of range")),
+        }
+    }
+
+    fn f64(&mut self) -> Result<f64, Error> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    fn coordinate(&mut self) -> Result<Coord, Error> {
+        Ok(Coord {
+            x: self.f64()?,
+            y: self.f64()?,
+        })
+    }
+
+    fn ray(&mut self) -> Result<Ray, Error> {
+        Ok(Ray {
+            origin: self.coordinate()?,
+            angle: self.coordinate()?,
+        })
+    }
+
+    fn index(&mut self) -> Result<IndexType, Error> {
+        match self.u8()? {
+            0 => Ok(IndexType::PointerIndex(self.usize()?)),
+            1 => Ok(IndexType::RealIndex(self.usize()?)),
+            _ => Err(Error::MalformedSkeleton("unknown index kind")),
+        }
+    }
+
+    /// Reads a length of a sequence whose elements take at least `min_size` bytes each,
+    /// so that a corrupted length cannot cause a huge allocation.
+    fn len(&mut self, min_size: usize) -> Result<usize, Error> {
+        let len = self.usize()?;
+        if len.saturating_mul(min_size) > self.0.len() {
+            return Err(Error::Truncated);
+        }
+        Ok(len)
+    }
+}
+
+impl Skeleton {
+    /// Serializes this skeleton into a compact, versioned binary format with a checksum, see [`Skeleton::from_bytes`].
+    ///
+    /// The cached wavefronts of the skeleton are not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, BufferOptions, Side, Skeleton};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let bytes: Vec<u8> = skel.to_bytes();
+    /// let loaded = Skeleton::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(loaded.buffer(0.45, &BufferOptions::new()), skel.buffer(0.45, &BufferOptions::new()));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer(Vec::new());
+        w.usize(self.ray_vector.len());
+        for v in &self.ray_vector {
+            match v {
+                VertexType::Tree {
+                    axis,
+                    left_ray,
+                    right_ray,
+                    parent,
+                    time_elapsed,
+                } => {
+                    w.u8(0);
+                    w.ray(axis);
+                    w.ray(left_ray);
+                    w.ray(right_ray);
+                    w.usize(*parent);
+                    w.f64(*time_elapsed);
+                }
+                VertexType::Split {
+                    anchor,
+                    location,
+                    split_left,
+                    split_right,
+                    time_elapsed,
+                } => {
+                    w.u8(1);
+                    w.usize(*anchor);
+                    w.coordinate(*location);
+                    w.usize(*split_left);
+                    w.usize(*split_right);
+                    w.f64(*time_elapsed);
+                }
+                VertexType::Root {
+                    location,
+                    time_elapsed,
+                } => {
+                    w.u8(2);
+                    w.coordinate(*location);
+                    w.f64(*time_elapsed);
+                }
+            }
+        }
+        w.usize(self.event_queue.len());
+        for e in &self.event_queue {
+            match e {
+                Event::VertexEvent {
+                    time,
+                    merge_from,
+                    merge_to,
+                } => {
+                    w.u8(0);
+                    w.f64(*time);
+                    w.usize(*merge_from);
+                    w.usize(*merge_to);
+                }
+                Event::EdgeEvent {
+                    time,
+                    split_from,
+                    split_into,
+                    split_to_left,
+                    split_to_right,
+                } => {
+                    w.u8(1);
+                    w.f64(*time);
+                    w.usize(*split_from);
+                    w.usize(*split_into);
+                    w.usize(*split_to_left);
+                    w.usize(*split_to_right);
+                }
+            }
+        }
+        let vq = &self.initial_vertex_queue;
+        w.usize(vq.content.len());
+        for node in &vq.content {
+            w.index(node.index);
+            w.index(node.left);
+            w.index(node.right);
+            w.u8(node.done.into());
+            w.u32(node.generation);
+        }
+        let ring_starts = vq.ring_starts();
+        w.usize(ring_starts.len());
+        for sv in ring_starts {
+            w.usize(sv);
+        }
+        w.u8(self.clockwise_input.into());
+
+        let payload = w.0;
+        let mut res = Vec::with_capacity(HEADER_LEN + payload.len());
+        res.extend_from_slice(MAGIC);
+        res.extend_from_slice(&VERSION.to_le_bytes());
+        res.extend_from_slice(&0u16.to_le_bytes());
+        res.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        res.extend_from_slice(&fnv1a(&payload).to_le_bytes());
+        res.extend_from_slice(&payload);
+        res
+    }
+
+    /// Deserializes a skeleton written by [`Skeleton::to_bytes`].
+    ///
+    /// The header, the checksum and the consistency of the indices are checked, so that corrupted bytes result in an [`Error`]
+    /// instead of a panic or a wrong buffer later. Trailing bytes after the skeleton are ignored, so skeletons can be
+    /// concatenated in one file (e.g. a memory-mapped one) and loaded from its slices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Error, Side, Skeleton};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)]), vec![],
+    /// );
+    /// let mut bytes = skeleton_of_polygon(&p1, Side::Exterior).to_bytes();
+    /// let last = bytes.len() - 2;
+    /// bytes[last] ^= 1;
+    ///
+    /// assert_eq!(Skeleton::from_bytes(&bytes).err(), Some(Error::ChecksumMismatch));
+    /// assert_eq!(Skeleton::from_bytes(b"not a skeleton").err(), Some(Error::Truncated));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let header = bytes.get(..HEADER_LEN).ok_or(Error::Truncated)?;
+        if &header[0..4] != MAGIC {
+            return Err(Error::NotASkeleton);
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let mut r = Reader(&header[8..]);
+        let payload_len = r.usize()?;
+        let checksum = u64::from_le_bytes(r.take()?);
+        let payload = bytes[HEADER_LEN..]
+            .get(..payload_len)
+            .ok_or(Error::Truncated)?;
+        if fnv1a(payload) != checksum {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let mut r = Reader(payload);
+        let len = r.len(1)?;
+        let mut ray_vector = Vec::with_capacity(len);
+        for _ in 0..len {
+            ray_vector.push(match r.u8()? {
+                0 => VertexType::Tree {
+                    axis: r.ray()?,
+                    left_ray: r.ray()?,
+                    right_ray: r.ray()?,
+                    parent: r.usize()?,
+                    time_elapsed: r.f64()?,
+                },
+                1 => VertexType::Split {
+                    anchor: r.usize()?,
+                    location: r.coordinate()?,
+                    split_left: r.usize()?,
+                    split_right: r.usize()?,
+                    time_elapsed: r.f64()?,
+                },
+                2 => VertexType::Root {
+                    location: r.coordinate()?,
+                    time_elapsed: r.f64()?,
+                },
+                _ => return Err(Error::MalformedSkeleton("unknown vertex kind")),
+            });
+        }
+        let len = r.len(1)?;
+        let mut event_queue = Vec::with_capacity(len);
+        for _ in 0..len {
+            event_queue.push(match r.u8()? {
+                0 => Event::VertexEvent {
+                    time: r.f64()?,
+                    merge_from: r.usize()?,
+                    merge_to: r.usize()?,
+                },
+                1 => Event::EdgeEvent {
+                    time: r.f64()?,
+                    split_from: r.usize()?,
+                    split_into: r.usize()?,
+                    split_to_left: r.usize()?,
+                    split_to_right: r.usize()?,
+                },
+                _ => return Err(Error::MalformedSkeleton("unknown event kind")),
+            });
+        }
+        let len = r.len(1)?;
+        let mut content = Vec::with_capacity(len);
+        for _ in 0..len {
+            content.push(Node {
+                index: r.index()?,
+                left: r.index()?,
+                right: r.index()?,
+                done: r.u8()? != 0,
+                generation: r.u32()?,
+            });
+        }
+        let len = r.len(1)?;
+        let mut start_vertex = Vec::with_capacity(len);
+        for _ in 0..len {
+            start_vertex.push(r.usize()?);
+        }
+        let clockwise_input = r.u8()? != 0;
+        if !r.0.is_empty() {
+            return Err(Error::MalformedSkeleton(
+                "unexpected bytes after the skeleton",
+            ));
+        }
+
+        let mut initial_vertex_queue = VertexQueue::new();
+        initial_vertex_queue.content = content;
+        // The rings are found by following the links of the nodes, so their first nodes are only checked.
+        check_indices(
+            &ray_vector,
+            &event_queue,
+            &initial_vertex_queue,
+            &start_vertex,
+        )?;
+        let side = Skeleton::traced_side(&ray_vector);
+        Ok(Skeleton::from_parts(
+            ray_vector,
+            event_queue,
+            initial_vertex_queue,
+            side,
+            clockwise_input,
+        ))
+    }
+}
+
+/// The stored fields of a [`Skeleton`], which are checked when it is deserialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(super) struct SkeletonParts {
+    ray_vector: Vec<VertexType>,
+    event_queue: Vec<Event>,
+    initial_vertex_queue: VertexQueue,
+    side: Side,
+    clockwise_input: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SkeletonParts> for Skeleton {
+    type Error = Error;
+
+    fn try_from(parts: SkeletonParts) -> Result<Self, Error> {
+        check_indices(
+            &parts.ray_vector,
+            &parts.event_queue,
+            &parts.initial_vertex_queue,
+            &[],
+        )?;
+        Ok(Skeleton::from_parts(
+            parts.ray_vector,
+            parts.event_queue,
+            parts.initial_vertex_queue,
+            parts.side,
+            parts.clockwise_input,
+        ))
+    }
+}
+
+/// Checks that every index of a loaded skeleton points into the vector it refers to.
+fn check_indices(
+    ray_vector: &[VertexType],
+    event_queue: &[Event],
+    vertex_queue: &VertexQueue,
+    start_vertex: &[usize],
+) -> Result<(), Error> {
+    let vertices = ray_vector.len();
+    // Each edge event adds one node to the wavefront.
+    let nodes = vertex_queue.content.len()
+        + event_queue
+            .iter()
+            .filter(|e| matches!(e, Event::EdgeEvent { .. }))
+            .count();
+    let vertex_ok = ray_vector.iter().all(|v| match *v {
+        VertexType::Tree { parent, .. } => parent == usize::MAX || parent < vertices,
+        VertexType::Split {
+            anchor,
+            split_left,
+            split_right,
+            ..
+        } => anchor < vertices && split_left < vertices && split_right < vertices,
+        VertexType::Root { .. } => true,
+    });
+    if !vertex_ok {
+        return Err(Error::MalformedSkeleton("vertex index out of range"));
+    }
+    let event_ok = event_queue.iter().all(|e| match *e {
+        Event::VertexEvent {
+            merge_from,
+            merge_to,
+            ..
+        } => merge_from < nodes && merge_to < vertices,
+        Event::EdgeEvent {
+            split_from,
+            split_into,
+            split_to_left,
+            split_to_right,
+            ..
+        } => {
+            split_from < nodes
+                && split_into < nodes
+                && split_to_left < vertices
+                && split_to_right < vertices
+        }
+    });
+    if !event_ok {
+        return Err(Error::MalformedSkeleton("event index out of range"));
+    }
+    let initial_nodes = vertex_queue.content.len();
+    let node_ok = vertex_queue.content.iter().all(|node| {
+        matches!(node.index, IndexType::RealIndex(i) if i < vertices)
+            && matches!(node.left, IndexType::PointerIndex(i) if i < initial_nodes)
+            && matches!(node.right, IndexType::PointerIndex(i) if i < initial_nodes)
+    });
+    if !node_ok || start_vertex.iter().any(|&i| i >= initial_nodes) {
+        return Err(Error::MalformedSkeleton("wavefront index out of range"));
+    }
+    Ok(())
+}
@@ -0,0 +1,505 @@
+//! The split events of the construction of a skeleton.
+//!
+//! Each reflex vertex of the input (an anchor) keeps a single split event in the queue: its earliest split with the current wavefront.
+//! The earliest split of an anchor only gets later when the edge it splits changes, which is checked when its event is taken,
+//! and only gets earlier when an event creates a new wavefront edge, whose anchors are found with an [`AnchorIndex`].
+//! An edge which already crossed the anchor when its event is taken blocks the anchor until that edge changes.
+//! The searches are limited to a horizon, which is extended whenever the next event lies beyond it.
+
+use super::*;
+use crate::edge_index::{AnchorIndex, HORIZON_GROWTH};
+#[cfg(feature = "rayon")]
+use crate::monitor::Watcher;
+
+/// The number of anchors searched between two checks of the monitor when the search runs in parallel.
+#[cfg(feature = "rayon")]
+const SEARCH_CHUNK: usize = 1024;
+
+/// Where the search for the earliest split of an anchor stands.
+#[derive(Clone, Copy, Debug)]
+enum SplitState {
+    /// No split has been found until the horizon.
+    Unresolved,
+    /// The earliest split is queued as the event of the handle.
+    Scheduled {
+        split: SplitCandidate,
+        handle: Handle,
+        /// Whether the split was found among all the edges near the anchor, rather than on a new edge only.
+        searched: bool,
+    },
+    /// The earliest split lies before the current time, so the anchor cannot split any edge until the edge of that split changes.
+    /// The nodes at the ends of the edge are stamped with their generations.
+    Blocked(SplitCandidate, [(IndexType, u32); 2]),
+}
+
+/// A reflex vertex of the input which has not been split yet.
+#[derive(Clone, Copy, Debug)]
+struct Anchor {
+    /// The generation of the node of the anchor, which changes when the anchor is split or merged.
+    generation: u32,
+    state: SplitState,
+}
+
+/// The split events of the anchors, see the module documentation.
+pub(super) struct SplitSchedule {
+    /// The anchors by node. Only the nodes of the initial wavefront can be anchors.
+    anchors: Vec<Option<Anchor>>,
+    /// The anchors whose splits have not been found until the horizon (and possibly some which have been since).
+    unresolved: Vec<usize>,
+    /// The blocked anchors by the nodes at the ends of the edges blocking them (and possibly some which are not blocked anymore).
+    watchers: Vec<Vec<usize>>,
+    edge_index: EdgeIndex,
+    anchor_index: AnchorIndex,
+    /// The time until which the earliest split of every anchor is known.
+    horizon: f64,
+    /// The time of the event being processed.
+    time: f64,
+    orient: bool,
+    /// The pool running the searches after the horizon is extended, or `None` for the global pool.
+    #[cfg(feature = "rayon")]
+    pub(super) thread_pool: Option<Arc<rayon::ThreadPool>>,
+    candidates: Vec<IndexType>,
+    split_vertices: Vec<SplitCandidate>,
+    found_anchors: Vec<usize>,
+}
+
+impl SplitSchedule {
+    pub(super) fn new() -> Self {
+        Self {
+            anchors: Vec::new(),
+            unresolved: Vec::new(),
+            watchers: Vec::new(),
+            edge_index: EdgeIndex::new(),
+            anchor_index: AnchorIndex::new(),
+            horizon: f64::NEG_INFINITY,
+            time: 0.,
+            orient: true,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+            candidates: Vec::new(),
+            split_vertices: Vec::new(),
+            found_anchors: Vec::new(),
+        }
+    }
+
+    /// Starts the schedule of the initial wavefront `vertex_queue`, whose reflex vertices become the anchors.
+    ///
+    /// The anchors are searched by the first call to [`SplitSchedule::advance`].
+    pub(super) fn initialize(
+        &mut self,
+        orient: bool,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+    ) {
+        self.anchors.clear();
+        self.anchors.resize(vertex_queue.content.len(), None);
+        self.unresolved.clear();
+        self.watchers.iter_mut().for_each(Vec::clear);
+        self.edge_index.clear();
+        self.anchor_index.clear();
+        self.horizon = f64::NEG_INFINITY;
+        self.time = 0.;
+        self.orient = orient;
+        for cv in vertex_queue.live_nodes() {
+            let vertex = &vertex_vector[vertex_queue.get_real_index(cv)];
+            if Skeleton::is_reflex(vertex, orient) {
+                self.anchors[cv.get_index()] = Some(Anchor {
+                    generation: vertex_queue.generation(cv),
+                    state: SplitState::Unresolved,
+                });
+                self.unresolved.push(cv.get_index());
+            }
+        }
+    }
+
+    /// Returns the anchor of the node `node` if it has not been split or merged.
+    fn anchor(&self, node: usize, vertex_queue: &VertexQueue) -> Option<Anchor> {
+        self.anchors
+            .get(node)
+            .copied()
+            .flatten()
+            .filter(|a| vertex_queue.is_current(IndexType::PointerIndex(node), a.generation))
+    }
+
+    /// Extends the horizon and searches the unresolved anchors again, as long as the next event of `event_pq` lies beyond the horizon.
+    ///
+    /// This has to be called before every event is taken from the queue, since the splits found may come first.
+    pub(super) fn advance(
+        &mut self,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        event_pq: &mut EventQueue,
+        monitor: &mut Monitor,
+        processed_events: usize,
+    ) -> Result<(), Error> {
+        loop {
+            let mut unresolved = std::mem::take(&mut self.unresolved);
+            unresolved.sort_unstable();
+            unresolved.dedup();
+            unresolved.retain(|&node| {
+                self.anchor(node, vertex_queue)
+                    .is_some_and(|a| matches!(a.state, SplitState::Unresolved))
+            });
+            let next_time = event_pq.next_time();
+            if unresolved.is_empty()
+                || self.horizon == f64::INFINITY
+                || next_time.is_some_and(|time| time <= self.horizon)
+            {
+                self.unresolved = unresolved;
+                return Ok(());
+            }
+            // Without events, the wavefront does not change anymore and the anchors are searched until the end of time.
+            self.horizon = next_time.map_or(f64::INFINITY, |time| {
+                f64::max(time, self.horizon * HORIZON_GROWTH)
+            });
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                horizon = self.horizon,
+                unresolved = unresolved.len(),
+                "split search horizon extended"
+            );
+            self.edge_index
+                .reserve(self.horizon, vertex_queue, vertex_vector);
+            let anchors = (0..self.anchors.len()).filter(|&node| {
+                self.anchors[node].is_some_and(|a| {
+                    vertex_queue.is_current(IndexType::PointerIndex(node), a.generation)
+                })
+            });
+            self.anchor_index
+                .rebuild(anchors, vertex_queue, vertex_vector, self.horizon);
+            let progress = |event_pq: &EventQueue| Progress {
+                processed_events,
+                queued_events: event_pq.len(),
+                retired_events: event_pq.stats().removed,
+            };
+            #[cfg(feature = "rayon")]
+            {
+                let mut found = Vec::new();
+                for chunk in unresolved.chunks(SEARCH_CHUNK) {
+                    monitor.poll(progress(event_pq))?;
+                    self.search_in_parallel(
+                        chunk,
+                        vertex_queue,
+                        vertex_vector,
+                        monitor.watcher(),
+                        &mut found,
+                    )?;
+                    for (&node, split) in chunk.iter().zip(found.drain(..)) {
+                        self.settle(node, split, true, vertex_queue, vertex_vector, event_pq);
+                    }
+                }
+            }
+            #[cfg(not(feature = "rayon"))]
+            for &node in &unresolved {
+                monitor.poll(progress(event_pq))?;
+                let split = self.search(node, self.time, vertex_queue, vertex_vector);
+                self.settle(node, split, true, vertex_queue, vertex_vector, event_pq);
+            }
+        }
+    }
+
+    /// Searches the anchors of the nodes `chunk` on the thread pool, and stores their earliest splits in `found`.
+    ///
+    /// Every search reads the wavefront only, so they can run independently. The search stops as soon as `watcher` aborts it.
+    #[cfg(feature = "rayon")]
+    fn search_in_parallel(
+        &self,
+        chunk: &[usize],
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        watcher: Watcher,
+        found: &mut Vec<Option<SplitCandidate>>,
+    ) -> Result<(), Error> {
+        use rayon::prelude::*;
+
+        found.clear();
+        found.resize(chunk.len(), None);
+        let mut search = || {
+            chunk
+                .par_iter()
+                .zip(found.par_iter_mut())
+                .try_for_each_init(
+                    || (Vec::new(), Vec::new()),
+                    |(candidates, split_vertices), (&node, found)| {
+                        watcher.check()?;
+                        *found = Self::search_with(
+                            &self.edge_index,
+                            node,
+                            self.time,
+                            self.horizon,
+                            self.orient,
+                            vertex_queue,
+                            vertex_vector,
+                            candidates,
+                            split_vertices,
+                        );
+                        Ok(())
+                    },
+                )
+        };
+        match self.thread_pool.as_deref() {
+            Some(pool) => pool.install(search),
+            None => search(),
+        }
+    }
+
+    /// Returns the earliest split of the anchor of the node `node` with the current wavefront between `time` and `horizon`, if any.
+    ///
+    /// The horizon of `edge_index` has to reach `horizon`.
+    #[allow(clippy::too_many_arguments)]
+    fn search_with(
+        edge_index: &EdgeIndex,
+        node: usize,
+        time: f64,
+        horizon: f64,
+        orient: bool,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        candidates: &mut Vec<IndexType>,
+        split_vertices: &mut Vec<SplitCandidate>,
+    ) -> Option<SplitCandidate> {
+        let cv = IndexType::PointerIndex(node);
+        let anchor = &vertex_vector[vertex_queue.get_real_index(cv)];
+        edge_index.locate(anchor, horizon, vertex_queue, candidates);
+        Skeleton::find_split_vertex(
+            cv,
+            candidates.iter().copied(),
+            vertex_queue,
+            vertex_vector,
+            orient,
+            time,
+            horizon,
+            split_vertices,
+        );
+        split_vertices.first().copied()
+    }
+
+    /// Returns the earliest split of the anchor of the node `node` with the current wavefront between `time` and the horizon, if any.
+    fn search(
+        &mut self,
+        node: usize,
+        time: f64,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+    ) -> Option<SplitCandidate> {
+        self.edge_index
+            .reserve(self.horizon, vertex_queue, vertex_vector);
+        Self::search_with(
+            &self.edge_index,
+            node,
+            time,
+            self.horizon,
+            self.orient,
+            vertex_queue,
+            vertex_vector,
+            &mut self.candidates,
+            &mut self.split_vertices,
+        )
+    }
+
+    /// Makes `split` the earliest split of the anchor of the node `node`, replacing its queued event if it has one.
+    ///
+    /// `searched` tells whether `split` was found among all the edges near the anchor.
+    #[allow(clippy::too_many_arguments)]
+    fn settle(
+        &mut self,
+        node: usize,
+        split: Option<SplitCandidate>,
+        searched: bool,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        event_pq: &mut EventQueue,
+    ) {
+        let Some(anchor) = &mut self.anchors[node] else {
+            return;
+        };
+        if let SplitState::Scheduled { handle, .. } = anchor.state {
+            event_pq.remove(handle);
+        }
+        anchor.state = match split {
+            Some(split) if split.0 <= self.horizon => {
+                if split.0 < self.time && fneq(split.0, self.time) {
+                    let (sv, srv) = (split.2, vertex_queue.rv(split.2));
+                    let edge = [
+                        (sv, vertex_queue.generation(sv)),
+                        (srv, vertex_queue.generation(srv)),
+                    ];
+                    for (cv, _) in edge {
+                        let cv = cv.get_index();
+                        if self.watchers.len() <= cv {
+                            self.watchers.resize_with(cv + 1, Vec::new);
+                        }
+                        self.watchers[cv].push(node);
+                    }
+                    SplitState::Blocked(split, edge)
+                } else {
+                    let cv = IndexType::PointerIndex(node);
+                    let handle = Skeleton::make_split_event(
+                        cv,
+                        &split,
+                        vertex_queue,
+                        event_pq,
+                        vertex_vector,
+                    );
+                    SplitState::Scheduled {
+                        split,
+                        handle,
+                        searched,
+                    }
+                }
+            }
+            _ => {
+                self.unresolved.push(node);
+                SplitState::Unresolved
+            }
+        };
+    }
+
+    /// Takes the split event of the anchor `anchor_vertex` at `time` and `location`, just taken from the queue,
+    /// and returns the node and the vertex of the edge to split if the split happens.
+    ///
+    /// Otherwise the anchor is searched again, and its earliest split is queued instead.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn take(
+        &mut self,
+        anchor_vertex: IndexType,
+        time: f64,
+        location: Coord,
+        split_edge: [(IndexType, u32); 2],
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        event_pq: &mut EventQueue,
+    ) -> Option<(IndexType, usize)> {
+        self.time = time;
+        let node = anchor_vertex.get_index();
+        let is_split = |split: &SplitCandidate| feq(split.0, time) && split.1.approx_eq(&location);
+        // If the split was found among all the edges near the anchor and neither end of the edge to split has changed since,
+        // only that edge has to be checked against the current wavefront. Otherwise the split is searched again.
+        let [(sv, sv_generation), (srv, srv_generation)] = split_edge;
+        let mut split = None;
+        if self.anchors[node]
+            .is_some_and(|a| matches!(a.state, SplitState::Scheduled { searched: true, .. }))
+            && vertex_queue.is_current(sv, sv_generation)
+            && vertex_queue.is_current(srv, srv_generation)
+            && vertex_queue.rv(sv) == srv
+        {
+            Skeleton::find_split_vertex(
+                anchor_vertex,
+                std::iter::once(sv),
+                vertex_queue,
+                vertex_vector,
+                self.orient,
+                time,
+                time,
+                &mut self.split_vertices,
+            );
+            split = self.split_vertices.first().copied();
+        }
+        // The search goes back to the start, since an edge which already crossed the anchor blocks it until that edge changes.
+        if !split.as_ref().is_some_and(is_split) {
+            split = self.search(node, f64::NEG_INFINITY, vertex_queue, vertex_vector);
+        }
+        match split {
+            Some(split) if is_split(&split) => {
+                self.anchors[node] = None;
+                Some((split.2, split.3))
+            }
+            split => {
+                self.settle(node, split, true, vertex_queue, vertex_vector, event_pq);
+                None
+            }
+        }
+    }
+
+    /// Indexes the wavefront edges at the node `cv` after the event at `time` changed it,
+    /// and moves the splits of the anchors which hit these edges earlier to them.
+    pub(super) fn update(
+        &mut self,
+        cv: IndexType,
+        time: f64,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        event_pq: &mut EventQueue,
+    ) {
+        self.time = time;
+        self.edge_index.update(cv, vertex_queue, vertex_vector);
+        for sv in [vertex_queue.lv(cv), cv] {
+            let lv = &vertex_vector[vertex_queue.get_real_index(sv)];
+            let rv = &vertex_vector[vertex_queue.get_real_index(vertex_queue.rv(sv))];
+            self.anchor_index.locate(lv, rv, &mut self.found_anchors);
+            for i in 0..self.found_anchors.len() {
+                let node = self.found_anchors[i];
+                let Some(anchor) = self.anchor(node, vertex_queue) else {
+                    continue;
+                };
+                let earliest = match anchor.state {
+                    SplitState::Unresolved => None,
+                    SplitState::Scheduled { split, .. } | SplitState::Blocked(split, _) => {
+                        Some(split)
+                    }
+                };
+                Skeleton::find_split_vertex(
+                    IndexType::PointerIndex(node),
+                    std::iter::once(sv),
+                    vertex_queue,
+                    vertex_vector,
+                    self.orient,
+                    time,
+                    earliest.map_or(self.horizon, |split| split.0),
+                    &mut self.split_vertices,
+                );
+                let Some(split) = self.split_vertices.first().copied() else {
+                    continue;
+                };
+                if split.0 <= self.horizon
+                    && earliest.is_none_or(|earliest| split_key(&split) < split_key(&earliest))
+                {
+                    self.settle(
+                        node,
+                        Some(split),
+                        false,
+                        vertex_queue,
+                        vertex_vector,
+                        event_pq,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Searches the anchors blocked by an edge at the node `cv` again if the event at `time` changed that edge.
+    pub(super) fn touch(
+        &mut self,
+        cv: IndexType,
+        time: f64,
+        vertex_queue: &VertexQueue,
+        vertex_vector: &[VertexType],
+        event_pq: &mut EventQueue,
+    ) {
+        self.time = time;
+        let Some(watchers) = self.watchers.get_mut(cv.get_index()) else {
+            return;
+        };
+        for node in std::mem::take(watchers) {
+            let Some(Anchor {
+                state: SplitState::Blocked(_, edge),
+                ..
+            }) = self.anchor(node, vertex_queue)
+            else {
+                continue;
+            };
+            let [(sv, sv_generation), (srv, srv_generation)] = edge;
+            if vertex_queue.is_current(sv, sv_generation)
+                && vertex_queue.is_current(srv, srv_generation)
+                && vertex_queue.rv(sv) == srv
+            {
+                if cv == sv || cv == srv {
+                    self.watchers[cv.get_index()].push(node);
+                }
+                continue;
+            }
+            let split = self.search(node, f64::NEG_INFINITY, vertex_queue, vertex_vector);
+            self.settle(node, split, true, vertex_queue, vertex_vector, event_pq);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Summary statistics of a [`Skeleton`].
+
+use super::*;
+
+/// Counts and measures of a [`Skeleton`], see [`Skeleton::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SkeletonStats {
+    /// The number of nodes: the vertices of the input and the points where the wavefront changes its shape.
+    pub nodes: usize,
+    /// The number of arcs, i.e. of the paths traced by the vertices of the wavefront, including those to infinity.
+    pub arcs: usize,
+    /// The number of arcs to infinity, which are left by the vertices of the wavefront of an inflated input.
+    pub infinite_arcs: usize,
+    /// The number of events where two adjacent vertices of the wavefront met, see [`EventKind::Shrink`].
+    pub shrink_events: usize,
+    /// The number of events where a vertex of the wavefront hit an edge of it, see [`EventKind::Split`].
+    pub split_events: usize,
+    /// The total length of the arcs which end at a node.
+    pub total_arc_length: f64,
+    /// The latest time of a node, i.e. the distance by which the input has to be deflated to vanish (for an inward skeleton).
+    pub max_time: f64,
+}
+
+impl Skeleton {
+    /// Returns the counts of the nodes, arcs and events of this skeleton, with the total length of its arcs and the time of its latest node.
+    ///
+    /// These help to decide whether a skeleton is worth caching, and to study the behavior of the algorithm.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, Side};
+    /// use geo::{Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (10., 0.), (10., 2.), (0., 2.)]), vec![],
+    /// );
+    /// let stats = skeleton_of_polygon(&p1, Side::Interior).stats();
+    ///
+    /// assert_eq!(stats.arcs, 5);
+    /// assert_eq!(stats.infinite_arcs, 0);
+    /// assert_eq!(stats.split_events, 0);
+    /// assert_eq!(stats.max_time, 1.);
+    /// // Four corner bisectors, and the ridge from (1, 1) to (9, 1).
+    /// assert!((stats.total_arc_length - (4. * 2f64.sqrt() + 8.)).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn stats(&self) -> SkeletonStats {
+        let mut stats = SkeletonStats {
+            nodes: self.ray_vector.len(),
+            ..SkeletonStats::default()
+        };
+        for v in &self.ray_vector {
+            stats.max_time = stats.max_time.max(v.time_elapsed());
+            let VertexType::Tree { axis, parent, .. } = *v else {
+                continue;
+            };
+            stats.arcs += 1;
+            match self.ray_vector.get(parent) {
+                Some(p) => stats.total_arc_length += axis.origin.dist_coord(&p.inner_location()),
+                None => stats.infinite_arcs += 1,
+            }
+        }
+        for event in &self.event_queue {
+            match event {
+                Event::VertexEvent { .. } => stats.shrink_events += 1,
+                Event::EdgeEvent { .. } => stats.split_events += 1,
+            }
+        }
+        stats
+    }
+}
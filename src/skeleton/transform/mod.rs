@@ -0,0 +1,189 @@
+//! Similarity transformations of a [`Skeleton`], to reuse the skeleton of a shape for its translated, rotated or scaled copies.
+
+use super::*;
+
+impl Skeleton {
+    /// Returns this skeleton moved by `offset`, i.e. the skeleton of the input moved by `offset`, without building it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, BufferOptions, CoordExt, Side};
+    /// use geo::{CoordsIter, Polygon, LineString, Translate};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior).translated((10., 20.).into());
+    ///
+    /// let p2 = skel.buffer(0.45, &BufferOptions::new());
+    /// let p3 = skeleton_of_polygon(&p1.translate(10., 20.), Side::Interior).buffer(0.45, &BufferOptions::new());
+    /// assert_eq!(p2.coords_count(), p3.coords_count());
+    /// assert!(p2.coords_iter().zip(p3.coords_iter()).all(|(a, b)| a.approx_eq(&b)));
+    /// ```
+    #[must_use]
+    pub fn translated(&self, offset: Coord) -> Self {
+        self.transformed(|c| c + offset, |v| v, 1.)
+    }
+
+    /// Returns this skeleton rotated counter-clockwise by `angle` (in radian) around `origin`, i.e. the skeleton of the input rotated
+    /// the same way, without building it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, BufferOptions, Side};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior).rotated(std::f64::consts::FRAC_PI_2, (0., 0.).into());
+    /// let p2 = skel.buffer(0.2, &BufferOptions::new());
+    ///
+    /// assert!((p2.unsigned_area() - skeleton_of_polygon(&p1, Side::Interior).buffer(0.2, &BufferOptions::new()).unsigned_area()).abs() < 1e-9);
+    /// // The quarter turn brings the polygon to the left of the y-axis.
+    /// assert!(p2.0.iter().all(|p| p.exterior().0.iter().all(|c| c.x <= 0.)));
+    /// ```
+    #[must_use]
+    pub fn rotated(&self, angle: f64, origin: Coord) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let rotate = move |v: Coord| Coord {
+            x: v.x * cos - v.y * sin,
+            y: v.x * sin + v.y * cos,
+        };
+        self.transformed(move |c| origin + rotate(c - origin), rotate, 1.)
+    }
+
+    /// Returns this skeleton scaled by `factor` around `origin`, i.e. the skeleton of the input scaled the same way, without building
+    /// it again. The times of the events are scaled by `|factor|`, so buffering the result by `d` gives the buffer of this skeleton
+    /// by `d / |factor|`, scaled.
+    ///
+    /// A negative factor turns the skeleton by a half turn, which keeps its orientation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero or not finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{skeleton_of_polygon, BufferOptions, Side};
+    /// use geo::{Area, Polygon, LineString};
+    ///
+    /// let p1 = Polygon::new(
+    ///     LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]), vec![],
+    /// );
+    /// let skel = skeleton_of_polygon(&p1, Side::Interior);
+    /// let scaled = skel.scaled(2., (0., 0.).into());
+    ///
+    /// let a1 = skel.buffer(0.2, &BufferOptions::new()).unsigned_area();
+    /// let a2 = scaled.buffer(0.4, &BufferOptions::new()).unsigned_area();
+    /// assert!((a2 - 4. * a1).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn scaled(&self, factor: f64, origin: Coord) -> Self {
+        assert!(
+            factor != 0. && factor.is_finite(),
+            "the scale factor must be finite and non-zero"
+        );
+        self.transformed(
+            move |c| origin + (c - origin) * factor,
+            move |v| v * factor,
+            factor.abs(),
+        )
+    }
+
+    /// Returns this skeleton with its points moved by `point`, its directions by the linear part `linear` of `point`,
+    /// and its times multiplied by `time_scale`, the factor by which `linear` scales the lengths.
+    ///
+    /// The axes of the vertices are velocities, so they are divided by `time_scale`, while the base rays keep the edges of the input
+    /// as their directions.
+    fn transformed(
+        &self,
+        point: impl Fn(Coord) -> Coord,
+        linear: impl Fn(Coord) -> Coord,
+        time_scale: f64,
+    ) -> Self {
+        let ray = |r: Ray, speed: f64| Ray {
+            origin: point(r.origin),
+            angle: linear(r.angle) / speed,
+        };
+        let ray_vector = self
+            .ray_vector
+            .iter()
+            .map(|v| match *v {
+                VertexType::Tree {
+                    axis,
+                    left_ray,
+                    right_ray,
+                    parent,
+                    time_elapsed,
+                } => VertexType::Tree {
+                    axis: ray(axis, time_scale),
+                    left_ray: ray(left_ray, 1.),
+                    right_ray: ray(right_ray, 1.),
+                    parent,
+                    time_elapsed: time_elapsed * time_scale,
+                },
+                VertexType::Split {
+                    anchor,
+                    location,
+                    split_left,
+                    split_right,
+                    time_elapsed,
+                } => VertexType::Split {
+                    anchor,
+                    location: point(location),
+                    split_left,
+                    split_right,
+                    time_elapsed: time_elapsed * time_scale,
+                },
+                VertexType::Root {
+                    location,
+                    time_elapsed,
+                } => VertexType::Root {
+                    location: point(location),
+                    time_elapsed: time_elapsed * time_scale,
+                },
+            })
+            .collect();
+        let event_queue = self
+            .event_queue
+            .iter()
+            .map(|e| match *e {
+                Event::VertexEvent {
+                    time,
+                    merge_from,
+                    merge_to,
+                } => Event::VertexEvent {
+                    time: time * time_scale,
+                    merge_from,
+                    merge_to,
+                },
+                Event::EdgeEvent {
+                    time,
+                    split_from,
+                    split_into,
+                    split_to_left,
+                    split_to_right,
+                } => Event::EdgeEvent {
+                    time: time * time_scale,
+                    split_from,
+                    split_into,
+                    split_to_left,
+                    split_to_right,
+                },
+            })
+            .collect();
+        let mut initial_vertex_queue = VertexQueue::new();
+        initial_vertex_queue.content = self.initial_vertex_queue.content.clone();
+        Skeleton::from_parts(
+            ray_vector,
+            event_queue,
+            initial_vertex_queue,
+            self.side,
+            self.clockwise_input,
+        )
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+
+use geo::{BoundingRect, Intersects};
+use geo_types::{Coord, MultiPolygon, Polygon, Rect};
+
+use crate::{BufferOptions, SkeletonBuilder};
+
+/// Buffers a stream of polygons group by group, see [`buffer_polygons_iter`](crate::buffer_polygons_iter).
+///
+/// Two polygons are in the same group if their bounding rectangles, expanded by the distance, intersect, directly or
+/// through other polygons of the group. A group is buffered as soon as no later polygon can join it, which is the case
+/// once a polygon starts to the right of the group, as the polygons come sorted by the left of their bounding rectangles.
+pub(crate) struct BufferPolygons<I> {
+    input: I,
+    distance: f64,
+    /// The groups which later polygons may still join, with their expanded bounding rectangles.
+    pending: Vec<(Rect, Vec<Polygon>)>,
+    /// The groups which no later polygon can join, in the order they were closed.
+    ready: VecDeque<Vec<Polygon>>,
+    /// The builder of the skeletons of all the groups.
+    builder: SkeletonBuilder,
+}
+
+impl<I: Iterator<Item = Polygon>> BufferPolygons<I> {
+    pub(crate) fn new(input: I, distance: f64) -> Self {
+        Self {
+            input,
+            distance,
+            pending: Vec::new(),
+            ready: VecDeque::new(),
+            builder: SkeletonBuilder::new(),
+        }
+    }
+
+    fn push(&mut self, polygon: Polygon) {
+        let Some(bounds) = polygon.bounding_rect() else {
+            return;
+        };
+        let reach = self.distance.max(0.);
+        let reach = Coord { x: reach, y: reach };
+        let mut rect = Rect::new(bounds.min() - reach, bounds.max() + reach);
+
+        let (closed, open) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(r, _)| r.max().x < rect.min().x);
+        self.pending = open;
+        self.ready
+            .extend(closed.into_iter().map(|(_, group): (Rect, _)| group));
+
+        // Joining groups grows the rectangle, which may then reach groups checked before.
+        let mut group = vec![polygon];
+        loop {
+            let before = group.len();
+            self.pending.retain_mut(|(r, members)| {
+                if !r.intersects(&rect) {
+                    return true;
+                }
+                rect = Rect::new(
+                    Coord {
+                        x: rect.min().x.min(r.min().x),
+                        y: rect.min().y.min(r.min().y),
+                    },
+                    Coord {
+                        x: rect.max().x.max(r.max().x),
+                        y: rect.max().y.max(r.max().y),
+                    },
+                );
+                group.append(members);
+                false
+            });
+            if group.len() == before {
+                break;
+            }
+        }
+        self.pending.push((rect, group));
+    }
+}
+
+impl<I: Iterator<Item = Polygon>> Iterator for BufferPolygons<I> {
+    type Item = MultiPolygon;
+
+    fn next(&mut self) -> Option<MultiPolygon> {
+        loop {
+            if let Some(group) = self.ready.pop_front() {
+                let res = BufferOptions::new().buffer_with_builder(
+                    &mut self.builder,
+                    &group,
+                    self.distance,
+                );
+                if res.0.is_empty() {
+                    continue;
+                }
+                return Some(res);
+            }
+            match self.input.next() {
+                Some(polygon) => self.push(polygon),
+                None if self.pending.is_empty() => return None,
+                None => self
+                    .ready
+                    .extend(self.pending.drain(..).map(|(_, group)| group)),
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Toolpaths for pocketing, i.e. for clearing the inside of a polygon with a round tool (as CNC milling and 3D printing slicers do).
+//!
+//! The paths are built from the inward straight skeleton of the polygon, so all offsets of a pocket share one skeleton.
+
+use geo::{Distance, Euclidean};
+use geo_types::{Coord, LineString, Polygon};
+
+use crate::skeleton::{Side, Skeleton};
+use crate::{buffer_polygon, BufferOptions};
+
+/// This function returns the concentric toolpath of a pocket: the boundary of the given polygon deflated by `tool_radius`, then
+/// deflated further by `stepover` again and again until nothing is left, with each ring linked to a ring of the next offset.
+///
+/// Each `LineString` of the result is a continuous path. It runs once around a ring, then along a straight link to the closest vertex
+/// of a ring of the next offset, and so on. Where the pocket splits into several parts (e.g. around a hole), the further parts
+/// start new paths. Exteriors are counter-clockwise and interiors are clockwise, as in the results of [`buffer_polygon`](crate::buffer_polygon).
+///
+/// The result is empty if `stepover` is not positive.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::toolpath::concentric;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+/// );
+/// let paths = concentric(&p1, 1., 1.5);
+///
+/// // The square is deflated by 1, 2.5 and 4, and all three rings are linked into one path.
+/// assert_eq!(paths.len(), 1);
+/// assert_eq!(paths[0].0[0], (1., 1.).into());
+/// assert_eq!(paths[0].0.len(), 3 * 5);
+/// ```
+#[must_use]
+pub fn concentric(input_polygon: &Polygon, tool_radius: f64, stepover: f64) -> Vec<LineString> {
+    if stepover <= 0. {
+        return Vec::new();
+    }
+    let skel = Skeleton::skeleton_of_polygon(input_polygon, Side::Interior);
+    let Some((vanish, _)) = skel.last_event() else {
+        return Vec::new();
+    };
+
+    // The rings of each offset, together with the index of the ring of the previous offset they are linked to.
+    let mut rings: Vec<(LineString, Option<usize>)> = Vec::new();
+    let mut previous = 0..0;
+    let mut distance = tool_radius.max(0.);
+    while distance < vanish {
+        let offset = skel.buffer(distance, &BufferOptions::new());
+        let start = rings.len();
+        for ls in offset
+            .iter()
+            .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        {
+            let parent = previous.clone().min_by(|&i, &j| {
+                let (a, b) = (&rings[i].0, &rings[j].0);
+                Euclidean::distance(a, ls).total_cmp(&Euclidean::distance(b, ls))
+            });
+            rings.push((ls.clone(), parent));
+        }
+        previous = start..rings.len();
+        distance += stepover;
+    }
+
+    let mut children = vec![Vec::new(); rings.len()];
+    for (i, (_, parent)) in rings.iter().enumerate() {
+        if let Some(parent) = *parent {
+            children[parent].push(i);
+        }
+    }
+    let mut paths = Vec::new();
+    for root in (0..rings.len()).filter(|&i| rings[i].1.is_none()) {
+        let mut path = Vec::new();
+        walk(root, None, &rings, &children, &mut path, &mut paths);
+        paths.push(LineString::new(path));
+    }
+    paths
+}
+
+/// This function returns the zig-zag infill of a pocket: parallel lines at the given angle and spacing, clipped to the given
+/// polygon deflated by `tool_radius`, with consecutive lines joined at their ends.
+///
+/// The lines are those at multiples of `spacing` from the origin, so that the infills of neighbouring pockets (or layers) line up.
+/// The `angle` is measured in radians, counter-clockwise from the x-axis. Two consecutive lines are joined by a straight link
+/// if each of them only overlaps the other one; otherwise (e.g. where a hole splits the lines) the next line starts a new path.
+///
+/// The result is empty if `spacing` is not positive.
+///
+/// # Example
+///
+/// ```
+/// use geo_buf::toolpath::zigzag;
+/// use geo::{Polygon, LineString};
+///
+/// let p1 = Polygon::new(
+///     LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]), vec![],
+/// );
+/// let paths = zigzag(&p1, 1., 2., 0.);
+///
+/// // The lines at y = 2, 4, 6 and 8 run between x = 1 and x = 9, one way and back.
+/// assert_eq!(paths.len(), 1);
+/// assert_eq!(paths[0], LineString::from(vec![
+///     (1., 2.), (9., 2.), (9., 4.), (1., 4.), (1., 6.), (9., 6.), (9., 8.), (1., 8.),
+/// ]));
+/// ```
+#[must_use]
+pub fn zigzag(
+    input_polygon: &Polygon,
+    tool_radius: f64,
+    spacing: f64,
+    angle: f64,
+) -> Vec<LineString> {
+    if spacing <= 0. {
+        return Vec::new();
+    }
+    let deflated = buffer_polygon(input_polygon, -tool_radius.max(0.));
+    // The lines are made horizontal by rotating the pocket by `-angle`, and rotated back at the end.
+    let (sin, cos) = angle.sin_cos();
+    let rotate = |c: Coord, sin: f64| Coord {
+        x: c.x * cos - c.y * sin,
+        y: c.x * sin + c.y * cos,
+    };
+    let edges: Vec<(Coord, Coord)> = deflated
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .flat_map(|ls| ls.lines())
+        .map(|l| (rotate(l.start, -sin), rotate(l.end, -sin)))
+        .collect();
+    if edges.is_empty() {
+        return Vec::new();
+    }
+    let (min_y, max_y) = edges
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (a, _)| {
+            (lo.min(a.y), hi.max(a.y))
+        });
+
+    // The open paths, with the direction of their last segment (whether it runs towards increasing x).
+    let mut paths: Vec<(Vec<Coord>, bool)> = Vec::new();
+    // The segments of the previous line, with the paths they ended.
+    let mut previous: Vec<((f64, f64), usize)> = Vec::new();
+    let mut k = (min_y / spacing).floor() + 1.;
+    while k * spacing < max_y {
+        let y = k * spacing;
+        let mut xs: Vec<f64> = edges
+            .iter()
+            .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+            .map(|(a, b)| a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y))
+            .collect();
+        xs.sort_by(f64::total_cmp);
+        let segments: Vec<(f64, f64)> = xs.chunks_exact(2).map(|s| (s[0], s[1])).collect();
+        let overlaps = |a: (f64, f64), b: (f64, f64)| a.0 <= b.1 && b.0 <= a.1;
+        let mut current = Vec::with_capacity(segments.len());
+        for &seg in &segments {
+            let mut above = previous.iter().filter(|(prev, _)| overlaps(*prev, seg));
+            let joined = match (above.next(), above.next()) {
+                (Some(&(prev, path)), None)
+                    if segments
+                        .iter()
+                        .filter(|&&other| overlaps(prev, other))
+                        .count()
+                        == 1 =>
+                {
+                    Some(path)
+                }
+                _ => None,
+            };
+            let path = joined.unwrap_or_else(|| {
+                paths.push((Vec::new(), false));
+                paths.len() - 1
+            });
+            let (points, rightwards) = &mut paths[path];
+            *rightwards = !*rightwards;
+            let (from, to) = if *rightwards {
+                (seg.0, seg.1)
+            } else {
+                (seg.1, seg.0)
+            };
+            points.push(Coord { x: from, y });
+            points.push(Coord { x: to, y });
+            current.push((seg, path));
+        }
+        previous = current;
+        k += 1.;
+    }
+    paths
+        .into_iter()
+        .map(|(points, _)| points.into_iter().map(|c| rotate(c, sin)).collect())
+        .collect()
+}
+
+/// Appends the ring `i`, started at its vertex closest to `from`, and then the rings linked to it, to `path`.
+///
+/// The first ring linked to ring `i` continues `path`, while the others start new paths, pushed to `paths`.
+fn walk(
+    i: usize,
+    from: Option<Coord>,
+    rings: &[(LineString, Option<usize>)],
+    children: &[Vec<usize>],
+    path: &mut Vec<Coord>,
+    paths: &mut Vec<LineString>,
+) {
+    let ring = &rings[i].0 .0;
+    let len = ring.len() - 1;
+    let start = from.map_or(0, |from| {
+        (0..len)
+            .min_by(|&a, &b| {
+                let dist = |c: Coord| f64::hypot(c.x - from.x, c.y - from.y);
+                dist(ring[a]).total_cmp(&dist(ring[b]))
+            })
+            .unwrap_or(0)
+    });
+    path.extend(ring[start..len].iter().chain(&ring[..=start]));
+    let end = ring[start];
+    for (k, &child) in children[i].iter().enumerate() {
+        if k == 0 {
+            walk(child, Some(end), rings, children, path, paths);
+        } else {
+            let mut branch = Vec::new();
+            walk(child, Some(end), rings, children, &mut branch, paths);
+            paths.push(LineString::new(branch));
+        }
+    }
+}
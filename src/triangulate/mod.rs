@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use geo::{Area, TriangulateEarcut};
+use geo_types::{Coord, Polygon, Triangle};
+
+use crate::postprocess::{coord_key, CoordKey};
+use crate::skeleton::{Side, Skeleton};
+use crate::util::{feq, fgt};
+
+/// Splits `input` into the faces of its inward straight skeleton, and splits each face into triangles.
+///
+/// The face of an edge is monotone along the edge, so it is cut into triangles by clipping ears off its skeleton side.
+/// The skeleton of a degenerate input may have crossing arcs, whose faces overlap: if the triangles do not add up to the area
+/// of `input`, it is triangulated by ear clipping instead.
+pub(crate) fn triangulate(input: &Polygon) -> Vec<Triangle> {
+    let res = triangulate_faces(input);
+    let area = input.unsigned_area();
+    let sum: f64 = res.iter().map(|t| t.unsigned_area()).sum();
+    if (sum - area).abs() <= 1e-9 * area.max(1.) {
+        return res;
+    }
+    input
+        .earcut_triangles()
+        .into_iter()
+        .map(|t| {
+            if t.signed_area() < 0. {
+                Triangle::new(t.v1(), t.v3(), t.v2())
+            } else {
+                t
+            }
+        })
+        .collect()
+}
+
+fn triangulate_faces(input: &Polygon) -> Vec<Triangle> {
+    let skel = Skeleton::skeleton_of_polygon(input, Side::Interior);
+    let mut graph = Graph::default();
+    for ls in std::iter::once(input.exterior()).chain(input.interiors()) {
+        for l in ls.lines() {
+            graph.add_edge(l.start, l.end, false);
+        }
+    }
+    // The arcs of an inward skeleton end at their parents, except for those of degenerate inputs which go to infinity.
+    skel.for_each_arc_until(f64::INFINITY, |a, b| {
+        if b.x.is_finite() && b.y.is_finite() {
+            graph.add_edge(a, b, true);
+        }
+    });
+    let mut res = Vec::new();
+    for face in graph.faces() {
+        clip_ears(face, &mut res);
+    }
+    res
+}
+
+/// A planar graph on the boundary of the input and the arcs of its skeleton.
+#[derive(Default)]
+struct Graph {
+    vertices: Vec<Coord>,
+    index: HashMap<CoordKey, usize>,
+    /// The half-edges leaving each vertex, as their targets and whether they run along an arc of the skeleton.
+    outgoing: Vec<Vec<(usize, bool)>>,
+}
+
+impl Graph {
+    fn vertex(&mut self, c: Coord) -> usize {
+        *self.index.entry(coord_key(c)).or_insert_with(|| {
+            self.vertices.push(c);
+            self.outgoing.push(Vec::new());
+            self.vertices.len() - 1
+        })
+    }
+
+    fn add_edge(&mut self, a: Coord, b: Coord, arc: bool) {
+        let (u, v) = (self.vertex(a), self.vertex(b));
+        if u == v || self.outgoing[u].iter().any(|&(w, _)| w == v) {
+            return;
+        }
+        self.outgoing[u].push((v, arc));
+        self.outgoing[v].push((u, arc));
+    }
+
+    /// Returns the bounded faces of the graph which touch the skeleton, as counter-clockwise rings without closing coordinate.
+    ///
+    /// The other bounded faces are the holes of the input.
+    fn faces(mut self) -> Vec<Vec<Coord>> {
+        let vertices = &self.vertices;
+        let angle = |u: usize, v: usize| {
+            f64::atan2(vertices[v].y - vertices[u].y, vertices[v].x - vertices[u].x)
+        };
+        for (u, out) in self.outgoing.iter_mut().enumerate() {
+            out.sort_by(|&(v, _), &(w, _)| angle(u, v).total_cmp(&angle(u, w)));
+        }
+        let mut visited: Vec<Vec<bool>> = self
+            .outgoing
+            .iter()
+            .map(|out| vec![false; out.len()])
+            .collect();
+        let mut faces = Vec::new();
+        for start in 0..vertices.len() {
+            for first in 0..self.outgoing[start].len() {
+                if visited[start][first] {
+                    continue;
+                }
+                // Walks along the face on the left of the half-edge, turning as far right as possible at each vertex.
+                let mut ring = Vec::new();
+                let mut touches_skeleton = false;
+                let (mut u, mut i) = (start, first);
+                while !visited[u][i] {
+                    visited[u][i] = true;
+                    ring.push(vertices[u]);
+                    let (v, arc) = self.outgoing[u][i];
+                    touches_skeleton |= arc;
+                    let out = &self.outgoing[v];
+                    let back = out.iter().position(|&(w, _)| w == u).unwrap();
+                    i = (back + out.len() - 1) % out.len();
+                    u = v;
+                }
+                if touches_skeleton && signed_area(&ring) > 0. {
+                    faces.push(ring);
+                }
+            }
+        }
+        faces
+    }
+}
+
+fn signed_area(ring: &[Coord]) -> f64 {
+    ring.iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - a.y * b.x)
+        .sum::<f64>()
+        / 2.
+}
+
+fn cross(a: Coord, b: Coord, c: Coord) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Cuts the counter-clockwise ring `ring` into triangles, pushed to `res`.
+fn clip_ears(mut ring: Vec<Coord>, res: &mut Vec<Triangle>) {
+    while ring.len() > 3 {
+        let len = ring.len();
+        let corner = |i: usize| (ring[(i + len - 1) % len], ring[i], ring[(i + 1) % len]);
+        // Only vertices strictly inside block an ear, since the faces of degenerate inputs often have vertices on a line
+        // through two others which are not their neighbours.
+        let is_ear = |i: usize| {
+            let (a, b, c) = corner(i);
+            fgt(cross(a, b, c), 0.)
+                && ring.iter().all(|&p| {
+                    !fgt(cross(a, b, p), 0.) || !fgt(cross(b, c, p), 0.) || !fgt(cross(c, a, p), 0.)
+                })
+        };
+        // Vertices on a straight line between their neighbours only add empty triangles.
+        if let Some(i) = (0..len).find(|&i| {
+            let (a, b, c) = corner(i);
+            feq(cross(a, b, c), 0.)
+        }) {
+            ring.remove(i);
+            continue;
+        }
+        let Some(i) = (0..len).find(|&i| is_ear(i)) else {
+            // Rounding errors can hide the ears of a nearly degenerate ring, which is fanned out instead.
+            for k in 1..len - 1 {
+                res.push(Triangle::new(ring[0], ring[k], ring[k + 1]));
+            }
+            return;
+        };
+        let (a, b, c) = corner(i);
+        res.push(Triangle::new(a, b, c));
+        ring.remove(i);
+    }
+    if let [a, b, c] = ring[..] {
+        if fgt(cross(a, b, c), 0.) {
+            res.push(Triangle::new(a, b, c));
+        }
+    }
+}
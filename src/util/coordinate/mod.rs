@@ -1,124 +1,20 @@
-use core::ops::{Add, Div, Mul, Sub};
+use geo_types::Coord;
 
 use crate::util::{feq, Ray};
 
-/// This structure conceptually represents a point or a vector on
-/// the 2-dimensional Cartesian plane.
+/// The former point and vector type of this crate, which is now [`geo::Coord`] itself.
 ///
-/// It may be vary on the context which represents which.
-#[derive(Clone, Default, Debug, Copy, PartialEq, PartialOrd)]
-pub struct Coordinate(
-    /// x-component of the Cartesian coordinates.
-    pub f64,
-    /// y-component of the Cartesian coordinates.
-    pub f64,
-);
-
-impl From<Coordinate> for (f64, f64) {
-    fn from(item: Coordinate) -> (f64, f64) {
-        (item.0, item.1)
-    }
-}
-
-impl From<(f64, f64)> for Coordinate {
-    fn from(item: (f64, f64)) -> Coordinate {
-        Coordinate(item.0, item.1)
-    }
-}
-
-impl From<geo_types::Coord<f64>> for Coordinate {
-    fn from(value: geo_types::Coord<f64>) -> Self {
-        Coordinate(value.x, value.y)
-    }
-}
-
-impl From<Coordinate> for geo_types::Coord<f64> {
-    fn from(value: Coordinate) -> geo_types::Coord<f64> {
-        geo_types::geometry::Coord {
-            x: value.0,
-            y: value.1,
-        }
-    }
-}
-
-impl Add for Coordinate {
-    type Output = Self;
-    fn add(self, rhs: Self) -> Self {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-impl Sub for Coordinate {
-    type Output = Self;
-    fn sub(self, rhs: Self) -> Self {
-        Self(self.0 - rhs.0, self.1 - rhs.1)
-    }
-}
-
-impl Mul<f64> for Coordinate {
-    type Output = Self;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self(self.0 * rhs, self.1 * rhs)
-    }
-}
-
-impl Div<f64> for Coordinate {
-    type Output = Self;
-    fn div(self, rhs: f64) -> Self::Output {
-        if rhs == 0. {
-            return self;
-        }
-        Self(self.0 / rhs, self.1 / rhs)
-    }
-}
-
-impl Div<Coordinate> for Coordinate {
-    type Output = f64;
-    fn div(self, rhs: Self) -> Self::Output {
-        if rhs.0 == 0. && rhs.1 == 0. {
-            0.
-        } else if rhs.1 == 0. {
-            return self.0 / rhs.0;
-        } else {
-            return self.1 / rhs.1;
-        }
-    }
-}
-
-impl Coordinate {
-    /// Creates and returns a [Coordinate] w.r.t. the given argument.
-    ///
-    /// # Argument
-    ///
-    /// + `x`: x-component of the `Coordinate`.
-    /// + `y`: y-component of the `Coordinate`.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let c1 = geo_buf::Coordinate::new(3., 4.);
-    /// assert_eq!(c1, (3., 4.).into());
-    /// ```
-    #[inline]
-    #[must_use]
-    pub const fn new(x: f64, y: f64) -> Self {
-        Self(x, y)
-    }
-
-    /// Returns a tuple which has values of each component.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let c1 = geo_buf::Coordinate::new(3., 4.);
-    /// let t1 = c1.get_val();
-    /// assert_eq!(t1, (3., 4.));
-    /// ```
-    #[inline]
-    pub const fn get_val(&self) -> (f64, f64) {
-        (self.0, self.1)
-    }
+/// The vector operations of the former type are provided by the [`CoordExt`] trait.
+#[deprecated(note = "use `geo::Coord` together with the `CoordExt` trait")]
+pub type Coordinate = Coord<f64>;
 
+/// This trait provides the vector operations this crate needs on [`geo::Coord`], which
+/// conceptually represents a point or a vector on the 2-dimensional Cartesian plane.
+///
+/// It may be vary on the context which represents which.
+///
+/// The arithmetic operators (`+`, `-`, and `*`, `/` by a scalar) are provided by [`geo::Coord`] itself.
+pub trait CoordExt {
     /// Returns a value of inner product (i.e. dot product) of the Cartesian coordinates of
     /// two vectors.
     ///
@@ -134,8 +30,11 @@ impl Coordinate {
     /// # Example
     ///
     /// ```
-    /// let c1 = geo_buf::Coordinate::new(1., 2.);
-    /// let c2 = geo_buf::Coordinate::new(3., 4.);
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: 1., y: 2. };
+    /// let c2 = coord! { x: 3., y: 4. };
     /// let ip = c1.inner_product(&c2);
     /// assert_eq!(ip, 11.);
     /// ```
@@ -145,9 +44,7 @@ impl Coordinate {
     /// + This operation is linear.
     /// + This operation is commutative.
     ///
-    pub fn inner_product(&self, rhs: &Self) -> f64 {
-        self.0 * rhs.0 + self.1 * rhs.1
-    }
+    fn inner_product(&self, rhs: &Self) -> f64;
 
     /// Returns a value of the magnitude of cross product of the Cartesian coordinates of
     /// two vectors.
@@ -164,8 +61,11 @@ impl Coordinate {
     /// # Example
     ///
     /// ```
-    /// let c1 = geo_buf::Coordinate::new(1., 2.);
-    /// let c2 = geo_buf::Coordinate::new(3., 4.);
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: 1., y: 2. };
+    /// let c2 = coord! { x: 3., y: 4. };
     /// let op = c1.outer_product(&c2);
     /// assert_eq!(op, -2.);
     /// ```
@@ -178,34 +78,49 @@ impl Coordinate {
     ///   the counter-clockwise (CCW for short) ordering, the sign of the result will be positive. If **a** lies after **b** in CCW ordering,
     ///   the sign will be negative. The result will be zero if two vectors are co-linear. (I.e. lay on the same line.)
     ///
-    pub fn outer_product(&self, rhs: &Self) -> f64 {
-        self.0 * rhs.1 - self.1 * rhs.0
-    }
+    fn outer_product(&self, rhs: &Self) -> f64;
 
     /// Returns the Euclidean norm (i.e. magnitude, or L2 norm) of the given vector.
     ///
     /// # Example
     ///
     /// ```
-    /// let c1 = geo_buf::Coordinate::new(3., 4.);
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: 3., y: 4. };
     /// assert_eq!(c1.norm(), 5.);
     /// ```
-    pub fn norm(&self) -> f64 {
-        self.inner_product(self).sqrt()
-    }
+    fn norm(&self) -> f64;
+
+    /// Returns the angle (in radian) of the given vector, measured counter-clockwise from the positive x-axis.
+    ///
+    /// The result lies in the range \[-π, π\], see [`f64::atan2`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: -1., y: 0. };
+    /// assert_eq!(c1.angle(), std::f64::consts::PI);
+    /// ```
+    fn angle(&self) -> f64;
 
     /// Returns the distance between two Cartesian coordinates.
     ///
     /// # Example
     ///
     /// ```
-    /// let c1 = geo_buf::Coordinate::new(3., 4.);
-    /// let c2 = geo_buf::Coordinate::new(7., 7.);
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: 3., y: 4. };
+    /// let c2 = coord! { x: 7., y: 7. };
     /// assert_eq!(c1.dist_coord(&c2), 5.);
     /// ```
-    pub fn dist_coord(&self, rhs: &Coordinate) -> f64 {
-        f64::sqrt((self.0 - rhs.0) * (self.0 - rhs.0) + (self.1 - rhs.1) * (self.1 - rhs.1))
-    }
+    fn dist_coord(&self, rhs: &Self) -> f64;
 
     /// Returns the distance from `self` to the given ray.
     ///
@@ -215,19 +130,15 @@ impl Coordinate {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo::coord;
+    /// use geo_buf::{CoordExt, Ray};
     ///
-    /// let r1 = Ray::new((0., 3.).into(), (4., 0.).into());
-    /// let c1 = Coordinate::new(0., 0.);
+    /// let r1 = Ray::new(coord! { x: 0., y: 3. }, coord! { x: 4., y: 0. });
+    /// let c1 = coord! { x: 0., y: 0. };
     /// assert_eq!(c1.dist_ray(&r1), 2.4);
     /// ```
     ///
-    pub fn dist_ray(&self, rhs: &Ray) -> f64 {
-        if rhs.is_degenerated() {
-            return self.dist_coord(&rhs.origin);
-        }
-        f64::abs((*self - rhs.origin).outer_product(&rhs.angle)) / rhs.angle.norm()
-    }
+    fn dist_ray(&self, rhs: &Ray) -> f64;
 
     /// Checks whether the given two Cartesian coordinates are the same (by the equality test with a small epsilon).
     ///
@@ -239,23 +150,78 @@ impl Coordinate {
     /// # Example
     ///
     /// ```
-    /// let c1 = geo_buf::Coordinate::new(0.1, 0.2);
-    /// let c2 = geo_buf::Coordinate::new(0.2, 0.3);
-    /// let c3 = geo_buf::Coordinate::new(0.3, 0.5);
+    /// use geo::coord;
+    /// use geo_buf::CoordExt;
+    ///
+    /// let c1 = coord! { x: 0.1, y: 0.2 };
+    /// let c2 = coord! { x: 0.2, y: 0.3 };
+    /// let c3 = coord! { x: 0.3, y: 0.5 };
     /// let c4 = c1 + c2;
-    /// assert!(c3.eq(&c4));
+    /// assert!(c3.approx_eq(&c4));
     /// ```
     ///
     /// # Example (this example panics)
     ///
     /// ```should_panic
-    /// let c1 = geo_buf::Coordinate::new(0.1, 0.2);
-    /// let c2 = geo_buf::Coordinate::new(0.2, 0.3);
-    /// let c3 = geo_buf::Coordinate::new(0.3, 0.5);
+    /// use geo::coord;
+    ///
+    /// let c1 = coord! { x: 0.1, y: 0.2 };
+    /// let c2 = coord! { x: 0.2, y: 0.3 };
+    /// let c3 = coord! { x: 0.3, y: 0.5 };
     /// let c4 = c1 + c2;
     /// assert_eq!(c3, c4); // should panic since 0.1 + 0.2 != 0.3 due to floating point errors
     /// ```
-    pub fn eq(&self, rhs: &Self) -> bool {
-        feq(self.0, rhs.0) && feq(self.1, rhs.1)
+    fn approx_eq(&self, rhs: &Self) -> bool;
+}
+
+impl CoordExt for Coord<f64> {
+    fn inner_product(&self, rhs: &Self) -> f64 {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    fn outer_product(&self, rhs: &Self) -> f64 {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    fn norm(&self) -> f64 {
+        self.inner_product(self).sqrt()
+    }
+
+    fn angle(&self) -> f64 {
+        f64::atan2(self.y, self.x)
+    }
+
+    fn dist_coord(&self, rhs: &Self) -> f64 {
+        f64::sqrt((self.x - rhs.x) * (self.x - rhs.x) + (self.y - rhs.y) * (self.y - rhs.y))
+    }
+
+    fn dist_ray(&self, rhs: &Ray) -> f64 {
+        if rhs.is_degenerated() {
+            return self.dist_coord(&rhs.origin);
+        }
+        f64::abs((*self - rhs.origin).outer_product(&rhs.angle)) / rhs.angle.norm()
+    }
+
+    fn approx_eq(&self, rhs: &Self) -> bool {
+        feq(self.x, rhs.x) && feq(self.y, rhs.y)
+    }
+}
+
+/// Returns the ratio `t` such that `a` = `t` * `b`, assuming that `a` and `b` are parallel. Returns 0 if `b` is the zero vector.
+pub(crate) fn ratio(a: Coord, b: Coord) -> f64 {
+    if b.x == 0. && b.y == 0. {
+        0.
+    } else if b.y == 0. {
+        a.x / b.x
+    } else {
+        a.y / b.y
+    }
+}
+
+/// Returns `c` divided by `divisor`, or `c` itself if `divisor` is 0.
+pub(crate) fn div_or_keep(c: Coord, divisor: f64) -> Coord {
+    if divisor == 0. {
+        return c;
     }
+    c / divisor
 }
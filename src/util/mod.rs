@@ -5,7 +5,10 @@
 mod coordinate;
 mod ray;
 
+pub use coordinate::CoordExt;
+#[allow(deprecated)]
 pub use coordinate::Coordinate;
+pub(crate) use coordinate::{div_or_keep, ratio};
 pub use ray::Ray;
 
 const EPS: f64 = 1e-9;
@@ -25,7 +28,6 @@ pub(crate) fn fgt(x: f64, y: f64) -> bool {
     x > y
 }
 
-#[allow(dead_code)]
 pub(crate) fn flt(x: f64, y: f64) -> bool {
     if feq(x, y) {
         return false;
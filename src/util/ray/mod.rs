@@ -1,4 +1,5 @@
 use crate::util::*;
+use geo_types::Coord;
 use std::fmt;
 
 /// This structure conceptually represents a half-line (which also known as "Ray").
@@ -17,9 +18,10 @@ use std::fmt;
 /// We can also think of a ray as the locus of a moving point at a constant velocity from the starting point **r<sub>0</sub>** as time passes.
 /// In this case, the location of the point after time *t* (*t* ≥ 0) is equal to **r<sub>0</sub>** + *t***v**.
 #[derive(Clone, Default, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ray {
-    pub(crate) origin: Coordinate,
-    pub(crate) angle: Coordinate,
+    pub(crate) origin: Coord,
+    pub(crate) angle: Coord,
 }
 
 impl fmt::Display for Ray {
@@ -27,11 +29,17 @@ impl fmt::Display for Ray {
         write!(
             f,
             "Origin : (x, y) = ({}, {}) / Angle : (dx, dy) = ({}, {})",
-            self.origin.0, self.origin.1, self.angle.0, self.angle.1
+            self.origin.x, self.origin.y, self.angle.x, self.angle.y
         )
     }
 }
 
+impl From<geo_types::Line<f64>> for Ray {
+    fn from(value: geo_types::Line<f64>) -> Self {
+        Ray::new(value.start, value.end)
+    }
+}
+
 impl Ray {
     /// Creates and returns a [Ray] w.r.t. the given arguments.
     ///  
@@ -47,7 +55,7 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (1., 2.).into();
     /// let c2 = (2., 3.).into();
@@ -55,28 +63,92 @@ impl Ray {
     ///
     /// ```
     ///
-    pub fn new(src: Coordinate, dst: Coordinate) -> Self {
+    pub fn new(src: Coord, dst: Coord) -> Self {
         Self {
             origin: src,
             angle: dst - src,
         }
     }
 
+    /// Creates and returns a [Ray] which starts from `origin` and moves by `direction` per unit of time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::from_direction((1., 2.).into(), (0., 3.).into());
+    ///
+    /// assert!(r1.point_by_ratio(1.).approx_eq(&(1., 5.).into()));
+    /// ```
+    pub fn from_direction(origin: Coord, direction: Coord) -> Self {
+        Self {
+            origin,
+            angle: direction,
+        }
+    }
+
+    /// Returns the "starting point" of the given ray. This is the same as [`Ray::point`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1: Ray = geo::Line::new((1., 2.), (2., 3.)).into();
+    ///
+    /// assert!(r1.origin().approx_eq(&(1., 2.).into()));
+    /// ```
+    pub fn origin(&self) -> Coord {
+        self.origin
+    }
+
+    /// Returns the "direction vector" **v** of the given ray, i.e. the displacement of the moving point per unit of time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::new((1., 2.).into(), (2., 4.).into());
+    ///
+    /// assert!(r1.direction().approx_eq(&(1., 2.).into()));
+    /// ```
+    pub fn direction(&self) -> Coord {
+        self.angle
+    }
+
+    /// Returns the angle (in radian) of the direction of the given ray, measured counter-clockwise from the positive x-axis.
+    /// See [`CoordExt::angle`](crate::CoordExt::angle).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::new((1., 1.).into(), (1., 3.).into());
+    ///
+    /// assert_eq!(r1.angle(), std::f64::consts::FRAC_PI_2);
+    /// ```
+    pub fn angle(&self) -> f64 {
+        self.angle.angle()
+    }
+
     /// Returns the "starting point" of the given ray.
     ///
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (1., 2.).into();
     /// let c2 = (2., 3.).into();
     /// let r1 = Ray::new(c1, c2);
     ///
-    /// assert!(c1.eq(&r1.point()));
+    /// assert!(c1.approx_eq(&r1.point()));
     ///
     /// ```
-    pub fn point(&self) -> Coordinate {
+    pub fn point(&self) -> Coord {
         self.point_by_ratio(0.)
     }
 
@@ -85,22 +157,40 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (1., 2.).into();
     /// let c2 = (2., 3.).into();
     /// let r1 = Ray::new(c1, c2);
     ///
-    /// assert!(r1.point_by_ratio(2.).eq(&(3., 4.).into()));
+    /// assert!(r1.point_by_ratio(2.).approx_eq(&(3., 4.).into()));
     /// ```
-    pub fn point_by_ratio(&self, ratio: f64) -> Coordinate {
+    pub fn point_by_ratio(&self, ratio: f64) -> Coord {
         self.origin + self.angle * ratio
     }
 
-    pub(crate) fn bisector(&self, rhs: &Ray, origin: Coordinate, orient: bool) -> Self {
+    /// Returns the ray starting from `origin` along the bisector of the angle between the directions of `self` and `rhs`.
+    ///
+    /// The bisector is chosen to lie on the left of `self` if `orient` is `false`, and on its right otherwise.
+    /// If the given rays point in opposite directions, the returned ray is perpendicular to `self`.
+    /// The magnitude of the direction of the returned ray is unspecified; see [`Ray::normalize`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::new((0., 0.).into(), (1., 0.).into());
+    /// let r2 = Ray::new((0., 0.).into(), (0., 1.).into());
+    /// let mut r3 = r1.bisector(&r2, (0., 0.).into(), false);
+    /// r3.normalize();
+    ///
+    /// assert!(r3.point_by_ratio(1.).approx_eq(&(0.5_f64.sqrt(), 0.5_f64.sqrt()).into()));
+    /// ```
+    pub fn bisector(&self, rhs: &Ray, origin: Coord, orient: bool) -> Self {
         let mut ray = self.angle * rhs.angle.norm() + rhs.angle * self.angle.norm();
-        if feq(ray.0, 0.) && feq(ray.1, 0.) {
-            ray = (-self.angle.1, self.angle.0).into();
+        if feq(ray.x, 0.) && feq(ray.y, 0.) {
+            ray = (-self.angle.y, self.angle.x).into();
             if orient {
                 ray = ray * -1.;
             }
@@ -132,7 +222,7 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (1., 2.).into();
     /// let c2 = (2., 3.).into();
@@ -140,9 +230,9 @@ impl Ray {
     ///
     /// assert!(r1.is_contain(&(3., 4.).into()));
     /// ```
-    pub fn is_contain(&self, rhs: &Coordinate) -> bool {
+    pub fn is_contain(&self, rhs: &Coord) -> bool {
         if self.is_degenerated() {
-            return feq(self.origin.0, rhs.0) && feq(self.origin.1, rhs.1);
+            return self.origin.approx_eq(rhs);
         }
         feq((*rhs - self.origin).outer_product(&self.angle), 0.)
     }
@@ -158,7 +248,7 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (1., 2.).into();
     /// let c2 = (2., 3.).into();
@@ -196,7 +286,7 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (0., 0.).into();
     /// let c2 = (1., 1.).into();
@@ -205,14 +295,14 @@ impl Ray {
     /// let r1 = Ray::new(c1, c2);
     /// let r2 = Ray::new(c3, c4);
     ///
-    /// assert!(r1.intersect(&r2).eq(&(2., 2.).into()));
+    /// assert!(r1.intersect(&r2).approx_eq(&(2., 2.).into()));
     ///
     /// ```
-    pub fn intersect(&self, rhs: &Ray) -> Coordinate {
+    pub fn intersect(&self, rhs: &Ray) -> Coord {
         let op = self.angle.outer_product(&rhs.angle);
         if feq(op, 0.) {
             if self.is_contain(&rhs.origin) {
-                if fgt((rhs.origin - self.origin) / self.angle, 0.) {
+                if fgt(ratio(rhs.origin - self.origin, self.angle), 0.) {
                     return rhs.origin;
                 } else {
                     return self.origin;
@@ -225,6 +315,46 @@ impl Ray {
         self.origin + self.angle * i
     }
 
+    /// Returns a common point of the given rays, or `None` if they have none.
+    ///
+    /// Unlike [`Ray::intersect`], this function does not consider the extended lines of the rays.
+    /// If the rays lie on the same line and have more than one common point, it returns the starting
+    /// point of one of them which lies on the other.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::new((0., 0.).into(), (1., 1.).into());
+    /// let r2 = Ray::new((4., 0.).into(), (0., 4.).into());
+    /// let r3 = r2.reverse();
+    ///
+    /// assert!(r1.intersection(&r2).unwrap().approx_eq(&(2., 2.).into()));
+    /// assert!(r1.intersection(&r3).is_none());
+    /// ```
+    pub fn intersection(&self, rhs: &Ray) -> Option<Coord> {
+        let op = self.angle.outer_product(&rhs.angle);
+        if feq(op, 0.) {
+            if !self.is_contain(&rhs.origin) || !rhs.is_contain(&self.origin) {
+                return None;
+            }
+            if fgeq(ratio(rhs.origin - self.origin, self.angle), 0.) {
+                return Some(rhs.origin);
+            }
+            if fgeq(ratio(self.origin - rhs.origin, rhs.angle), 0.) {
+                return Some(self.origin);
+            }
+            return None;
+        }
+        let i = (rhs.origin - self.origin).outer_product(&rhs.angle) / op;
+        let j = (rhs.origin - self.origin).outer_product(&self.angle) / op;
+        if fgeq(i, 0.) && fgeq(j, 0.) {
+            return Some(self.origin + self.angle * i);
+        }
+        None
+    }
+
     /// Checks whether the given two rays are parallel. If they have more than 2 common points,
     /// they are not considered as parallel.
     ///
@@ -236,7 +366,7 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (0., 0.).into();
     /// let c2 = (1., 1.).into();
@@ -255,8 +385,19 @@ impl Ray {
         false
     }
 
-    pub(crate) fn is_degenerated(&self) -> bool {
-        feq(self.angle.0, 0.) && feq(self.angle.1, 0.)
+    /// Checks whether the direction of the given ray is the zero vector, i.e. whether the ray is a single point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let c1 = (1., 2.).into();
+    ///
+    /// assert!(Ray::new(c1, c1).is_degenerated());
+    /// ```
+    pub fn is_degenerated(&self) -> bool {
+        feq(self.angle.x, 0.) && feq(self.angle.y, 0.)
     }
 
     /// Normalizes the given `Ray`. The magnitude of the 'velocity' becomes 1. Does nothing if it is 0.
@@ -264,14 +405,14 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (0., 0.).into();
     /// let c2 = (3., 4.).into();
     /// let mut r1 = Ray::new(c1, c2);
     /// r1.normalize();
     ///
-    /// assert!(r1.point_by_ratio(1.).eq(&(0.6, 0.8).into()));
+    /// assert!(r1.point_by_ratio(1.).approx_eq(&(0.6, 0.8).into()));
     /// ```
     pub fn normalize(&mut self) {
         if self.is_degenerated() {
@@ -280,7 +421,26 @@ impl Ray {
         self.angle = self.angle / self.angle.norm();
     }
 
-    pub(crate) fn orientation(&self, rhs: &Coordinate) -> i32 {
+    /// Returns on which side of the extended line of the given ray the given point lies.
+    ///
+    /// # Return
+    ///
+    /// + `1` if the point lies on the left of the ray (counter-clockwise from its direction),
+    /// + `-1` if the point lies on the right of the ray,
+    /// + `0` if the point lies on the extended line of the ray.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use geo_buf::{CoordExt, Ray};
+    ///
+    /// let r1 = Ray::new((0., 0.).into(), (1., 0.).into());
+    ///
+    /// assert_eq!(r1.orientation(&(0., 1.).into()), 1);
+    /// assert_eq!(r1.orientation(&(0., -1.).into()), -1);
+    /// assert_eq!(r1.orientation(&(-5., 0.).into()), 0);
+    /// ```
+    pub fn orientation(&self, rhs: &Coord) -> i32 {
         let res = self.angle.outer_product(&(*rhs - self.origin));
         if feq(res, 0.) {
             return 0;
@@ -297,14 +457,14 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (0., 0.).into();
     /// let c2 = (3., 4.).into();
     /// let r1 = Ray::new(c1, c2);
     /// let r2 = r1.reverse();
     ///
-    /// assert!(r2.point_by_ratio(1.).eq(&(-3., -4.).into()));
+    /// assert!(r2.point_by_ratio(1.).approx_eq(&(-3., -4.).into()));
     /// ```
     pub fn reverse(&self) -> Self {
         Self {
@@ -319,18 +479,18 @@ impl Ray {
     /// # Example
     ///
     /// ```
-    /// use geo_buf::{Coordinate, Ray};
+    /// use geo_buf::{CoordExt, Ray};
     ///
     /// let c1 = (0., 0.).into();
     /// let c2 = (3., 4.).into();
     /// let r1 = Ray::new(c1, c2);
     /// let r2 = r1.rotate_by(std::f64::consts::PI/2.);
     ///
-    /// assert!(r2.point_by_ratio(1.).eq(&(-4., 3.).into()));
+    /// assert!(r2.point_by_ratio(1.).approx_eq(&(-4., 3.).into()));
     /// ```
     pub fn rotate_by(&self, angle: f64) -> Self {
-        let nx = self.angle.0 * f64::cos(angle) - self.angle.1 * f64::sin(angle);
-        let ny = self.angle.0 * f64::sin(angle) + self.angle.1 * f64::cos(angle);
+        let nx = self.angle.x * f64::cos(angle) - self.angle.y * f64::sin(angle);
+        let ny = self.angle.x * f64::sin(angle) + self.angle.y * f64::cos(angle);
         Self {
             origin: self.origin,
             angle: (nx, ny).into(),
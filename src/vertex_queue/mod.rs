@@ -3,6 +3,7 @@ use core::fmt;
 use geo_types::Polygon;
 
 #[derive(Clone, Debug, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum IndexType {
     PointerIndex(usize),
     RealIndex(usize),
@@ -34,11 +35,15 @@ impl IndexType {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Node {
     pub(crate) index: IndexType,
     pub(crate) left: IndexType,
     pub(crate) right: IndexType,
     pub(crate) done: bool,
+    /// Incremented whenever the vertex of this node is replaced or the node is removed,
+    /// so that events referring to an older state of the node can be discarded lazily.
+    pub(crate) generation: u32,
 }
 
 impl Node {
@@ -48,6 +53,7 @@ impl Node {
             left: IndexType::PointerIndex(left),
             right: IndexType::PointerIndex(right),
             done: false,
+            generation: 0,
         }
     }
 
@@ -62,74 +68,99 @@ impl Node {
     }
 }
 
+/// A state of a `VertexQueue` that can be restored with [`VertexQueue::rollback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+    journal_len: usize,
+    content_len: usize,
+}
+
+/// The wavefront, as circular doubly linked lists of nodes (one list per ring) stored in a slab.
+///
+/// Nodes are never moved nor freed: a removed node is marked as done and its generation is incremented,
+/// so indices into the slab stay valid and events referring to a removed node can tell. Removing and
+/// splitting only relink the neighbouring nodes, so the rings are found by following the links when iterating.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct VertexQueue {
     pub(crate) content: Vec<Node>,
-    pub(crate) start_vertex: Vec<usize>,
+    /// The previous states of the nodes changed since the first checkpoint, or `None` if no checkpoint has been taken.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    journal: Option<Vec<(usize, Node)>>,
 }
 
 impl VertexQueue {
     pub(crate) const fn new() -> Self {
         Self {
             content: Vec::new(),
-            start_vertex: Vec::new(),
+            journal: None,
         }
     }
 
-    pub(crate) fn initialize_from_polygon(&mut self, p: &Polygon) {
-        let offset = self.content.len();
-        let len = p.exterior().0.len() - 1;
+    /// Records the current state, after which every change is journaled until it is rolled back.
+    pub(crate) fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint {
+            journal_len: self.journal.get_or_insert_with(Vec::new).len(),
+            content_len: self.content.len(),
+        }
+    }
 
-        self.start_vertex.push(offset);
-        for i in 0..len {
-            let new_node = Node::new(
-                i + offset,
-                (i + len - 1) % len + offset,
-                (i + 1) % len + offset,
-            );
-            self.content.push(new_node);
+    /// Restores the state at the checkpoint `cp`, undoing every change made since.
+    ///
+    /// Checkpoints taken after `cp` become invalid.
+    pub(crate) fn rollback(&mut self, cp: Checkpoint) {
+        let journal = self
+            .journal
+            .as_mut()
+            .expect("rollback called without a checkpoint");
+        for (i, node) in journal.drain(cp.journal_len..).rev() {
+            self.content[i] = node;
         }
-        for i in 0..p.interiors().len() {
-            let offset = self.content.len();
-            let len = p.interiors()[i].0.len() - 1;
-            self.start_vertex.push(offset);
-            for j in 0..len {
-                let new_node = Node::new(
-                    j + offset,
-                    (j + len - 1) % len + offset,
-                    (j + 1) % len + offset,
-                );
-                self.content.push(new_node);
-            }
+        self.content.truncate(cp.content_len);
+    }
+
+    /// Returns a copy of the current state, without the journal.
+    pub(crate) fn snapshot(&self) -> Self {
+        Self {
+            content: self.content.clone(),
+            journal: None,
         }
     }
 
-    pub(crate) fn initialize_from_polygon_vector(&mut self, pv: &Vec<Polygon>) {
-        for p in pv {
-            let offset = self.content.len();
-            let len = p.exterior().0.len() - 1;
-            self.start_vertex.push(offset);
-            for i in 0..len {
-                let new_node = Node::new(
-                    i + offset,
-                    (i + len - 1) % len + offset,
-                    (i + 1) % len + offset,
-                );
-                self.content.push(new_node);
-            }
-            for i in 0..p.interiors().len() {
-                let offset = self.content.len();
-                let len = p.interiors()[i].0.len() - 1;
-                self.start_vertex.push(offset);
-                for j in 0..len {
-                    let new_node = Node::new(
-                        j + offset,
-                        (j + len - 1) % len + offset,
-                        (j + 1) % len + offset,
-                    );
-                    self.content.push(new_node);
-                }
-            }
+    /// Drops the journal, invalidating every checkpoint.
+    pub(crate) fn clear_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Returns the node at `i` for modification, journaling its current state first.
+    fn node_mut(&mut self, i: usize) -> &mut Node {
+        if let Some(journal) = &mut self.journal {
+            journal.push((i, self.content[i]));
+        }
+        &mut self.content[i]
+    }
+
+    pub(crate) fn initialize_from_polygon_vector(&mut self, pv: &[Polygon]) {
+        let rings = || {
+            pv.iter()
+                .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        };
+        self.content
+            .reserve(rings().map(|ls| ls.0.len() - 1).sum::<usize>());
+        for ls in rings() {
+            self.push_ring(ls.0.len() - 1);
+        }
+    }
+
+    /// Pushes a new ring of `len` nodes, indexed in the order they are pushed.
+    fn push_ring(&mut self, len: usize) {
+        let offset = self.content.len();
+        for i in 0..len {
+            self.content.push(Node::new(
+                i + offset,
+                (i + len - 1) % len + offset,
+                (i + 1) % len + offset,
+            ));
         }
     }
 
@@ -168,19 +199,42 @@ impl VertexQueue {
         self.rv(cv)
     }
 
+    /// Returns the generation of the node `cv`.
+    pub(crate) fn generation(&self, cv: IndexType) -> u32 {
+        self.content[cv.get_index()].generation
+    }
+
+    /// Checks whether the node `cv` is alive and has not been changed since it had the generation `generation`.
+    pub(crate) fn is_current(&self, cv: IndexType, generation: u32) -> bool {
+        let node = &self.content[cv.get_index()];
+        !node.done && node.generation == generation
+    }
+
+    pub(crate) fn mark_done(&mut self, cv: IndexType) {
+        let node = self.node_mut(cv.get_index());
+        node.done = true;
+        node.generation += 1;
+    }
+
+    fn set_index(&mut self, cv: IndexType, nv: IndexType) {
+        let node = self.node_mut(cv.get_index());
+        node.index = nv;
+        node.generation += 1;
+    }
+
     pub(crate) fn remove(&mut self, cv: IndexType) -> IndexType {
         let tl = self.lv(cv);
         let tr = self.rv(cv);
-        self.content[tl.get_index()].right = tr;
-        self.content[tr.get_index()].left = tl;
-        self.content[cv.get_index()].done = true;
+        self.node_mut(tl.get_index()).right = tr;
+        self.node_mut(tr.get_index()).left = tl;
+        self.mark_done(cv);
         tr
     }
 
     pub(crate) fn remove_and_set(&mut self, cv: IndexType, nv: IndexType) -> IndexType {
         let cv = self.remove(cv);
         if let IndexType::RealIndex(_) = nv {
-            self.content[cv.get_index()].index = nv;
+            self.set_index(cv, nv);
         } else {
             panic!("Expected parameter \"nv\" as IndexType::RealIndex");
         }
@@ -198,63 +252,59 @@ impl VertexQueue {
         let new_index = IndexType::PointerIndex(self.content.len());
         self.content.push(new_node);
         if let IndexType::RealIndex(_) = nv1 {
-            self.content[cv.get_index()].index = nv1;
+            self.set_index(cv, nv1);
         } else {
             panic!("Expected parameter \"nv1\" as IndexType::RealIndex");
         }
         if let IndexType::RealIndex(_) = nv2 {
-            self.content[new_index.get_index()].index = nv2;
+            self.set_index(new_index, nv2);
         } else {
             panic!("Expected parameter \"nv2\" as IndexType::RealIndex");
         }
         let svx = self.rv(sv); // right of sv (split vertex)
         let cvx = self.rv(cv); // right of cv (current (anchor) vertex)
-        self.content[cvx.get_index()].left = new_index;
-        self.content[sv.get_index()].right = new_index;
-        self.content[cv.get_index()].right = svx;
-        self.content[svx.get_index()].left = cv;
-        self.start_vertex.push(cv.get_index());
-        self.start_vertex.push(new_index.get_index());
+        self.node_mut(cvx.get_index()).left = new_index;
+        self.node_mut(sv.get_index()).right = new_index;
+        self.node_mut(cv.get_index()).right = svx;
+        self.node_mut(svx.get_index()).left = cv;
         (cv, new_index)
     }
 
-    pub(crate) fn cleanup(&mut self) {
-        let mut sv_idx = 0;
-        let mut visit = vec![false; self.content.len()];
-        while sv_idx < self.start_vertex.len() {
-            let mut cur = self.start_vertex[sv_idx];
-            while self.content[cur].done && !visit[cur] {
-                visit[cur] = true;
-                cur = self.content[cur].right.get_index();
-            }
-            if visit[cur]
-                || self.content[cur].left.get_index() == self.content[cur].right.get_index()
-            {
-                self.start_vertex.swap_remove(sv_idx);
-                continue;
-            }
-            self.start_vertex[sv_idx] = cur;
-            visit[cur] = true;
-            cur = self.content[cur].right.get_index();
-            while cur != self.start_vertex[sv_idx] {
-                if visit[cur] {
-                    panic!(
-                        "Something Wrong in cleanup phase: cur {} from {}, sv {:?}",
-                        cur, sv_idx, self.start_vertex
-                    );
-                }
-                visit[cur] = true;
-                cur = self.content[cur].right.get_index();
-            }
-            sv_idx += 1;
-        }
+    /// Checks whether the node `i` is a vertex of a ring which has not collapsed yet.
+    fn is_live(&self, i: usize) -> bool {
+        let node = &self.content[i];
+        !node.done && node.left != node.right
     }
 
+    /// Returns the nodes of every ring that has not collapsed yet, in the order of the slab.
+    pub(crate) fn live_nodes(&self) -> impl Iterator<Item = IndexType> + '_ {
+        (0..self.content.len())
+            .filter(|&i| self.is_live(i))
+            .map(IndexType::PointerIndex)
+    }
+
+    /// Returns the first node of every ring that has not collapsed yet, in the order of the slab.
+    pub(crate) fn ring_starts(&self) -> Vec<usize> {
+        self.iter()
+            .scan(usize::MAX, |ring, (r, cv, _)| {
+                let first = *ring != r;
+                *ring = r;
+                Some(first.then(|| cv.get_index()))
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns the nodes of the rings that have not collapsed yet, ring by ring, each ring following the links to the right.
+    ///
+    /// The rings are ordered by their first node in the slab, and each ring starts at that node.
     pub(crate) fn iter(&self) -> Iter<'_> {
         Iter {
             item: self,
-            sv_idx: 0,
-            idx: usize::MAX,
+            visited: vec![false; self.content.len()],
+            next_start: 0,
+            rings: 0,
+            current: None,
         }
     }
 }
@@ -272,30 +322,40 @@ impl fmt::Display for VertexQueue {
 
 pub(crate) struct Iter<'a> {
     item: &'a VertexQueue,
-    sv_idx: usize,
-    idx: usize,
+    visited: Vec<bool>,
+    /// The slab index from which the first node of the next ring is searched.
+    next_start: usize,
+    /// The number of rings started so far.
+    rings: usize,
+    /// The first node of the current ring and the next node to return, if a ring is being walked.
+    current: Option<(usize, usize)>,
 }
 
 impl<'a> Iterator for Iter<'a> {
     type Item = (usize, IndexType, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.idx == usize::MAX {
-            if self.sv_idx >= self.item.start_vertex.len() {
-                return None;
+        let (start, idx) = match self.current {
+            Some(current) => current,
+            None => {
+                let queue = self.item;
+                let start = (self.next_start..queue.content.len())
+                    .find(|&i| !self.visited[i] && queue.is_live(i))?;
+                self.next_start = start + 1;
+                self.rings += 1;
+                (start, start)
             }
-            self.idx = self.item.start_vertex[self.sv_idx];
-        }
-        let ret = match self.item.content[self.idx].index {
+        };
+        let ret = match self.item.content[idx].index {
             IndexType::RealIndex(rv) => rv,
             _ => panic!("Expected IndexType::RealIndex"),
         };
-        let ret = (self.sv_idx, IndexType::PointerIndex(self.idx), ret);
-        self.idx = self.item.content[self.idx].right.get_index();
-        if self.item.start_vertex[self.sv_idx] == self.idx {
-            self.sv_idx += 1;
-            self.idx = usize::MAX;
+        self.visited[idx] = true;
+        let next = self.item.content[idx].right.get_index();
+        if next != start && self.visited[next] {
+            panic!("Something Wrong in the wavefront: the ring of {start} does not close");
         }
-        Some(ret)
+        self.current = (next != start).then_some((start, next));
+        Some((self.rings - 1, IndexType::PointerIndex(idx), ret))
     }
 }
@@ -0,0 +1,94 @@
+//! JavaScript bindings of the buffering functions over flat coordinate arrays. Requires the `wasm-bindgen` feature.
+//!
+//! A multi-polygon is described by three arrays as in the [`flat`](crate::flat) module: `coords`, `ring_ends` and `polygon_ends`.
+//!
+//! # Example
+//!
+//! ```
+//! use geo_buf::wasm::buffer_polygon;
+//!
+//! // The square from (0, 0) to (2, 2) with the hole from (0.5, 0.5) to (1.5, 1.5)
+//! let coords = [0., 0., 2., 0., 2., 2., 0., 2., 0.5, 0.5, 0.5, 1.5, 1.5, 1.5, 1.5, 0.5];
+//! let res = buffer_polygon(&coords, &[4, 8], 0.25).unwrap();
+//! assert_eq!(res.polygon_ends(), vec![2]);
+//! assert_eq!(res.ring_ends(), vec![4, 8]);
+//! assert_eq!(res.coords()[..8], [-0.25, -0.25, 2.25, -0.25, 2.25, 2.25, -0.25, 2.25]);
+//! ```
+
+use geo_types::MultiPolygon;
+use wasm_bindgen::prelude::*;
+
+use crate::flat::{self, FlatBuffer};
+
+/// A multi-polygon in flat arrays, see the [module documentation](self).
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FlatMultiPolygon {
+    coords: Vec<f64>,
+    ring_ends: Vec<u32>,
+    polygon_ends: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl FlatMultiPolygon {
+    /// The x and y coordinates of every vertex.
+    #[wasm_bindgen(getter)]
+    pub fn coords(&self) -> Vec<f64> {
+        self.coords.clone()
+    }
+
+    /// The index of the vertex following the last vertex of each ring.
+    #[wasm_bindgen(getter = ringEnds)]
+    pub fn ring_ends(&self) -> Vec<u32> {
+        self.ring_ends.clone()
+    }
+
+    /// The index of the ring following the last ring of each polygon.
+    #[wasm_bindgen(getter = polygonEnds)]
+    pub fn polygon_ends(&self) -> Vec<u32> {
+        self.polygon_ends.clone()
+    }
+}
+
+/// Buffers the polygon described by `coords` and `ring_ends` by `distance`, see [`buffer_polygon`](crate::buffer_polygon).
+///
+/// # Errors
+///
+/// Returns an error if the arrays do not describe a polygon.
+#[wasm_bindgen(js_name = bufferPolygon)]
+pub fn buffer_polygon(
+    coords: &[f64],
+    ring_ends: &[u32],
+    distance: f64,
+) -> Result<FlatMultiPolygon, JsError> {
+    let polygon_ends = [ring_ends.len() as u32];
+    let input = flat::from_flat(coords, ring_ends, &polygon_ends)?;
+    Ok(to_flat(&crate::buffer_polygon(&input.0[0], distance)))
+}
+
+/// Buffers the multi-polygon described by `coords`, `ring_ends` and `polygon_ends` by `distance`,
+/// see [`buffer_multi_polygon`](crate::buffer_multi_polygon).
+///
+/// # Errors
+///
+/// Returns an error if the arrays do not describe a multi-polygon.
+#[wasm_bindgen(js_name = bufferMultiPolygon)]
+pub fn buffer_multi_polygon(
+    coords: &[f64],
+    ring_ends: &[u32],
+    polygon_ends: &[u32],
+    distance: f64,
+) -> Result<FlatMultiPolygon, JsError> {
+    let input = flat::from_flat(coords, ring_ends, polygon_ends)?;
+    Ok(to_flat(&crate::buffer_multi_polygon(&input, distance)))
+}
+
+fn to_flat(mp: &MultiPolygon) -> FlatMultiPolygon {
+    let mut out = FlatBuffer::default();
+    flat::write_flat(mp, &mut out);
+    FlatMultiPolygon {
+        coords: out.coords,
+        ring_ends: out.ring_ends,
+        polygon_ends: out.polygon_ends,
+    }
+}
@@ -0,0 +1,109 @@
+//! Tests that GeoArrow polygons buffered with `buffer_arrow_polygons` come back as a tagged multi-polygon column holding the
+//! same buffers as `buffer_polygon_with`.
+#![cfg(feature = "arrow")]
+
+use arrow_array::builder::{FixedSizeListBuilder, Float64Builder, ListBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, ListArray};
+use geo_buf::{buffer_arrow_polygons, buffer_polygon_with, BufferOptions};
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+
+fn arrow_of_polygons(polygons: &[Option<Polygon>]) -> ListArray {
+    let mut builder = ListBuilder::new(ListBuilder::new(FixedSizeListBuilder::new(
+        Float64Builder::new(),
+        2,
+    )));
+    for p in polygons {
+        let Some(p) = p else {
+            builder.append_null();
+            continue;
+        };
+        for ls in std::iter::once(p.exterior()).chain(p.interiors()) {
+            for c in ls {
+                builder.values().values().values().append_slice(&[c.x, c.y]);
+                builder.values().values().append(true);
+            }
+            builder.values().append(true);
+        }
+        builder.append(true);
+    }
+    builder.finish()
+}
+
+fn multi_polygons_of_arrow(array: &ListArray) -> Vec<Option<MultiPolygon>> {
+    let ring = |coords: &dyn Array| {
+        let xy = coords.as_fixed_size_list();
+        let values = xy.values().as_primitive::<Float64Type>();
+        LineString::from(
+            (0..xy.len())
+                .map(|i| Coord {
+                    x: values.value(2 * (xy.offset() + i)),
+                    y: values.value(2 * (xy.offset() + i) + 1),
+                })
+                .collect::<Vec<_>>(),
+        )
+    };
+    (0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                return None;
+            }
+            let polygons = array.value(i);
+            let polygons = polygons.as_list::<i32>();
+            Some(MultiPolygon::new(
+                (0..polygons.len())
+                    .map(|j| {
+                        let rings = polygons.value(j);
+                        let rings = rings.as_list::<i32>();
+                        let mut rings = (0..rings.len()).map(|k| ring(rings.value(k).as_ref()));
+                        let exterior = rings.next().unwrap();
+                        Polygon::new(exterior, rings.collect())
+                    })
+                    .collect(),
+            ))
+        })
+        .collect()
+}
+
+#[test]
+fn multi_polygons_round_trip_with_their_extension_name() {
+    let polygons = [
+        Some(Polygon::new(
+            LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (2., 1.), (0., 4.)]),
+            vec![],
+        )),
+        None,
+        Some(Polygon::new(
+            LineString::from(vec![(0., 0.), (10., 0.), (10., 10.), (0., 10.)]),
+            vec![LineString::from(vec![
+                (4., 4.),
+                (4., 6.),
+                (6., 6.),
+                (6., 4.),
+            ])],
+        )),
+        Some(Polygon::new(LineString::new(vec![]), vec![])),
+    ];
+    let options = BufferOptions::new();
+    for distance in [-0.75, 0.5] {
+        let batch =
+            buffer_arrow_polygons(&arrow_of_polygons(&polygons), distance, &options).unwrap();
+
+        let schema = batch.schema();
+        let field = schema.field(0);
+        assert_eq!(field.name(), "geometry");
+        assert_eq!(field.extension_type_name(), Some("geoarrow.multipolygon"));
+        assert_eq!(field.data_type(), batch.column(0).data_type());
+
+        let buffered = multi_polygons_of_arrow(batch.column(0).as_list::<i32>());
+        let expected: Vec<_> = polygons
+            .iter()
+            .map(|p| {
+                p.as_ref()
+                    .map(|p| buffer_polygon_with(p, distance, &options))
+            })
+            .collect();
+        assert_eq!(buffered, expected);
+    }
+}
@@ -0,0 +1,68 @@
+//! Tests that the grid options of `BufferOptions` reject the sizes they cannot snap to.
+
+use std::panic::catch_unwind;
+
+use geo_buf::{buffer_polygon_with, BufferOptions};
+use geo_types::{LineString, Polygon};
+
+const INVALID_SIZES: [f64; 6] = [0., -0., -1., f64::INFINITY, f64::NAN, 1e-320];
+
+#[test]
+fn output_grid_rejects_invalid_sizes() {
+    for size in INVALID_SIZES {
+        assert!(
+            catch_unwind(|| BufferOptions::new().output_grid(size)).is_err(),
+            "{size} was accepted"
+        );
+    }
+}
+
+#[test]
+fn snap_input_rejects_invalid_sizes() {
+    for size in INVALID_SIZES {
+        assert!(
+            catch_unwind(|| BufferOptions::new().snap_input(size)).is_err(),
+            "{size} was accepted"
+        );
+    }
+}
+
+#[test]
+fn output_precision_rejects_invalid_decimals() {
+    for decimals in [-400, -308, 309, 400, i32::MIN, i32::MAX] {
+        assert!(
+            catch_unwind(|| BufferOptions::new().output_precision(decimals)).is_err(),
+            "{decimals} was accepted"
+        );
+    }
+}
+
+#[test]
+fn grids_of_valid_sizes_are_used() {
+    let p = Polygon::new(
+        LineString::from(vec![(0.1, 0.1), (3.9, 0.2), (3.8, 4.1), (0.2, 3.9)]),
+        vec![],
+    );
+    let on_grid = |options: &BufferOptions, size: f64| {
+        buffer_polygon_with(&p, 0.4, options)
+            .0
+            .iter()
+            .flat_map(|q| q.exterior().coords())
+            .all(|c| (c.x / size).fract() == 0. && (c.y / size).fract() == 0.)
+    };
+    for size in [0.25, 1., 2.] {
+        assert!(on_grid(&BufferOptions::new().output_grid(size), size));
+    }
+    assert!(on_grid(&BufferOptions::new().output_precision(0), 1.));
+    assert!(on_grid(&BufferOptions::new().output_precision(-1), 10.));
+    let snapped = buffer_polygon_with(&p, -1., &BufferOptions::new().snap_input(1.));
+    let expected = buffer_polygon_with(
+        &Polygon::new(
+            LineString::from(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]),
+            vec![],
+        ),
+        -1.,
+        &BufferOptions::new(),
+    );
+    assert_eq!(snapped, expected);
+}
@@ -0,0 +1,354 @@
+//! Regression tests for split events of the straight skeleton, checked against a brute-force wavefront simulation.
+
+use geo::{Area, BooleanOps, Contains, Distance, Euclidean};
+use geo_types::{Line, LineString, MultiPolygon, Point, Polygon};
+
+/// A supporting line of an edge, as its inward unit normal and offset.
+#[derive(Clone, Copy)]
+struct Edge {
+    normal: (f64, f64),
+    offset: f64,
+}
+
+impl Edge {
+    fn new(p: (f64, f64), q: (f64, f64)) -> Self {
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let len = dx.hypot(dy);
+        let normal = (-dy / len, dx / len);
+        Self {
+            normal,
+            offset: normal.0 * p.0 + normal.1 * p.1,
+        }
+    }
+}
+
+/// A wavefront vertex between two edges, created at `p` at time `t0`.
+#[derive(Clone, Copy)]
+struct Vertex {
+    prev: Edge,
+    next: Edge,
+    p: (f64, f64),
+    t0: f64,
+    v: (f64, f64),
+}
+
+impl Vertex {
+    fn new(prev: Edge, next: Edge, p: (f64, f64), t0: f64) -> Self {
+        let (n1, n2) = (prev.normal, next.normal);
+        let det = n1.0 * n2.1 - n1.1 * n2.0;
+        let v = if det.abs() < 1e-12 {
+            n1
+        } else {
+            ((n2.1 - n1.1) / det, (n1.0 - n2.0) / det)
+        };
+        Self {
+            prev,
+            next,
+            p,
+            t0,
+            v,
+        }
+    }
+
+    fn at(&self, t: f64) -> (f64, f64) {
+        (
+            self.p.0 + self.v.0 * (t - self.t0),
+            self.p.1 + self.v.1 * (t - self.t0),
+        )
+    }
+
+    fn is_reflex(&self) -> bool {
+        let (a, b) = (self.prev.normal, self.next.normal);
+        a.0 * b.1 - a.1 * b.0 < -1e-12
+    }
+}
+
+/// This function returns the inward offset of a simple counter-clockwise ring by `distance`, found by simulating the
+/// wavefront one event at a time and testing every reflex vertex against every edge.
+fn reference_offset(ring: &[(f64, f64)], distance: f64) -> MultiPolygon {
+    let n = ring.len();
+    let edges: Vec<Edge> = (0..n)
+        .map(|i| Edge::new(ring[i], ring[(i + 1) % n]))
+        .collect();
+    let mut rings: Vec<Vec<Vertex>> = vec![(0..n)
+        .map(|i| Vertex::new(edges[(i + n - 1) % n], edges[i], ring[i], 0.))
+        .collect()];
+    let mut now = 0.;
+    loop {
+        // (time, is split, ring, vertex, edge)
+        let mut best: Option<(f64, bool, usize, usize, usize)> = None;
+        for (ri, r) in rings.iter().enumerate() {
+            let m = r.len();
+            for i in 0..m {
+                let (a, b) = (&r[i], &r[(i + 1) % m]);
+                let u = (a.next.normal.1, -a.next.normal.0);
+                let (pa, pb) = (a.at(now), b.at(now));
+                let len = (pb.0 - pa.0) * u.0 + (pb.1 - pa.1) * u.1;
+                let rate = (b.v.0 - a.v.0) * u.0 + (b.v.1 - a.v.1) * u.1;
+                if rate < -1e-12 {
+                    let t = now - len / rate;
+                    if t >= now - 1e-9 && best.is_none_or(|b| t < b.0) {
+                        best = Some((t, false, ri, i, 0));
+                    }
+                }
+            }
+            for i in (0..m).filter(|&i| r[i].is_reflex()) {
+                let reflex = &r[i];
+                let p = reflex.at(now);
+                for j in (0..m).filter(|&j| j != i && (j + 1) % m != i) {
+                    let (a, b) = (&r[j], &r[(j + 1) % m]);
+                    let normal = a.next.normal;
+                    let den = 1. - (reflex.v.0 * normal.0 + reflex.v.1 * normal.1);
+                    if den <= 1e-12 {
+                        continue;
+                    }
+                    let t = now + (p.0 * normal.0 + p.1 * normal.1 - (a.next.offset + now)) / den;
+                    if t < now - 1e-9 || best.is_some_and(|b| t >= b.0) {
+                        continue;
+                    }
+                    let (q, qa, qb) = (reflex.at(t), a.at(t), b.at(t));
+                    let u = (normal.1, -normal.0);
+                    let from_a = (q.0 - qa.0) * u.0 + (q.1 - qa.1) * u.1;
+                    let from_b = (q.0 - qb.0) * u.0 + (q.1 - qb.1) * u.1;
+                    if from_a > -1e-9 && from_b < 1e-9 {
+                        best = Some((t, true, ri, i, j));
+                    }
+                }
+            }
+        }
+        let Some((t, is_split, ri, i, j)) = best.filter(|b| b.0 <= distance) else {
+            break;
+        };
+        now = t;
+        let r = &mut rings[ri];
+        let m = r.len();
+        if !is_split {
+            if m <= 3 {
+                rings.swap_remove(ri);
+                continue;
+            }
+            let (a, b) = (r[i], r[(i + 1) % m]);
+            r[i] = Vertex::new(a.prev, b.next, a.at(t), t);
+            r.remove((i + 1) % m);
+        } else {
+            let (reflex, a) = (r[i], r[j]);
+            let p = reflex.at(t);
+            let rest: Vec<Vertex> = r[i + 1..].iter().chain(r[..i].iter()).copied().collect();
+            let k = (j + m - i - 1) % m;
+            let mut left = vec![Vertex::new(a.next, reflex.next, p, t)];
+            left.extend_from_slice(&rest[..=k]);
+            let mut right = vec![Vertex::new(reflex.prev, a.next, p, t)];
+            right.extend_from_slice(&rest[k + 1..]);
+            rings.swap_remove(ri);
+            rings.extend([left, right].into_iter().filter(|r| r.len() >= 3));
+        }
+    }
+    MultiPolygon::new(
+        rings
+            .iter()
+            .map(|r| {
+                Polygon::new(
+                    LineString::from(r.iter().map(|v| v.at(distance)).collect::<Vec<_>>()),
+                    vec![],
+                )
+            })
+            .filter(|p| p.signed_area() > 1e-9)
+            .collect(),
+    )
+}
+
+/// A small xorshift generator, so the random polygons are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// This function returns a random star-shaped counter-clockwise ring, which is in general position unless `round`.
+fn star(rng: &mut Rng, n: usize, round: bool) -> Vec<(f64, f64)> {
+    let mut angles: Vec<f64> = (0..n).map(|_| rng.unit() * std::f64::consts::TAU).collect();
+    angles.sort_by(f64::total_cmp);
+    let mut ring: Vec<(f64, f64)> = angles
+        .iter()
+        .map(|a| {
+            let r = 2. + rng.unit() * 12.;
+            let (x, y) = (r * a.cos(), r * a.sin());
+            if round {
+                (x.round(), y.round())
+            } else {
+                (x, y)
+            }
+        })
+        .collect();
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    ring
+}
+
+fn polygon(ring: &[(f64, f64)]) -> Polygon {
+    Polygon::new(LineString::from(ring.to_vec()), vec![])
+}
+
+fn is_simple(p: &Polygon) -> bool {
+    use geo::line_intersection::{line_intersection, LineIntersection};
+    let lines: Vec<Line> = p.exterior().lines().collect();
+    let n = lines.len();
+    (0..n).all(|i| {
+        (i + 1..n).all(|j| match line_intersection(lines[i], lines[j]) {
+            None => true,
+            Some(LineIntersection::SinglePoint { .. }) => j == i + 1 || (i == 0 && j == n - 1),
+            Some(LineIntersection::Collinear { .. }) => false,
+        })
+    })
+}
+
+fn symmetric_difference(a: &MultiPolygon, b: &MultiPolygon) -> f64 {
+    a.difference(b).unsigned_area() + b.difference(a).unsigned_area()
+}
+
+/// This function asserts that every vertex of `offset` lies inside `p`, at least `distance` away from its boundary.
+fn assert_within(p: &Polygon, offset: &MultiPolygon, distance: f64) {
+    let lines: Vec<Line> = p.exterior().lines().collect();
+    for c in offset
+        .iter()
+        .flat_map(|q| std::iter::once(q.exterior()).chain(q.interiors()))
+        .flat_map(|ls| ls.coords())
+    {
+        let point = Point(*c);
+        let closest = lines
+            .iter()
+            .map(|l| Euclidean::distance(l, &point))
+            .fold(f64::INFINITY, f64::min);
+        assert!(p.contains(&point), "{c:?} lies outside the input");
+        assert!(
+            closest > distance - 1e-6,
+            "{c:?} lies {closest} from the boundary, closer than {distance}"
+        );
+    }
+}
+
+fn assert_matches_reference(ring: &[(f64, f64)], distance: f64) {
+    let p = polygon(ring);
+    let offset = geo_buf::buffer_polygon(&p, -distance);
+    let expected = reference_offset(ring, distance);
+    let sym = symmetric_difference(&offset, &expected);
+    assert!(
+        sym < 1e-6,
+        "{ring:?} at {distance}: area {} but expected {}, symmetric difference {sym}",
+        offset.unsigned_area(),
+        expected.unsigned_area()
+    );
+    assert_within(&p, &offset, distance);
+}
+
+#[test]
+fn split_of_an_edge_that_has_moved_away() {
+    let ring = [
+        (14., 0.),
+        (13., 2.),
+        (8., 3.),
+        (10., 6.),
+        (5., 4.),
+        (9., 11.),
+        (5., 8.),
+        (2., 6.),
+        (2., 9.),
+        (0., 10.),
+        (-1., 8.),
+        (-3., 9.),
+        (-3., 6.),
+        (-5., 6.),
+        (-9., 8.),
+        (-9., 6.),
+        (-7., 3.),
+        (-13., 3.),
+        (-9., 1.),
+        (-12., -1.),
+        (-12., -3.),
+        (-6., -3.),
+        (-8., -6.),
+        (-4., -4.),
+        (-7., -9.),
+        (-5., -10.),
+        (-2., -6.),
+        (-2., -12.),
+        (1., -14.),
+        (2., -8.),
+        (4., -10.),
+        (3., -5.),
+        (7., -8.),
+        (9., -8.),
+        (8., -4.),
+        (5., -2.),
+        (7., -1.),
+    ];
+    let p = polygon(&ring);
+    // The deepest part of the polygon is a small triangle about the origin, still 3 away from every edge.
+    let offset = geo_buf::buffer_polygon(&p, -3.);
+    assert!(
+        (offset.unsigned_area() - 3.7775).abs() < 1e-3,
+        "area {}",
+        offset.unsigned_area()
+    );
+    assert!((geo_buf::buffer_polygon(&p, -1.).unsigned_area() - 149.43498).abs() < 1e-4);
+    for distance in [0.5, 1., 1.5, 2., 2.5, 3., 3.5, 4.] {
+        assert_matches_reference(&ring, distance);
+    }
+}
+
+#[test]
+fn split_next_to_collinear_edges() {
+    let spike = [
+        (4., 0.),
+        (3., 0.),
+        (6., 3.),
+        (6., 4.),
+        (10., 9.),
+        (7., 7.),
+        (6., 7.),
+        (5., 6.),
+        (4., 5.),
+        (0., 3.),
+        (-7., 2.),
+        (-13., -2.),
+        (-2., -3.),
+        (-5., -7.),
+        (-7., -11.),
+        (-4., -7.),
+        (1., -11.),
+        (2., -2.),
+        (8., -2.),
+    ];
+    for distance in [0.5, 1., 2., 3.] {
+        assert_matches_reference(&spike, distance);
+    }
+}
+
+#[test]
+fn splits_match_reference_in_general_position() {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut checked = 0;
+    while checked < 200 {
+        let n = 6 + (rng.next() % 40) as usize;
+        let ring = star(&mut rng, n, false);
+        let p = polygon(&ring);
+        if ring.len() < 4 || p.unsigned_area() < 1. || !is_simple(&p) {
+            continue;
+        }
+        for distance in [1., 2., 3.] {
+            assert_matches_reference(&ring, distance);
+        }
+        checked += 1;
+    }
+}
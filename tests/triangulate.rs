@@ -0,0 +1,167 @@
+//! Tests that the triangulations of non-convex polygons cover them exactly, without overlapping triangles.
+
+use geo::{Area, BooleanOps, BoundingRect, Contains, Intersects};
+use geo_types::{LineString, Polygon, Triangle};
+
+/// A small xorshift generator, so the random polygons are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// This function returns a random star-shaped counter-clockwise ring with integer coordinates, which makes many of them
+/// degenerate (collinear edges, vertices at the same distance from several edges).
+fn star(rng: &mut Rng, n: usize, radius: f64) -> LineString {
+    let mut angles: Vec<f64> = (0..n).map(|_| rng.unit() * std::f64::consts::TAU).collect();
+    angles.sort_by(f64::total_cmp);
+    let mut ring: Vec<(f64, f64)> = angles
+        .iter()
+        .map(|a| {
+            let r = radius * (0.15 + rng.unit() * 0.85);
+            ((r * a.cos()).round(), (r * a.sin()).round())
+        })
+        .collect();
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    LineString::from(ring)
+}
+
+fn is_simple(ls: &LineString) -> bool {
+    use geo::line_intersection::{line_intersection, LineIntersection};
+    let lines: Vec<_> = ls.lines().collect();
+    let n = lines.len();
+    (0..n).all(|i| {
+        (i + 1..n).all(|j| match line_intersection(lines[i], lines[j]) {
+            None => true,
+            Some(LineIntersection::SinglePoint { .. }) => j == i + 1 || (i == 0 && j == n - 1),
+            Some(LineIntersection::Collinear { .. }) => false,
+        })
+    })
+}
+
+/// This function asserts that the triangles of `p` are counter-clockwise, add up to its area and do not overlap.
+fn assert_covers(p: &Polygon, triangles: &[Triangle]) {
+    let area = p.unsigned_area();
+    let sum: f64 = triangles.iter().map(|t| t.signed_area()).sum();
+    assert!(triangles.iter().all(|t| t.signed_area() >= 0.), "{p:?}");
+    assert!(
+        (sum - area).abs() < 1e-6 * area,
+        "{p:?}: triangles add up to {sum} instead of {area}"
+    );
+    let polygons: Vec<Polygon> = triangles.iter().map(|t| t.to_polygon()).collect();
+    for (i, a) in polygons.iter().enumerate() {
+        for b in &polygons[i + 1..] {
+            if a.bounding_rect()
+                .unwrap()
+                .intersects(&b.bounding_rect().unwrap())
+            {
+                let overlap = a.intersection(b).unsigned_area();
+                assert!(
+                    overlap < 1e-9 * area,
+                    "{p:?}: {a:?} and {b:?} overlap by {overlap}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn triangulation_of_collinear_faces() {
+    // The face of the edge from (-6, 3) to (-2, -4) has two skeleton nodes on a line through (-6, 3).
+    let p = Polygon::new(
+        LineString::from(vec![
+            (12., 3.),
+            (8., 3.),
+            (3., 3.),
+            (10., 10.),
+            (1., 9.),
+            (-3., 13.),
+            (-3., 10.),
+            (-3., 7.),
+            (-4., 7.),
+            (-3., 5.),
+            (-6., 3.),
+            (-2., -4.),
+            (1., -11.),
+            (3., -8.),
+            (4., -5.),
+            (9., -9.),
+        ]),
+        vec![],
+    );
+    assert_covers(&p, &geo_buf::triangulate(&p));
+}
+
+#[test]
+fn triangulation_of_a_spike() {
+    // The skeleton of the spike at (-1, -5) has crossing arcs.
+    let p = Polygon::new(
+        LineString::from(vec![
+            (2., 1.),
+            (5., 5.),
+            (4., 5.),
+            (5., 7.),
+            (6., 11.),
+            (2., 9.),
+            (2., 10.),
+            (0., 11.),
+            (-1., 7.),
+            (-12., 6.),
+            (-10., -3.),
+            (-4., -4.),
+            (-6., -8.),
+            (-5., -10.),
+            (-3., -13.),
+            (-1., -5.),
+            (-1., -11.),
+            (8., -10.),
+            (2., -3.),
+            (7., -3.),
+            (13., -2.),
+        ]),
+        vec![],
+    );
+    assert_covers(&p, &geo_buf::triangulate(&p));
+}
+
+#[test]
+fn triangulation_of_random_polygons() {
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut checked = 0;
+    while checked < 300 {
+        let n = 6 + (rng.next() % 40) as usize;
+        let exterior = star(&mut rng, n, 14.);
+        let mut interiors = Vec::new();
+        if checked % 3 == 0 {
+            let mut hole = star(&mut rng, 8, 3.);
+            hole.0.reverse();
+            interiors.push(hole);
+        }
+        let p = Polygon::new(exterior, interiors);
+        let outer = Polygon::new(p.exterior().clone(), vec![]);
+        if p.unsigned_area() < 1.
+            || std::iter::once(p.exterior())
+                .chain(p.interiors())
+                .any(|ls| ls.0.len() < 5 || !is_simple(ls))
+            || p.interiors()
+                .iter()
+                .any(|h| h.intersects(p.exterior()) || !outer.contains(h))
+        {
+            continue;
+        }
+        assert_covers(&p, &geo_buf::triangulate(&p));
+        checked += 1;
+    }
+}
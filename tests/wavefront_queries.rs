@@ -0,0 +1,103 @@
+//! Tests that a skeleton queried at several distances in turn, which moves its last wavefront back and forth,
+//! gives the same buffers as a skeleton queried once.
+
+use geo::Area;
+use geo_buf::{BufferOptions, Side};
+use geo_types::{LineString, Polygon};
+
+fn assert_same_as_fresh(p: &Polygon, side: Side, distances: &[f64]) {
+    let skel = geo_buf::skeleton_of_polygon(p, side);
+    let options = BufferOptions::new();
+    for &distance in distances {
+        let fresh = skel.clone().buffer(distance, &options);
+        let reused = skel.buffer(distance, &options);
+        assert_eq!(
+            reused, fresh,
+            "at {distance} after the queries before it in {distances:?}"
+        );
+    }
+}
+
+#[test]
+fn queries_back_and_forth() {
+    let p = Polygon::new(
+        LineString::from(vec![
+            (0., 0.),
+            (10., 0.),
+            (10., 6.),
+            (6., 6.),
+            (6., 3.),
+            (4., 3.),
+            (4., 8.),
+            (12., 8.),
+            (12., 12.),
+            (0., 12.),
+        ]),
+        vec![LineString::from(vec![
+            (1., 9.),
+            (1., 11.),
+            (3., 11.),
+            (3., 9.),
+        ])],
+    );
+    let distances = [0.5, 1.75, 0.25, 1.9, 1.0, 0.0, 1.5, 0.75, 3.0, 0.1];
+    assert_same_as_fresh(&p, Side::Interior, &distances);
+    assert_same_as_fresh(&p, Side::Exterior, &distances);
+    // The deflation has collapsed by then, and still has after going back.
+    let skel = geo_buf::skeleton_of_polygon(&p, Side::Interior);
+    let options = BufferOptions::new();
+    assert!(skel.buffer(3., &options).0.is_empty());
+    assert!(skel.buffer(0.5, &options).unsigned_area() > 0.);
+    assert!(skel.buffer(3., &options).0.is_empty());
+}
+
+#[test]
+fn queries_from_several_threads() {
+    let p = Polygon::new(
+        LineString::from(vec![
+            (0., 0.),
+            (8., 0.),
+            (8., 2.),
+            (2., 2.),
+            (2., 8.),
+            (0., 8.),
+        ]),
+        vec![],
+    );
+    let skel = geo_buf::skeleton_of_polygon(&p, Side::Interior);
+    let options = BufferOptions::new();
+    std::thread::scope(|scope| {
+        for k in 0..4 {
+            let (skel, options) = (&skel, &options);
+            scope.spawn(move || {
+                for i in 0..40 {
+                    let distance = f64::from((i * 7 + k * 3) % 11) / 10.;
+                    let fresh = skel.clone().buffer(distance, options);
+                    assert_eq!(skel.buffer(distance, options), fresh, "at {distance}");
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn queries_across_snapshots() {
+    // A gear with hundreds of events, so that the wavefront moves past several cached snapshots.
+    let p = Polygon::new(
+        (0..240)
+            .map(|i| {
+                let a = f64::from(i) * std::f64::consts::TAU / 240.;
+                let r = if i % 2 == 0 {
+                    10.
+                } else {
+                    8.5 + f64::from(i % 7) / 10.
+                };
+                (r * a.cos(), r * a.sin())
+            })
+            .collect(),
+        vec![],
+    );
+    let distances = [0.2, 6.0, 0.9, 9.5, 3.3, 0.05, 8.0, 1.2, 4.4, 0.6];
+    assert_same_as_fresh(&p, Side::Interior, &distances);
+    assert_same_as_fresh(&p, Side::Exterior, &distances);
+}